@@ -0,0 +1,97 @@
+//! Benchmarks for large files
+//!
+//! These generate a 1 GB log file in the temporary directory, which
+//! is reused between runs, since generating it takes a while. It is
+//! opened just like a `FileCfg` would open it. Run them with `cargo
+//! bench -p duat-core`.
+#![feature(test)]
+extern crate test;
+
+use std::{
+    fs,
+    hint::black_box,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::LazyLock,
+};
+
+use duat_core::{file, text::Text};
+use test::Bencher;
+
+const SIZE: u64 = 1 << 30;
+/// The default of `FileCfg::large_file_threshold`
+const THRESHOLD: usize = 64 * 1024 * 1024;
+
+static LARGE_FILE: LazyLock<PathBuf> = LazyLock::new(|| {
+    let path = std::env::temp_dir().join("duat-large-file-bench.log");
+    if fs::metadata(&path).is_ok_and(|md| md.len() >= SIZE) {
+        return path;
+    }
+
+    let mut file = BufWriter::new(fs::File::create(&path).unwrap());
+    let mut written = 0;
+    for i in 0.. {
+        let line = format!(
+            "[{i:>10}] INFO server::handler: request handled in {}ms, status {}\n",
+            i % 997,
+            200 + i % 5
+        );
+        file.write_all(line.as_bytes()).unwrap();
+
+        written += line.len() as u64;
+        if written >= SIZE {
+            break;
+        }
+    }
+    file.flush().unwrap();
+
+    path
+});
+
+fn open_large_file() -> Text {
+    let (text, _) = file::open_large_file(&LARGE_FILE, THRESHOLD).unwrap();
+    text
+}
+
+#[bench]
+fn open(b: &mut Bencher) {
+    LazyLock::force(&LARGE_FILE);
+    b.iter(|| black_box(open_large_file()));
+}
+
+#[bench]
+fn point_at_line(b: &mut Bencher) {
+    let text = open_large_file();
+    let lines = text.len().line();
+
+    let mut l = 0;
+    b.iter(|| {
+        // Jumps around the whole file.
+        l = (l + 7_919_993) % lines;
+        black_box(text.point_at_line(l))
+    });
+}
+
+#[bench]
+fn scroll(b: &mut Bencher) {
+    let text = open_large_file();
+    let lines = text.len().line();
+
+    let mut l = lines / 2;
+    b.iter(|| {
+        l = (l + 1) % (lines - 100);
+        let start = text.point_at_line(l);
+        let end = text.point_at_line(l + 100);
+        black_box(text.strs(start..end).unwrap().chars().count())
+    });
+}
+
+#[bench]
+fn search_whole_file(b: &mut Bencher) {
+    let text = open_large_file();
+
+    b.iter(|| {
+        let mut matches = text.search_fwd("status 999", ..).unwrap();
+        black_box(matches.next())
+    });
+}
@@ -242,6 +242,7 @@ use std::{
     collections::HashMap,
     fmt::Display,
     ops::Range,
+    path::Path,
     sync::{
         Arc, LazyLock,
         atomic::{AtomicBool, Ordering},
@@ -253,11 +254,11 @@ use crossterm::style::Color;
 
 pub use self::{global::*, parameters::*};
 use crate::{
-    context::{self, sender},
+    context::{self, Handle, sender},
     data::{Pass, RwData},
-    file::File,
+    file::{self, Encoding, File, LineEnding},
     form::FormId,
-    mode,
+    mode, registers,
     text::{Text, txt},
     ui::{DuatEvent, Node, Ui, Widget},
 };
@@ -466,6 +467,83 @@ pub(crate) fn add_session_commands<U: Ui>() {
         Ok(None)
     });
 
+    add!("revert", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let file = handle.write(pa);
+
+        let Some(saved) = file.text().history().and_then(|h| h.saved_moment()) else {
+            return Err(txt!("There is no saved state to revert to").build());
+        };
+
+        if file.text().has_unsaved_changes() {
+            file.text_mut().move_to_moment(saved);
+            let name = file.name();
+            Ok(Some(
+                txt!("Reverted [a]{name}[] to its saved state").build(),
+            ))
+        } else {
+            Ok(Some(txt!("Nothing to revert").build()))
+        }
+    });
+
+    add!("reload-file", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        file::reload_from_disk(pa, &handle)?;
+
+        let name = handle.read(pa).name();
+        Ok(Some(txt!("Reloaded [a]{name}[] from disk").build()))
+    });
+
+    add!("recover", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        file::recover(pa, &handle)?;
+
+        let name = handle.read(pa).name();
+        Ok(Some(txt!("Recovered unsaved changes to [a]{name}").build()))
+    });
+
+    add!("discard-recovery", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        file::discard_recovery(pa, &handle)?;
+
+        let name = handle.read(pa).name();
+        Ok(Some(txt!("Discarded unsaved changes to [a]{name}").build()))
+    });
+
+    add!("toggle-read-only", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let file = handle.write(pa);
+
+        let is_read_only = !file.text().is_read_only();
+        file.text_mut().set_read_only(is_read_only);
+
+        let name = file.name();
+        if is_read_only {
+            Ok(Some(txt!("[a]{name}[] is now read-only").build()))
+        } else {
+            Ok(Some(txt!("[a]{name}[] is now editable").build()))
+        }
+    });
+
+    add!("set-line-ending", |pa, line_ending: LineEnding| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.write(pa).set_line_ending(line_ending);
+        Ok(Some(txt!("Set line ending to [a]{line_ending}").build()))
+    });
+
+    add!("set-encoding", |pa, encoding: Encoding| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.write(pa).set_encoding(encoding);
+        Ok(Some(txt!("Set encoding to [a]{encoding}").build()))
+    });
+
+    add!("set-bom", |pa, has_bom: bool| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.write(pa).set_bom(has_bom);
+        let with = if has_bom { "with" } else { "without" };
+        Ok(Some(txt!("Will write {with} a byte order mark").build()))
+    });
+
     add!(["reload"], |_pa, flags: Flags| {
         static IS_UPDATING: AtomicBool = AtomicBool::new(false);
 
@@ -551,19 +629,7 @@ pub(crate) fn add_session_commands<U: Ui>() {
     });
 
     add!(["edit", "e"], |pa, path: ValidFile<U>| {
-        let name = if let Ok(path) = path.strip_prefix(context::cur_dir()) {
-            path.to_string_lossy().to_string()
-        } else {
-            path.to_string_lossy().to_string()
-        };
-
-        if context::windows::<U>().file_entry(pa, &name).is_err() {
-            sender().send(DuatEvent::OpenFile(name.clone())).unwrap();
-            return Ok(Some(txt!("Opened [a]{name}").build()));
-        }
-
-        mode::reset_to_file::<U>(name.clone(), true);
-        Ok(Some(txt!("Switched to [a]{name}").build()))
+        edit_file::<U>(pa, &path)
     });
 
     add!(["open", "o"], |pa, path: ValidFile<U>| {
@@ -683,15 +749,217 @@ pub(crate) fn add_session_commands<U: Ui>() {
             Ok(Some(txt!("Set [a]{name}[] to a new Form").build()))
         }
     );
+
+    add!("align-carets", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.edit_iter(pa, |cursors| cursors.align_carets());
+        handle.text_mut(pa).new_moment();
+        Ok(None)
+    });
+
+    add!("rotate-contents", |pa, by: Option<i32>| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.edit_iter(pa, |cursors| cursors.rotate_contents(by.unwrap_or(1)));
+        handle.text_mut(pa).new_moment();
+        Ok(None)
+    });
+
+    add!("split-selections", |pa, pat: Remainder| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle
+            .edit_iter(pa, |cursors| cursors.split_on(&pat))
+            .map_err(|_| txt!("[a]{pat}[] is not a valid regex").build())?;
+
+        let len = handle.selections(pa).len();
+        Ok(Some(txt!("Split into [a]{len}[] selections").build()))
+    });
+
+    add!("keep-matching", |pa, pat: Remainder| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle
+            .edit_iter(pa, |cursors| cursors.keep_matching(&pat))
+            .map_err(|_| txt!("[a]{pat}[] is not a valid regex").build())?;
+
+        let len = handle.selections(pa).len();
+        Ok(Some(txt!("Kept [a]{len}[] selections").build()))
+    });
+
+    add!("remove-matching", |pa, pat: Remainder| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let prev_len = handle.selections(pa).len();
+        handle
+            .edit_iter(pa, |cursors| cursors.remove_matching(&pat))
+            .map_err(|_| txt!("[a]{pat}[] is not a valid regex").build())?;
+
+        let removed = prev_len - handle.selections(pa).len();
+        Ok(Some(txt!("Removed [a]{removed}[] selections").build()))
+    });
+
+    add!("merge-selections", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.edit_iter(pa, |cursors| cursors.merge_overlapping());
+        Ok(None)
+    });
+
+    add!("registers", |_pa, name: Option<char>| {
+        let Some(name) = name else {
+            let names = registers::names();
+            if names.is_empty() {
+                return Ok(Some(txt!("All registers are empty").build()));
+            }
+
+            let mut builder = Text::builder();
+            builder.push(txt!("Registers:"));
+            for name in names {
+                let values = registers::get(name).unwrap_or_default();
+                let first = values.first().and_then(|v| v.lines().next()).unwrap_or("");
+                let count = values.len();
+                builder.push(txt!("\n  [a]{name}[]: {first} ({count} values)"));
+            }
+            return Ok(Some(builder.build()));
+        };
+
+        match registers::get(name) {
+            Some(values) => {
+                let mut builder = Text::builder();
+                builder.push(txt!("Register [a]{name}[]:"));
+                for (i, value) in values.iter().enumerate() {
+                    builder.push(txt!("\n  [a]{i}[]: {value}"));
+                }
+                Ok(Some(builder.build()))
+            }
+            None => Err(txt!("Register [a]{name}[] is empty").build()),
+        }
+    });
+
+    add!("replay-macro", |_pa, name: char, count: Option<usize>| {
+        if registers::get(name).is_none() {
+            return Err(txt!("Register [a]{name}[] is empty").build());
+        }
+
+        mode::replay_macro::<U>(name, count.unwrap_or(1));
+        Ok(None)
+    });
+
+    add!("save-mark", |pa, name: char| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.text_mut(pa).save_mark(name);
+        Ok(Some(txt!("Saved selections to mark [a]{name}").build()))
+    });
+
+    add!("restore-mark", |pa, name: char| {
+        let handle = context::fixed_file::<U>(pa)?;
+        if handle.text_mut(pa).restore_mark(name) {
+            Ok(None)
+        } else {
+            Err(txt!("There is no mark [a]{name}").build())
+        }
+    });
+
+    add!("union-mark", |pa, name: char| {
+        let handle = context::fixed_file::<U>(pa)?;
+        if handle.text_mut(pa).union_with_mark(name) {
+            Ok(None)
+        } else {
+            Err(txt!("There is no mark [a]{name}").build())
+        }
+    });
+
+    add!("intersect-mark", |pa, name: char| {
+        let handle = context::fixed_file::<U>(pa)?;
+        if handle.text(pa).marks().get(name).is_none() {
+            Err(txt!("There is no mark [a]{name}").build())
+        } else if handle.text_mut(pa).intersect_with_mark(name) {
+            Ok(None)
+        } else {
+            Err(txt!("No selections intersect with mark [a]{name}").build())
+        }
+    });
+
+    add!("fold", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let text = handle.text_mut(pa);
+        let Some(main) = text.selections().get_main().cloned() else {
+            return Err(txt!("There are no selections to fold").build());
+        };
+
+        // Multi line selections are folded directly.
+        let folded = if main.anchor().is_some_and(|a| a.line() != main.line()) {
+            let range = main.range(text);
+            text.fold(range)
+        } else {
+            text.fold_at(main.caret())
+        };
+
+        if folded {
+            move_out_of_folds(pa, &handle);
+            Ok(None)
+        } else {
+            Err(txt!("There is nothing to fold here").build())
+        }
+    });
+
+    add!("unfold", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let caret = handle.selections(pa).get_main().map(|main| main.caret());
+        if caret.is_some_and(|caret| handle.text_mut(pa).unfold_at(caret)) {
+            Ok(None)
+        } else {
+            Err(txt!("There is no fold here").build())
+        }
+    });
+
+    add!("toggle-fold", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let Some(caret) = handle.selections(pa).get_main().map(|main| main.caret()) else {
+            return Err(txt!("There are no selections to fold").build());
+        };
+
+        let text = handle.text_mut(pa);
+        if text.unfold_at(caret) {
+            Ok(None)
+        } else if text.fold_at(caret) {
+            move_out_of_folds(pa, &handle);
+            Ok(None)
+        } else {
+            Err(txt!("There is nothing to fold here").build())
+        }
+    });
+
+    add!("fold-all", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        let folded = handle.text_mut(pa).fold_all();
+        if folded == 0 {
+            return Err(txt!("There is nothing to fold").build());
+        }
+
+        move_out_of_folds(pa, &handle);
+        let ranges = if folded == 1 { "range" } else { "ranges" };
+        Ok(Some(txt!("Folded [a]{folded}[] {ranges}").build()))
+    });
+
+    add!("unfold-all", |pa| {
+        let handle = context::fixed_file::<U>(pa)?;
+        handle.text_mut(pa).unfold_all();
+        Ok(None)
+    });
+
+    add!("next-diagnostic", |pa| go_to_diagnostic::<U>(pa, true));
+
+    add!("prev-diagnostic", |pa| go_to_diagnostic::<U>(pa, false));
 }
 
 mod global {
-    use std::ops::Range;
+    use std::{ops::Range, path::Path};
 
     use super::{CheckerFn, CmdFn, CmdResult, Commands};
     use crate::{
-        context, data::Pass, form::FormId, main_thread_only::MainThreadOnly, text::Text,
-        ui::DuatEvent,
+        context,
+        data::Pass,
+        form::FormId,
+        main_thread_only::MainThreadOnly,
+        text::Text,
+        ui::{DuatEvent, Ui},
     };
 
     static COMMANDS: MainThreadOnly<Commands> = MainThreadOnly::new(Commands::new());
@@ -815,6 +1083,19 @@ mod global {
         call(pa, format!("edit {file}"))
     }
 
+    /// Switches to/opens a [`File`] with the given [`Path`]
+    ///
+    /// Unlike [`edit`], the path isn't parsed as an argument of a
+    /// command, so it can contain spaces and other special
+    /// characters.
+    ///
+    /// [`File`]: crate::file::File
+    pub fn edit_path<U: Ui>(pa: &mut Pass, path: impl AsRef<Path>) -> CmdResult {
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        super::edit_file::<U>(pa, &path)
+    }
+
     /// Switches to a [`File`] with the given name.
     ///
     /// If there is no file open with that name, does nothing. Use
@@ -1233,6 +1514,81 @@ pub type CheckerFn = fn(
     Option<(Range<usize>, Text)>,
 );
 
+/// Switches to/opens a [`File`], given its [`Path`]
+fn edit_file<U: Ui>(pa: &mut Pass, path: &Path) -> CmdResult {
+    let name = if let Ok(path) = path.strip_prefix(context::cur_dir()) {
+        path.to_string_lossy().to_string()
+    } else {
+        path.to_string_lossy().to_string()
+    };
+
+    if context::windows::<U>().file_entry(pa, &name).is_err() {
+        sender().send(DuatEvent::OpenFile(name.clone())).unwrap();
+        return Ok(Some(txt!("Opened [a]{name}").build()));
+    }
+
+    mode::reset_to_file::<U>(name.clone(), true);
+    Ok(Some(txt!("Switched to [a]{name}").build()))
+}
+
 fn get_name<U: Ui>(pa: &Pass) -> impl Fn((usize, usize, &Node<U>)) -> Option<String> {
     |(.., node)| node.read_as(pa).map(|f: &File<U>| f.name())
 }
+
+/// Moves the main caret to the next or previous [`Diagnostic`]
+///
+/// Wraps around the [`File`], and returns the message of the
+/// [`Diagnostic`].
+///
+/// [`Diagnostic`]: crate::text::Diagnostic
+fn go_to_diagnostic<U: Ui>(pa: &mut Pass, forward: bool) -> CmdResult {
+    let handle = context::fixed_file::<U>(pa)?;
+    let text = handle.text(pa);
+    let caret = text.selections().get_main().map_or(0, |main| main.byte());
+
+    let diags = text.diagnostics();
+    let diag = if forward {
+        diags.next_after(caret).or(diags.iter().next())
+    } else {
+        diags.prev_before(caret).or(diags.iter().next_back())
+    };
+    let Some(diag) = diag else {
+        return Err(txt!("There are no diagnostics").build());
+    };
+
+    let point = text.point_at_byte(diag.range().start);
+    let form = diag.severity().message_form_id();
+    let mut msg = txt!("{form}{}[]: {}", diag.severity(), diag.message());
+    if !diag.source().is_empty() {
+        msg.push(txt!(" ({})", diag.source()));
+    }
+    let msg = msg.build();
+
+    handle.edit_main(pa, |mut c| {
+        c.unset_anchor();
+        c.move_to(point);
+    });
+
+    Ok(Some(msg))
+}
+
+/// Moves the main caret to the first line of a fold, if it was hidden
+/// by it
+fn move_out_of_folds<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) {
+    let text = handle.text(pa);
+    let Some(line) = text.selections().get_main().map(|main| main.line()) else {
+        return;
+    };
+
+    let closed = text.closed_folds();
+    let hiding = closed
+        .iter()
+        .find(|lines| lines.start < line && line < lines.end);
+    if let Some(lines) = hiding {
+        let point = text.point_at_line(lines.start);
+        handle.edit_main(pa, |mut c| {
+            c.unset_anchor();
+            c.move_to(point);
+        });
+    }
+}
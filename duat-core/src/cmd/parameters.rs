@@ -13,6 +13,7 @@ use crossterm::style::Color;
 use crate::{
     context::{self, Handle},
     data::Pass,
+    file::{Encoding, LineEnding},
     form::{self, FormId},
     text::{Text, txt},
     ui::{Node, Ui, Widget},
@@ -381,6 +382,28 @@ impl<'a> Parameter<'a> for Color {
     }
 }
 
+impl Parameter<'_> for LineEnding {
+    type Returns = LineEnding;
+
+    fn new(_: &Pass, args: &mut Args) -> Result<(Self::Returns, Option<FormId>), Text> {
+        let arg = args.next()?;
+        arg.parse()
+            .map(|line_ending| (line_ending, None))
+            .map_err(|_| txt!("[a]{arg}[] is not a line ending, try [a]lf[] or [a]crlf").build())
+    }
+}
+
+impl Parameter<'_> for Encoding {
+    type Returns = Encoding;
+
+    fn new(_: &Pass, args: &mut Args) -> Result<(Self::Returns, Option<FormId>), Text> {
+        let arg = args.next()?;
+        arg.parse()
+            .map(|encoding| (encoding, None))
+            .map_err(|_| txt!("[a]{arg}[] is not a supported encoding").build())
+    }
+}
+
 /// Command [`Parameter`]: The name of a [`Form`] that has been [set]
 ///
 /// [set]: crate::form::set
@@ -768,6 +791,7 @@ parse_impl!(i128);
 parse_impl!(isize);
 parse_impl!(f32);
 parse_impl!(f64);
+parse_impl!(char);
 parse_impl!(std::path::PathBuf);
 
 macro parse_impl($t:ty) {
@@ -46,6 +46,9 @@ use crate::{
     text::{Text, txt},
 };
 
+/// The path used to store caches that aren't tied to any file
+const GLOBAL_PATH: &str = "global";
+
 /// Used in order to cache things
 pub struct Cache(PhantomData<()>);
 
@@ -147,6 +150,22 @@ impl Cache {
     }
 }
 
+/// Stores a cache that isn't tied to any file
+///
+/// This is useful for things that should be kept between sessions,
+/// like the history of a prompt. There is only one global cache per
+/// type, which can later be loaded by [`load_global`].
+pub fn store_global<C: Encode + 'static>(cache: C) -> Result<usize, Text> {
+    Cache::new().store(GLOBAL_PATH, cache)
+}
+
+/// Loads the cache stored by [`store_global`] for the given type
+///
+/// If it was never stored, returns the [`Default`] value.
+pub fn load_global<C: Decode<()> + Default + 'static>() -> Result<C, Text> {
+    Cache::new().load(GLOBAL_PATH)
+}
+
 fn cache_file<C: 'static>(path: PathBuf, truncate: bool) -> std::io::Result<File> {
     let mut hasher = DefaultHasher::new();
     path.hash(&mut hasher);
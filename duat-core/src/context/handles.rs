@@ -4,7 +4,7 @@
 //! [`RwData<W>`] conjoined to an [`Ui::Area`].
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     sync::{Arc, Mutex},
 };
 
@@ -12,6 +12,7 @@ use lender::Lender;
 
 use crate::{
     cfg::PrintCfg,
+    context,
     data::{Pass, RwData},
     mode::{Cursor, Cursors, Selection, Selections},
     text::{Point, Searcher, Text, TwoPoints},
@@ -304,20 +305,25 @@ impl<W: Widget<U> + ?Sized, U: Ui, S> Handle<W, U, S> {
             (selection, was_main, widget)
         }
 
+        let edit_denied = Cell::new(false);
         let (selection, was_main, widget) = get_parts(pa, &self.widget, n);
 
         // This is safe because of the &mut Pass argument
         let mut searcher = self.searcher.borrow_mut();
 
-        edit(Cursor::new(
+        let ret = edit(Cursor::new(
             selection,
             n,
             was_main,
             &mut *widget,
             &self.area,
             None,
+            &edit_denied,
             &mut searcher,
-        ))
+        ));
+
+        log_if_denied(&edit_denied);
+        ret
     }
 
     /// Edits the main [`Selection`] in the [`Text`]
@@ -402,7 +408,11 @@ impl<W: Widget<U> + ?Sized, U: Ui, S> Handle<W, U, S> {
         pa: &mut Pass,
         edit: impl FnOnce(Cursors<'_, W, U::Area, S>) -> Ret,
     ) -> Ret {
-        edit(self.get_iter(pa))
+        let edit_denied = Cell::new(false);
+        let ret = edit(self.get_iter(pa, &edit_denied));
+
+        log_if_denied(&edit_denied);
+        ret
     }
 
     /// A shortcut for iterating over all selections
@@ -420,16 +430,23 @@ impl<W: Widget<U> + ?Sized, U: Ui, S> Handle<W, U, S> {
     /// indentation that will inevitably come from using the
     /// equivalent long form call.
     pub fn edit_all(&self, pa: &mut Pass, edit: impl FnMut(Cursor<W, U::Area, S>)) {
-        self.get_iter(pa).for_each(edit);
+        let edit_denied = Cell::new(false);
+        self.get_iter(pa, &edit_denied).for_each(edit);
+
+        log_if_denied(&edit_denied);
     }
 
-    fn get_iter<'a>(&'a self, pa: &'a mut Pass) -> Cursors<'a, W, U::Area, S> {
+    fn get_iter<'a>(
+        &'a self,
+        pa: &'a mut Pass,
+        edit_denied: &'a Cell<bool>,
+    ) -> Cursors<'a, W, U::Area, S> {
         let widget = self.widget.write(pa);
         widget.text_mut().selections_mut().populate();
 
         let searcher = self.searcher.borrow_mut();
 
-        Cursors::new(0, widget, &self.area, searcher)
+        Cursors::new(0, widget, &self.area, edit_denied, searcher)
     }
 
     ////////// Area functions
@@ -679,3 +696,13 @@ impl<W: Widget<U> + ?Sized, U: Ui> Clone for Handle<W, U> {
 
 #[derive(Clone)]
 struct RelatedWidgets<U: Ui>(RwData<Vec<Handle<dyn Widget<U>, U>>>);
+
+/// Logs an error if a [`Cursor`] tried to edit a read-only [`Text`]
+///
+/// This is done once per call to an `edit_*` method, regardless of
+/// how many [`Cursor`]s tried to edit it.
+fn log_if_denied(edit_denied: &Cell<bool>) {
+    if edit_denied.get() {
+        context::error!("Tried to edit a read-only buffer");
+    }
+}
@@ -299,6 +299,9 @@ mod doc_state {
     pub fn cur_map_txt(pa: &Pass) -> DataMap<(Vec<KeyEvent>, bool), Text> {
         RwData::default().map(pa, |_| Text::new())
     }
+    pub fn search_txt(pa: &Pass) -> DataMap<(), Text> {
+        RwData::default().map(pa, |_| Text::new())
+    }
     pub fn last_key() -> RwData<String> { RwData::default() }
 }
 
@@ -408,6 +411,17 @@ mod doc_ui {
         }
         fn print_info(&self) -> Self::PrintInfo {}
         fn is_active(&self) -> bool { false }
+        fn contains_coord(&self, _: u32, _: u32) -> bool { false }
+        fn points_at_coord(
+            &self,
+            _: &Text,
+            _: u32,
+            _: u32,
+            _: PrintCfg,
+        ) -> Option<(Point, Option<Point>)> {
+            None
+        }
+        fn toggle_at_coord(&self, _: u32, _: u32) -> Option<crate::text::ToggleId> { None }
     }
 }
 
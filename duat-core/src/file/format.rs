@@ -0,0 +1,484 @@
+//! The format of a [`File`] on disk
+//!
+//! Internally, the [`Text`] of a [`File`] is always encoded in UTF-8,
+//! with `'\n'` line endings. However, files on disk may use `"\r\n"`
+//! line endings, start with a byte order mark, or be encoded in
+//! UTF-16 or Latin-1. The [`FileFormat`] is detected when opening a
+//! [`File`], and is used in order to write it back in the same
+//! format.
+//!
+//! [`File`]: super::File
+//! [`Text`]: crate::text::Text
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    str::FromStr,
+};
+
+/// The line ending used by a [`File`] on disk
+///
+/// [`File`]: super::File
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Unix style line endings, `'\n'`
+    #[default]
+    Lf,
+    /// Windows style line endings, `"\r\n"`
+    Crlf,
+}
+
+impl LineEnding {
+    /// The `str` that is written for each line ending
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LineEnding::Lf => "lf",
+            LineEnding::Crlf => "crlf",
+        })
+    }
+}
+
+impl FromStr for LineEnding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lf" | "unix" => Ok(LineEnding::Lf),
+            "crlf" | "dos" | "windows" => Ok(LineEnding::Crlf),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The encoding of a [`File`] on disk
+///
+/// [`File`]: super::File
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, the encoding used by Duat internally
+    #[default]
+    Utf8,
+    /// UTF-16, little endian
+    Utf16Le,
+    /// UTF-16, big endian
+    Utf16Be,
+    /// Latin-1 (ISO-8859-1)
+    ///
+    /// This is what Duat falls back to when a file is neither UTF-8
+    /// nor UTF-16, since every byte is a valid Latin-1 character.
+    Latin1,
+}
+
+impl Encoding {
+    /// The byte order mark of this [`Encoding`]
+    ///
+    /// Returns an empty slice for [`Encoding::Latin1`], since it has
+    /// no byte order mark.
+    pub const fn bom(&self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xef, 0xbb, 0xbf],
+            Encoding::Utf16Le => &[0xff, 0xfe],
+            Encoding::Utf16Be => &[0xfe, 0xff],
+            Encoding::Latin1 => &[],
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Latin1 => "latin-1",
+        })
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "utf-16le" | "utf16le" | "utf-16" | "utf16" => Ok(Encoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(Encoding::Latin1),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The format of a [`File`] on disk
+///
+/// This is detected when opening a [`File`], and is used in order to
+/// write it back exactly as it was. Do note that, if a file has mixed
+/// line endings, they will all be written as the [`LineEnding`] of
+/// the first line.
+///
+/// ```rust
+/// use duat_core::file::{Encoding, FileFormat, LineEnding};
+/// let (format, string) = FileFormat::decode(b"\xef\xbb\xbfhello\r\nworld\r\n");
+/// assert_eq!(format.encoding, Encoding::Utf8);
+/// assert_eq!(format.line_ending, LineEnding::Crlf);
+/// assert!(format.has_bom);
+/// assert_eq!(string, "hello\nworld\n");
+///
+/// let bytes = format.encode(&string).unwrap();
+/// assert_eq!(bytes, b"\xef\xbb\xbfhello\r\nworld\r\n");
+/// ```
+///
+/// [`File`]: super::File
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileFormat {
+    /// The [`LineEnding`] of the file
+    pub line_ending: LineEnding,
+    /// The [`Encoding`] of the file
+    pub encoding: Encoding,
+    /// Wether the file starts with a byte order mark
+    pub has_bom: bool,
+}
+
+impl FileFormat {
+    /// Detects the [`FileFormat`] of some bytes, returning it and
+    /// the decoded [`String`]
+    ///
+    /// The returned [`String`] will have no byte order mark, and its
+    /// `"\r\n"` line endings will be replaced by `'\n'`, if the
+    /// [`LineEnding`] is [`LineEnding::Crlf`].
+    pub fn decode(raw: &[u8]) -> (Self, String) {
+        let (encoding, has_bom) = if let Some(encoding) =
+            [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+                .into_iter()
+                .find(|encoding| raw.starts_with(encoding.bom()))
+        {
+            (encoding, true)
+        } else if let Some(encoding) = guess_utf16(raw) {
+            // UTF-16 encoded ASCII is also valid UTF-8, so this goes first.
+            (encoding, false)
+        } else if std::str::from_utf8(raw).is_ok() {
+            (Encoding::Utf8, false)
+        } else {
+            (Encoding::Latin1, false)
+        };
+
+        let raw = if has_bom {
+            &raw[encoding.bom().len()..]
+        } else {
+            raw
+        };
+
+        let string = match encoding {
+            Encoding::Utf8 => String::from_utf8_lossy(raw).into_owned(),
+            Encoding::Utf16Le => decode_utf16(raw, u16::from_le_bytes),
+            Encoding::Utf16Be => decode_utf16(raw, u16::from_be_bytes),
+            Encoding::Latin1 => raw.iter().map(|&b| b as char).collect(),
+        };
+
+        let line_ending = match string.find('\n') {
+            Some(i) if string[..i].ends_with('\r') => LineEnding::Crlf,
+            _ => LineEnding::Lf,
+        };
+
+        let string = match line_ending {
+            LineEnding::Lf => string,
+            LineEnding::Crlf => string.replace("\r\n", "\n"),
+        };
+
+        (Self { line_ending, encoding, has_bom }, string)
+    }
+
+    /// Like [`FileFormat::decode`], but takes ownership of the bytes
+    ///
+    /// If they are UTF-8 with `'\n'` line endings and no byte order
+    /// mark, which is the most common case, no copying is done. This
+    /// is meant for very large files.
+    pub fn decode_vec(raw: Vec<u8>) -> (Self, String) {
+        if raw.starts_with(Encoding::Utf8.bom()) || guess_utf16(&raw).is_some() {
+            return Self::decode(&raw);
+        }
+
+        match String::from_utf8(raw) {
+            Ok(string) if !string.contains("\r\n") => (Self::default(), string),
+            Ok(string) => Self::decode(string.as_bytes()),
+            Err(err) => Self::decode(err.as_bytes()),
+        }
+    }
+
+    /// Wether some UTF-8 bytes would be decoded as they are
+    ///
+    /// That is, wether they have `'\n'` line endings and no byte
+    /// order mark, in which case they can be used without being
+    /// copied. Checking that they are valid UTF-8 is left to the
+    /// caller.
+    pub(super) fn is_verbatim(raw: &[u8]) -> bool {
+        let is_crlf = raw
+            .iter()
+            .position(|b| *b == b'\n')
+            .is_some_and(|i| i > 0 && raw[i - 1] == b'\r');
+
+        !raw.starts_with(Encoding::Utf8.bom()) && guess_utf16(raw).is_none() && !is_crlf
+    }
+
+    /// Encodes a `str` in this [`FileFormat`]
+    ///
+    /// This will also add the byte order mark, if there should be
+    /// one. Returns an [`Err`] if the `str` has characters that
+    /// can't be represented in the [`Encoding`].
+    pub fn encode(&self, str: &str) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if self.has_bom {
+            bytes.extend_from_slice(self.encoding.bom());
+        }
+        bytes.extend(self.encode_inner(str)?);
+        Ok(bytes)
+    }
+
+    /// Wraps a writer, so whatever is written to it is in this
+    /// [`FileFormat`]
+    ///
+    /// What is written should be UTF-8, with `'\n'` line endings,
+    /// as is the case with [`Text::write_to`].
+    ///
+    /// [`Text::write_to`]: crate::text::Text::write_to
+    pub fn writer<W: Write>(&self, writer: W) -> impl Write {
+        FormatWriter {
+            format: *self,
+            writer,
+            wrote_bom: !self.has_bom,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Encodes a `str`, without a byte order mark
+    fn encode_inner(&self, str: &str) -> io::Result<Vec<u8>> {
+        let str = match self.line_ending {
+            LineEnding::Lf => std::borrow::Cow::Borrowed(str),
+            LineEnding::Crlf => std::borrow::Cow::Owned(str.replace('\n', "\r\n")),
+        };
+
+        match self.encoding {
+            Encoding::Utf8 => Ok(str.as_bytes().to_vec()),
+            Encoding::Utf16Le => Ok(str.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Encoding::Utf16Be => Ok(str.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            Encoding::Latin1 => str
+                .chars()
+                .map(|char| {
+                    u8::try_from(char).map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("{char:?} can't be encoded in Latin-1"),
+                        )
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Display for FileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bom = if self.has_bom { "+bom" } else { "" };
+        write!(f, "{}{bom} {}", self.encoding, self.line_ending)
+    }
+}
+
+/// A writer that transcodes UTF-8 into a [`FileFormat`]
+struct FormatWriter<W: Write> {
+    format: FileFormat,
+    writer: W,
+    wrote_bom: bool,
+    /// Bytes of an incomplete [`char`] from the last write
+    partial: Vec<u8>,
+}
+
+impl<W: Write> Write for FormatWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.wrote_bom {
+            self.writer.write_all(self.format.encoding.bom())?;
+            self.wrote_bom = true;
+        }
+
+        self.partial.extend_from_slice(buf);
+        let valid_up_to = match std::str::from_utf8(&self.partial) {
+            Ok(str) => str.len(),
+            // The rest could be completed by the next write.
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+
+        let str = std::str::from_utf8(&self.partial[..valid_up_to]).unwrap();
+        self.writer.write_all(&self.format.encode_inner(str)?)?;
+        self.partial.drain(..valid_up_to);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Guesses if a file is UTF-16 without a byte order mark
+///
+/// This is done by looking at the distribution of null bytes, which
+/// are very common in UTF-16 encoded ASCII text.
+fn guess_utf16(raw: &[u8]) -> Option<Encoding> {
+    if raw.len() < 2 || raw.len() % 2 != 0 {
+        return None;
+    }
+
+    let sample = &raw[..raw.len().min(1024)];
+    let [even, odd] = sample.chunks_exact(2).fold([0, 0], |[even, odd], pair| {
+        [
+            even + (pair[0] == 0) as usize,
+            odd + (pair[1] == 0) as usize,
+        ]
+    });
+
+    let pairs = sample.len() / 2;
+    if odd > pairs / 2 && even == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even > pairs / 2 && odd == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Decodes UTF-16, replacing invalid sequences
+fn decode_utf16(raw: &[u8], to_u16: fn([u8; 2]) -> u16) -> String {
+    let units = raw.chunks_exact(2).map(|pair| to_u16([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|char| char.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::{Encoding, FileFormat, LineEnding};
+
+    /// Decodes and encodes the bytes, checking the round trip
+    fn round_trip(raw: &[u8]) -> (FileFormat, String) {
+        let (format, string) = FileFormat::decode(raw);
+        assert_eq!(format.encode(&string).unwrap(), raw);
+        (format, string)
+    }
+
+    fn utf16(str: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        str.encode_utf16().flat_map(to_bytes).collect()
+    }
+
+    #[test]
+    fn crlf_round_trips() {
+        let (format, string) = round_trip(b"hello\r\nworld\r\n");
+        assert_eq!(format.line_ending, LineEnding::Crlf);
+        assert_eq!(format.encoding, Encoding::Utf8);
+        assert!(!format.has_bom);
+        assert_eq!(string, "hello\nworld\n");
+
+        let (format, string) = round_trip(b"hello\nworld\n");
+        assert_eq!(format, FileFormat::default());
+        assert_eq!(string, "hello\nworld\n");
+    }
+
+    #[test]
+    fn bom_round_trips() {
+        let (format, string) = round_trip(b"\xef\xbb\xbfol\xc3\xa1\n");
+        assert_eq!(format.encoding, Encoding::Utf8);
+        assert!(format.has_bom);
+        assert_eq!(string, "olá\n");
+    }
+
+    #[test]
+    fn utf16_round_trips() {
+        let mut raw = Encoding::Utf16Le.bom().to_vec();
+        raw.extend(utf16("olá\r\nmundo\r\n", u16::to_le_bytes));
+        let (format, string) = round_trip(&raw);
+        assert_eq!(format.encoding, Encoding::Utf16Le);
+        assert_eq!(format.line_ending, LineEnding::Crlf);
+        assert!(format.has_bom);
+        assert_eq!(string, "olá\nmundo\n");
+
+        let mut raw = Encoding::Utf16Be.bom().to_vec();
+        raw.extend(utf16("olá\n", u16::to_be_bytes));
+        let (format, string) = round_trip(&raw);
+        assert_eq!(format.encoding, Encoding::Utf16Be);
+        assert_eq!(string, "olá\n");
+
+        // Without a byte order mark, it is guessed from the null bytes.
+        let (format, string) = round_trip(&utf16("hello\nworld\n", u16::to_le_bytes));
+        assert_eq!(format.encoding, Encoding::Utf16Le);
+        assert!(!format.has_bom);
+        assert_eq!(string, "hello\nworld\n");
+
+        let (format, _) = round_trip(&utf16("hello\nworld\n", u16::to_be_bytes));
+        assert_eq!(format.encoding, Encoding::Utf16Be);
+    }
+
+    #[test]
+    fn mixed_line_endings_follow_the_first_line() {
+        let (format, string) = FileFormat::decode(b"a\r\nb\nc\r\n");
+        assert_eq!(format.line_ending, LineEnding::Crlf);
+        assert_eq!(string, "a\nb\nc\n");
+        assert_eq!(format.encode(&string).unwrap(), b"a\r\nb\r\nc\r\n");
+
+        // With a '\n' on the first line, the '\r's are kept as is.
+        let (format, string) = round_trip(b"a\nb\r\nc\n");
+        assert_eq!(format.line_ending, LineEnding::Lf);
+        assert_eq!(string, "a\nb\r\nc\n");
+    }
+
+    #[test]
+    fn latin1_is_the_fallback() {
+        let (format, string) = round_trip(b"ol\xe1\n");
+        assert_eq!(format.encoding, Encoding::Latin1);
+        assert_eq!(string, "olá\n");
+
+        assert!(format.encode("日本\n").is_err());
+    }
+
+    #[test]
+    fn verbatim_bytes_decode_to_themselves() {
+        for raw in [&b"hello\nworld"[..], b"a\nb\r\nc\n", b"ol\xc3\xa1\n"] {
+            assert!(FileFormat::is_verbatim(raw));
+            let string = str::from_utf8(raw).unwrap().to_string();
+            assert_eq!(FileFormat::decode(raw), (FileFormat::default(), string));
+        }
+
+        let utf16 = utf16("hello\n", u16::to_le_bytes);
+        for raw in [&b"a\r\nb\n"[..], b"\xef\xbb\xbfa\n", &utf16] {
+            assert!(!FileFormat::is_verbatim(raw));
+        }
+    }
+
+    #[test]
+    fn writer_matches_encode() {
+        let format = FileFormat {
+            line_ending: LineEnding::Crlf,
+            encoding: Encoding::Utf16Le,
+            has_bom: true,
+        };
+        let string = "olá\nmundo\n";
+
+        let mut bytes = Vec::new();
+        let mut writer = format.writer(&mut bytes);
+        // Splitting the 'á' in the middle.
+        let (first, second) = string.as_bytes().split_at(3);
+        writer.write_all(first).unwrap();
+        writer.write_all(second).unwrap();
+        drop(writer);
+
+        assert_eq!(bytes, format.encode(string).unwrap());
+    }
+}
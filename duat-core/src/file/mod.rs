@@ -11,22 +11,42 @@
 //!
 //! [`LineNumbers`]: https://docs.rs/duat-utils/latest/duat_utils/widgets/struct.LineNumbers.html
 //! [`Cursor`]: crate::mode::Cursor
-use std::{fs, marker::PhantomData, path::PathBuf};
+use std::{
+    ffi::OsString,
+    fs,
+    io::{self, Write},
+    marker::PhantomData,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use memmap2::Mmap;
 
-use self::parser::InnerParsers;
-pub use self::parser::{FileParts, FileSnapshot, Parser, ParserBox, ParserCfg, Parsers};
+pub use self::{
+    format::{Encoding, FileFormat, LineEnding},
+    parser::{FileParts, FileSnapshot, Parser, ParserBox, ParserCfg, Parsers},
+};
+use self::{parser::InnerParsers, recovery::Recovery};
+pub(crate) use self::{
+    recovery::{discard as discard_recovery, recover},
+    watcher::FileWatcher,
+};
 use crate::{
     cfg::{NewLine, PrintCfg, ScrollOff, TabStops, WordChars, WrapMethod},
     context::{self, Cache, Handle},
     data::Pass,
     form::Painter,
-    hook::{self, FileWritten},
-    mode::{Selection, Selections},
+    hook::{self, FileWritten, OnFileReload},
+    mode::{self, MouseEvent, Selection, Selections},
     text::{Bytes, Text, txt},
     ui::{Area, BuildInfo, PushSpecs, Ui, Widget, WidgetCfg},
 };
 
+mod format;
 mod parser;
+mod recovery;
+mod watcher;
 
 /// The configuration for a new [`File`]
 #[derive(Default)]
@@ -34,6 +54,10 @@ mod parser;
 pub struct FileCfg<U: Ui> {
     text_op: TextOp,
     print_cfg: PrintCfg,
+    format: FileFormat,
+    keep_backups: bool,
+    recovery_interval: Option<Duration>,
+    large_file_threshold: usize,
     add_parsers: Option<Box<dyn FnOnce(&mut File<U>)>>,
 }
 
@@ -43,6 +67,10 @@ impl<U: Ui> FileCfg<U> {
         FileCfg {
             text_op: TextOp::NewBuffer,
             print_cfg: PrintCfg::default_for_input(),
+            format: FileFormat::default(),
+            keep_backups: false,
+            recovery_interval: Some(Duration::from_secs(5)),
+            large_file_threshold: 64 * 1024 * 1024,
             add_parsers: None,
         }
     }
@@ -171,6 +199,74 @@ impl<U: Ui> FileCfg<U> {
         self
     }
 
+    ////////// FileFormat functions
+
+    /// Sets the [`LineEnding`] of new files
+    ///
+    /// Files that already exist will have their [`LineEnding`]
+    /// detected when opened.
+    pub const fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.format.line_ending = line_ending;
+        self
+    }
+
+    /// Sets the [`Encoding`] of new files
+    ///
+    /// Files that already exist will have their [`Encoding`] detected
+    /// when opened.
+    pub const fn encoding(mut self, encoding: Encoding) -> Self {
+        self.format.encoding = encoding;
+        self
+    }
+
+    /// Wether new files should start with a byte order mark
+    pub const fn with_bom(mut self, has_bom: bool) -> Self {
+        self.format.has_bom = has_bom;
+        self
+    }
+
+    ////////// Writing functions
+
+    /// Keeps a backup of the previous version of the file on writes
+    ///
+    /// The backup will be placed in the same directory, with a `~`
+    /// appended to its name, like `file.rs~`.
+    pub const fn keep_backups(mut self, value: bool) -> Self {
+        self.keep_backups = value;
+        self
+    }
+
+    /// How often to store unsaved changes, for crash recovery
+    ///
+    /// If Duat crashes, these changes can be recovered by calling
+    /// `recover` on the next opening of the file. By default, this
+    /// is done every 5 seconds. Setting it to [`None`] disables crash
+    /// recovery.
+    pub const fn recovery_interval(mut self, interval: Option<Duration>) -> Self {
+        self.recovery_interval = interval;
+        self
+    }
+
+    ////////// Large file functions
+
+    /// The size, in bytes, above which files are opened as large
+    /// files
+    ///
+    /// In order to keep large files (like multi gigabyte logs)
+    /// responsive, they are opened without a [`History`], so changes
+    /// can't be undone, and no [`Parser`]s are added to them. They
+    /// can still be searched, scrolled and edited normally.
+    ///
+    /// If a large file is UTF-8 with `'\n'` line endings, it is
+    /// mapped into memory instead of being read, and is only copied
+    /// once it is edited. By default, this is 64 MiB.
+    ///
+    /// [`History`]: crate::text::History
+    pub const fn large_file_threshold(mut self, bytes: usize) -> Self {
+        self.large_file_threshold = bytes;
+        self
+    }
+
     ////////// Path functions
 
     /// The path that the [`File`] will open with, if it was set
@@ -193,9 +289,11 @@ impl<U: Ui> FileCfg<U> {
         bytes: Bytes,
         pk: PathKind,
         has_unsaved_changes: bool,
+        format: FileFormat,
     ) -> Self {
         Self {
             text_op: TextOp::TakeBuf(bytes, pk, has_unsaved_changes),
+            format,
             ..self
         }
     }
@@ -205,8 +303,23 @@ impl<U: Ui> WidgetCfg<U> for FileCfg<U> {
     type Widget = File<U>;
 
     fn build(self, _: &mut Pass, _: BuildInfo<U>) -> (Self::Widget, PushSpecs) {
-        let (text, path) = match self.text_op {
+        let mut format = self.format;
+        let mut recovery = None;
+        let mut disk_hash = None;
+        let mut is_large = false;
+        let (mut text, path) = match self.text_op {
             TextOp::NewBuffer => (Text::new_with_history(), PathKind::new_unset()),
+            TextOp::TakeBuf(bytes, pk, has_unsaved_changes)
+                if bytes.len().byte() > self.large_file_threshold =>
+            {
+                is_large = true;
+                let selections = Selections::new(Selection::default());
+                let text = Text::from_bytes(bytes, selections, false);
+                if has_unsaved_changes {
+                    text.declare_unsaved();
+                }
+                (text, pk)
+            }
             TextOp::TakeBuf(bytes, pk, has_unsaved_changes) => match &pk {
                 PathKind::SetExists(path) | PathKind::SetAbsent(path) => {
                     let selections = {
@@ -214,6 +327,11 @@ impl<U: Ui> WidgetCfg<U> for FileCfg<U> {
                         Selections::new(cursor)
                     };
                     let text = Text::from_file(bytes, selections, path, has_unsaved_changes);
+                    disk_hash = if !has_unsaved_changes {
+                        Some(text.content_hash())
+                    } else {
+                        hash_of_disk(path)
+                    };
                     (text, pk)
                 }
                 PathKind::NotSet(_) => (
@@ -224,13 +342,30 @@ impl<U: Ui> WidgetCfg<U> for FileCfg<U> {
             TextOp::OpenPath(path) => {
                 let canon_path = path.canonicalize();
                 if let Ok(path) = &canon_path
-                    && let Ok(file) = std::fs::read_to_string(path)
+                    && let Some((text, detected)) = open_large_file(path, self.large_file_threshold)
                 {
-                    let selections = {
-                        let cursor = Cache::new().load(path).unwrap_or_default();
-                        Selections::new(cursor)
+                    (format, is_large) = (detected, true);
+                    (text, PathKind::SetExists(path.clone()))
+                } else if let Ok(path) = &canon_path
+                    && let Ok(raw) = std::fs::read(path)
+                {
+                    let (detected, file) = FileFormat::decode_vec(raw);
+                    format = detected;
+                    is_large = file.len() > self.large_file_threshold;
+
+                    let text = if is_large {
+                        let selections = Selections::new(Selection::default());
+                        Text::from_bytes(Bytes::new(&file), selections, false)
+                    } else {
+                        let selections = {
+                            let cursor = Cache::new().load(path).unwrap_or_default();
+                            Selections::new(cursor)
+                        };
+                        let text = Text::from_file(Bytes::new(&file), selections, path, false);
+                        recovery = Recovery::load(path, &text);
+                        disk_hash = Some(text.content_hash());
+                        text
                     };
-                    let text = Text::from_file(Bytes::new(&file), selections, path, false);
                     (text, PathKind::SetExists(path.clone()))
                 } else if canon_path.is_err()
                     && let Ok(mut canon_path) = path.with_file_name(".").canonicalize()
@@ -243,9 +378,26 @@ impl<U: Ui> WidgetCfg<U> for FileCfg<U> {
             }
         };
 
+        // Opening for appending doesn't change the file.
+        if let PathKind::SetExists(path) = &path
+            && fs::OpenOptions::new().append(true).open(path).is_err()
+        {
+            text.set_read_only(true);
+        }
+
         let mut file = File {
             path,
             text,
+            format,
+            format_changed: false,
+            keep_backups: self.keep_backups,
+            recovery_interval: self.recovery_interval,
+            last_recovery: Instant::now(),
+            // There could be a stale journal from a previous session.
+            has_journal: true,
+            recovery,
+            disk_hash,
+            is_large,
             cfg: self.print_cfg,
             printed_lines: (0..40).map(|i| (i, i == 1)).collect(),
             parsers: InnerParsers::default(),
@@ -253,10 +405,20 @@ impl<U: Ui> WidgetCfg<U> for FileCfg<U> {
             _ghost: PhantomData,
         };
 
-        if let Some(add_parsers) = self.add_parsers {
+        if let Some(add_parsers) = self.add_parsers
+            && !file.is_large
+        {
             add_parsers(&mut file);
         }
 
+        if file.recovery.is_some() {
+            let name = file.name();
+            context::warn!(
+                "[a]{name}[] has unsaved changes from a previous session, use [a]recover[] or \
+                 [a]discard-recovery[]"
+            );
+        }
+
         // The PushSpecs don't matter
         (file, PushSpecs::above())
     }
@@ -267,6 +429,10 @@ impl<U: Ui> Clone for FileCfg<U> {
         Self {
             text_op: self.text_op.clone(),
             print_cfg: self.print_cfg,
+            format: self.format,
+            keep_backups: self.keep_backups,
+            recovery_interval: self.recovery_interval,
+            large_file_threshold: self.large_file_threshold,
             add_parsers: None,
         }
     }
@@ -276,6 +442,16 @@ impl<U: Ui> Clone for FileCfg<U> {
 pub struct File<U: Ui> {
     path: PathKind,
     text: Text,
+    format: FileFormat,
+    format_changed: bool,
+    keep_backups: bool,
+    recovery_interval: Option<Duration>,
+    last_recovery: Instant,
+    has_journal: bool,
+    recovery: Option<Recovery>,
+    /// A hash of the contents on disk, which [`Recovery`]s apply to
+    disk_hash: Option<u64>,
+    is_large: bool,
     printed_lines: Vec<(usize, bool)>,
     parsers: InnerParsers<U>,
     /// The [`PrintCfg`] of this [`File`]
@@ -288,6 +464,12 @@ impl<U: Ui> File<U> {
     ////////// Writing the File
 
     /// Writes the file to the current [`PathBuf`], if one was set
+    ///
+    /// The file is written atomically, that is, it is first written
+    /// to a temporary file in the same directory, which then replaces
+    /// the original. This means that a crash or a full disk won't
+    /// leave you with a truncated file. Symlinks are followed, and
+    /// the permissions and ownership of the file are preserved.
     pub fn save(&mut self) -> Result<Option<usize>, Text> {
         self.save_quit(false)
     }
@@ -295,11 +477,23 @@ impl<U: Ui> File<U> {
     pub(crate) fn save_quit(&mut self, quit: bool) -> Result<Option<usize>, Text> {
         if let PathKind::SetExists(path) | PathKind::SetAbsent(path) = &self.path {
             let path = path.clone();
-            if self.text.has_unsaved_changes() {
-                let bytes = self
-                    .text
-                    .write_to(std::io::BufWriter::new(fs::File::create(&path)?))
-                    .inspect(|_| self.path = PathKind::SetExists(path.clone()))?;
+            if self.text.has_unsaved_changes() || self.format_changed {
+                // So the saved state is reachable in the History.
+                self.text.finish_moment();
+                let (bytes, encoded) = self.encode().map_err(|err| write_error(&path, err))?;
+                write_atomically(&path, self.keep_backups, &encoded)
+                    .map_err(|err| write_error(&path, err))?;
+                self.text.declare_saved();
+                if let PathKind::SetAbsent(_) = &self.path {
+                    context::windows::<U>().declare_files_changed();
+                }
+                self.path = PathKind::SetExists(path.clone());
+                self.format_changed = false;
+
+                // Whatever was stored no longer applies to the file.
+                self.recovery = None;
+                self.disk_hash = Some(self.text.content_hash());
+                Recovery::delete(&path);
 
                 let path = path.to_string_lossy().to_string();
                 hook::queue(FileWritten((path, bytes, quit)));
@@ -315,40 +509,202 @@ impl<U: Ui> File<U> {
 
     /// Writes the file to the given [`Path`]
     ///
-    /// [`Path`]: std::path::Path
-    pub fn save_to(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<Option<usize>> {
+    /// Just like with [`File::save`], this is done atomically.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<Option<usize>, Text> {
         self.save_quit_to(path, false)
     }
 
     /// Writes the file to the given [`Path`]
     ///
-    /// [`Path`]: std::path::Path
+    /// The [`File`] is only declared as saved if the [`Path`] points
+    /// to its own file, since writing anywhere else leaves that one
+    /// unchanged.
     pub(crate) fn save_quit_to(
         &self,
-        path: impl AsRef<std::path::Path>,
+        path: impl AsRef<Path>,
         quit: bool,
-    ) -> std::io::Result<Option<usize>> {
-        if self.text.has_unsaved_changes() {
+    ) -> Result<Option<usize>, Text> {
+        if self.text.has_unsaved_changes() || self.format_changed {
             let path = path.as_ref();
-            let res = self
-                .text
-                .write_to(std::io::BufWriter::new(fs::File::create(path)?))
-                .map(Some);
-
-            if let Ok(Some(bytes)) = res.as_ref() {
-                hook::queue(FileWritten((
-                    path.to_string_lossy().to_string(),
-                    *bytes,
-                    quit,
-                )));
+            let (bytes, encoded) = self.encode().map_err(|err| write_error(path, err))?;
+            write_atomically(path, self.keep_backups, &encoded)
+                .map_err(|err| write_error(path, err))?;
+
+            let is_own_file = self.path_set().is_some_and(|own_path| {
+                let own_path = fs::canonicalize(own_path);
+                fs::canonicalize(path).is_ok_and(|path| own_path.is_ok_and(|own| own == path))
+            });
+            if is_own_file {
+                self.text.declare_saved();
             }
 
-            res
+            hook::queue(FileWritten((
+                path.to_string_lossy().to_string(),
+                bytes,
+                quit,
+            )));
+
+            Ok(Some(bytes))
         } else {
             Ok(None)
         }
     }
 
+    /// Encodes the [`Text`] in this [`File`]'s [`FileFormat`]
+    ///
+    /// This is done before opening anything for writing, so failing
+    /// to encode can't leave a half written file behind. Returns the
+    /// number of bytes of the [`Text`], alongside the encoded ones.
+    fn encode(&self) -> io::Result<(usize, Vec<u8>)> {
+        let mut encoded = Vec::new();
+        let bytes = self.text.write_to(self.format.writer(&mut encoded))?;
+        Ok((bytes, encoded))
+    }
+
+    ////////// Reloading the File
+
+    /// Reloads the [`File`] from disk
+    ///
+    /// Only the part of the [`Text`] that is actually different is
+    /// replaced, and this is done through the [`History`], so the
+    /// reload can be undone, even if there were unsaved changes.
+    ///
+    /// This won't trigger the [`OnFileReload`] hook. If you want
+    /// that, you can call the `reload-file` command instead.
+    ///
+    /// [`History`]: crate::text::History
+    /// [`OnFileReload`]: crate::hook::OnFileReload
+    pub fn reload(&mut self) -> Result<(), Text> {
+        let PathKind::SetExists(path) = &self.path else {
+            return Err(txt!("[a]{}[] doesn't exist on disk", self.name()).build());
+        };
+
+        let raw = fs::read(path).map_err(|err| {
+            let path = path.to_string_lossy();
+            txt!("Failed to read [a]{path}[]: {err}").build()
+        })?;
+        let (format, new) = decode_for_text(&raw);
+        let [s0, s1] = self.text.strs(..).unwrap().to_array();
+        let cur = s0.to_string() + s1;
+
+        // Being read-only shouldn't prevent syncing with the disk.
+        let is_read_only = self.text.is_read_only();
+        self.text.set_read_only(false);
+
+        let (cur_range, new_range) = differing_ranges(&cur, &new);
+        if !cur_range.is_empty() || !new_range.is_empty() {
+            self.text.finish_moment();
+            self.text.replace_range(cur_range, &new[new_range]);
+            self.text.finish_moment();
+        }
+
+        self.text.set_read_only(is_read_only);
+
+        self.text.declare_saved();
+        self.disk_hash = Some(self.text.content_hash());
+        self.format = format;
+        self.format_changed = false;
+
+        Ok(())
+    }
+
+    ////////// Large files
+
+    /// Wether this [`File`] was opened as a large file
+    ///
+    /// Large files have no [`History`] and no [`Parser`]s, in order
+    /// to stay responsive. What counts as a large file can be set
+    /// through [`FileCfg::large_file_threshold`].
+    ///
+    /// [`History`]: crate::text::History
+    pub fn is_large(&self) -> bool {
+        self.is_large
+    }
+
+    ////////// Crash recovery
+
+    /// Wether there are unsaved changes from a previous session
+    ///
+    /// These would be left behind if Duat crashed, and can be
+    /// recovered with the `recover` command.
+    pub fn has_recovery(&self) -> bool {
+        self.recovery.is_some()
+    }
+
+    /// Wether the unsaved changes should be stored now
+    ///
+    /// This is checked before [`File::store_recovery`], so the
+    /// [`File`] is only written to when something will actually be
+    /// stored or deleted.
+    pub(crate) fn needs_recovery(&self) -> bool {
+        let (Some(interval), PathKind::SetExists(_)) = (self.recovery_interval, &self.path) else {
+            return false;
+        };
+
+        // Don't overwrite what is yet to be recovered.
+        !self.is_large
+            && self.recovery.is_none()
+            && self.last_recovery.elapsed() >= interval
+            && (self.text.has_unsaved_changes() || self.has_journal)
+    }
+
+    /// Stores the unsaved changes, or deletes them if there are none
+    pub(crate) fn store_recovery(&mut self) {
+        let PathKind::SetExists(path) = &self.path else {
+            return;
+        };
+
+        self.last_recovery = Instant::now();
+        self.has_journal = Recovery::store(path, &self.text, self.disk_hash);
+    }
+
+    /// Deletes the stored unsaved changes
+    ///
+    /// This is done when the [`File`] is closed normally, since any
+    /// unsaved changes were deliberately thrown away.
+    pub(crate) fn delete_recovery(&self) {
+        if let PathKind::SetExists(path) = &self.path {
+            Recovery::delete(path);
+        }
+    }
+
+    /// Wether the [`File`] has the same contents as the raw bytes
+    /// from disk
+    pub(crate) fn matches_disk(&self, raw: &[u8]) -> bool {
+        let (_, new) = decode_for_text(raw);
+        let [s0, s1] = self.text.strs(..).unwrap().to_array();
+        s0.len() + s1.len() == new.len() && new.starts_with(s0) && new.ends_with(s1)
+    }
+
+    ////////// FileFormat functions
+
+    /// The [`FileFormat`] of this [`File`]
+    ///
+    /// This is the format that was detected when the [`File`] was
+    /// opened, or the one that was set afterwards. It is the format
+    /// that will be used when writing the [`File`].
+    pub fn format(&self) -> FileFormat {
+        self.format
+    }
+
+    /// Sets the [`LineEnding`] that will be used when writing
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.format_changed |= self.format.line_ending != line_ending;
+        self.format.line_ending = line_ending;
+    }
+
+    /// Sets the [`Encoding`] that will be used when writing
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.format_changed |= self.format.encoding != encoding;
+        self.format.encoding = encoding;
+    }
+
+    /// Sets wether a byte order mark will be written
+    pub fn set_bom(&mut self, has_bom: bool) {
+        self.format_changed |= self.format.has_bom != has_bom;
+        self.format.has_bom = has_bom;
+    }
+
     ////////// Path querying functions
 
     /// The full path of the file.
@@ -507,9 +863,15 @@ impl<U: Ui> File<U> {
 impl<U: Ui> Handle<File<U>, U> {
     /// Adds a [`Parser`] to react to [`Text`] [`Change`]s
     ///
+    /// If the [`File`] [is large], the [`Parser`] won't be added.
+    ///
     /// [`Change`]: crate::text::Change
+    /// [is large]: File::is_large
     pub fn add_parser(&mut self, pa: &mut Pass, cfg: impl ParserCfg<U>) {
         let file = self.widget().read(pa);
+        if file.is_large {
+            return;
+        }
 
         if let Err(err) = file.parsers.add(file, cfg) {
             context::error!("{err}");
@@ -589,6 +951,10 @@ impl<U: Ui> Widget<U> for File<U> {
         })
     }
 
+    fn on_mouse_event(pa: &mut Pass, handle: &Handle<Self, U>, event: MouseEvent) {
+        mode::select_with_mouse(pa, handle, event);
+    }
+
     fn once() -> Result<(), Text> {
         Ok(())
     }
@@ -733,6 +1099,170 @@ impl PathKind {
     }
 }
 
+/// Writes to a file atomically
+///
+/// The contents are first written to a temporary file in the same
+/// directory, which is synced and then renamed over the original.
+/// Symlinks are followed, and the permissions and ownership of the
+/// original are kept. If `keep_backup` is `true`, a copy of the
+/// original is kept at `{path}~`.
+fn write_atomically(path: &Path, keep_backup: bool, encoded: &[u8]) -> io::Result<()> {
+    // Canonicalizing follows symlinks, so they are kept intact.
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not a file path",
+        ));
+    };
+    let metadata = fs::metadata(&path).ok();
+
+    let tmp_path = dir.join({
+        let mut tmp_name = OsString::from(".");
+        tmp_name.push(name);
+        tmp_name.push(format!(".{}.duat-tmp", std::process::id()));
+        tmp_name
+    });
+
+    let write_and_rename = || -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)?;
+
+        if let Some(metadata) = &metadata {
+            file.set_permissions(metadata.permissions())?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                // Fails if the file belongs to someone else, but the
+                // write should still go through.
+                let (uid, gid) = (metadata.uid(), metadata.gid());
+                let _ = std::os::unix::fs::fchown(&file, Some(uid), Some(gid));
+            }
+        }
+
+        file.write_all(encoded)?;
+        file.sync_all()?;
+
+        if keep_backup && metadata.is_some() {
+            let mut backup_path = path.clone().into_os_string();
+            backup_path.push("~");
+            fs::copy(&path, backup_path)?;
+        }
+
+        fs::rename(&tmp_path, &path)?;
+
+        // Makes sure that the rename itself is persisted.
+        #[cfg(unix)]
+        fs::File::open(dir)?.sync_all()?;
+
+        Ok(())
+    };
+
+    write_and_rename().inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// Reloads a [`File`] from disk, triggering [`OnFileReload`]
+///
+/// This discards any unsaved changes, but since the reload goes
+/// through the [`History`], it can be undone.
+///
+/// [`History`]: crate::text::History
+pub fn reload_from_disk<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) -> Result<(), Text> {
+    hook::trigger(pa, OnFileReload((handle.clone(), Cache::new())));
+    handle.write(pa).reload()
+}
+
+/// Opens a file as a large file, if it is larger than the threshold
+///
+/// If the file is UTF-8 with `'\n'` line endings and no byte order
+/// mark, it is mapped into memory. Otherwise, it has to be read and
+/// decoded into a new [`String`].
+///
+/// This is what [`FileCfg`] uses to open large files, and is only
+/// public so it can be benchmarked.
+#[doc(hidden)]
+pub fn open_large_file(path: &Path, threshold: usize) -> Option<(Text, FileFormat)> {
+    let file = fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() <= threshold as u64 {
+        return None;
+    }
+
+    // SAFETY: Duat writes files by replacing them, so it never changes
+    // the contents of a mapped file. Other programs could still truncate
+    // it, which is a risk that comes with mapping any file.
+    let map = unsafe { Mmap::map(&file) }.ok();
+    let (format, bytes) = match map
+        .filter(|map| FileFormat::is_verbatim(map))
+        .and_then(|map| Bytes::from_map(map).ok())
+    {
+        Some(bytes) => (FileFormat::default(), bytes),
+        None => {
+            let (format, string) = FileFormat::decode_vec(fs::read(path).ok()?);
+            (format, Bytes::new(&string))
+        }
+    };
+
+    let selections = Selections::new(Selection::default());
+    Some((Text::from_bytes(bytes, selections, false), format))
+}
+
+/// A hash of the contents of a file on disk, as they would be in a
+/// [`Text`]
+fn hash_of_disk(path: &Path) -> Option<u64> {
+    let (_, string) = decode_for_text(&fs::read(path).ok()?);
+    let text = Text::from_bytes(Bytes::new(&string), Selections::new_empty(), false);
+    Some(text.content_hash())
+}
+
+/// Decodes the raw bytes of a file, as they would be in a [`Text`]
+fn decode_for_text(raw: &[u8]) -> (FileFormat, String) {
+    let (format, mut string) = FileFormat::decode(raw);
+    // The Text always ends in a '\n'.
+    if !string.ends_with('\n') {
+        string.push('\n');
+    }
+    (format, string)
+}
+
+/// The byte ranges where two `str`s differ
+///
+/// This is done by removing their common prefix and suffix.
+fn differing_ranges(lhs: &str, rhs: &str) -> (Range<usize>, Range<usize>) {
+    let prefix = lhs
+        .bytes()
+        .zip(rhs.bytes())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let prefix = (0..=prefix)
+        .rev()
+        .find(|&b| rhs.is_char_boundary(b))
+        .unwrap();
+
+    let (lhs_rest, rhs_rest) = (&lhs[prefix..], &rhs[prefix..]);
+    let suffix = lhs_rest
+        .bytes()
+        .rev()
+        .zip(rhs_rest.bytes().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let suffix = (0..=suffix)
+        .rev()
+        .find(|&len| rhs_rest.is_char_boundary(rhs_rest.len() - len))
+        .unwrap();
+
+    (prefix..lhs.len() - suffix, prefix..rhs.len() - suffix)
+}
+
+/// The error shown when failing to write to a file
+fn write_error(path: &Path, err: io::Error) -> Text {
+    let path = path.to_string_lossy();
+    txt!("Failed to write to [a]{path}[]: {err}").build()
+}
+
 /// What to do when opening the [`File`]
 #[derive(Default, Clone)]
 enum TextOp {
@@ -27,7 +27,7 @@ use crate::{
     data::Pass,
     mode::Selections,
     prelude::Ranges,
-    text::{Bytes, Moment, Point, Tags, Text, TextParts, txt},
+    text::{Bytes, Folds, Moment, Point, Tags, Text, TextParts, txt},
     ui::{Ui, Widget},
 };
 
@@ -660,7 +660,7 @@ fn get_ranges<'a>(
 
     if moment.len() <= MAX_CHANGES_TO_CONSIDER || bytes.len().byte() >= FOLDING_COULD_UPDATE_A_LOT {
         for change in moment.changes() {
-            let diff = change.added_end().byte() as i32 - change.taken_end().byte() as i32;
+            let diff = change.added_end().byte() as isize - change.taken_end().byte() as isize;
             ranges.shift_by(change.start().byte(), diff);
         }
 
@@ -683,6 +683,8 @@ fn type_eq<U: Ui, Rd: Parser<U>>(pb: &ParserBox<U>) -> bool {
 /// - [`parts.tags`]: The [`Tags`] of the [`File`], this is the
 ///   primary things that [`Parser`]s should update.
 /// - [`parts.selections`]: The [`Selections`] of the [`File`].
+/// - [`parts.folds`]: The [`Folds`] of the [`File`], where fold
+///   providers add the ranges that can be folded.
 /// - [`parts.parsers`]: An immutable reference to all the other
 ///   [`Parser`]s of the [`File`].
 ///
@@ -697,6 +699,7 @@ fn type_eq<U: Ui, Rd: Parser<U>>(pb: &ParserBox<U>) -> bool {
 /// [`parts.bytes`]: FileParts::bytes
 /// [`parts.tags`]: FileParts::tags
 /// [`parts.selections`]: FileParts::selections
+/// [`parts.folds`]: FileParts::folds
 /// [`parts.parsers`]: FileParts::parsers
 pub struct FileParts<'a, U: Ui> {
     /// The [`Bytes`] of the [`File`]
@@ -774,6 +777,16 @@ pub struct FileParts<'a, U: Ui> {
     /// [`Selection`]: crate::mode::Selection
     /// [`parts.suggested_max_range`]: FileParts::suggested_max_range
     pub selections: &'a Selections,
+    /// The [`Folds`] of the [`File`]
+    ///
+    /// These are the ranges that can be folded by [`Text::fold_at`],
+    /// or the `fold` command. Since they are shifted by every
+    /// [`Change`], you don't need to update them on every call, only
+    /// when the structure of the [`File`] changes.
+    ///
+    /// [`File`]: super::File
+    /// [`Change`]: crate::text::Change
+    pub folds: &'a mut Folds,
     /// Other [`Parser`]s that were added to this [`File`]
     ///
     /// This can be useful if you want to access the state of other
@@ -804,11 +817,12 @@ pub struct FileParts<'a, U: Ui> {
 impl<'a, U: Ui> FileParts<'a, U> {
     /// Returns a new [`FileParts`]
     pub fn new(text: &'a mut Text, parsers: Parsers<'a, U>, range: Range<Point>) -> Self {
-        let TextParts { bytes, tags, selections } = text.parts();
+        let TextParts { bytes, tags, selections, folds } = text.parts();
         Self {
             bytes,
             tags,
             selections,
+            folds,
             parsers,
             suggested_max_range: range,
         }
@@ -0,0 +1,134 @@
+//! Recovery of unsaved changes after a crash
+//!
+//! Periodically, the unsaved [`Change`]s of every [`File`] are stored
+//! in Duat's cache directory. When Duat quits or a [`File`] is
+//! closed normally, these are discarded, but if Duat (or the config
+//! crate) panics, they will be found on the next opening of the
+//! [`File`], and you will be able to recover them with the `recover`
+//! command, or discard them with the `discard-recovery` command.
+//!
+//! [`File`]: super::File
+use std::path::Path;
+
+use bincode::{Decode, Encode};
+
+use super::{File, PathKind};
+use crate::{
+    context::{self, Cache, Handle},
+    data::Pass,
+    hook::{self, FileRecovered},
+    text::{Change, Point, Text, txt},
+    ui::Ui,
+};
+
+/// Unsaved [`Change`]s to a [`File`], stored in case Duat crashes
+///
+/// [`File`]: super::File
+#[derive(Default, Debug, Clone, Encode, Decode)]
+pub(crate) struct Recovery {
+    /// A hash of the contents on disk, which this applies to
+    base_hash: u64,
+    changes: Vec<Change>,
+    /// The whole contents of the [`File`], for when the [`Change`]s
+    /// since the last save are unknown
+    ///
+    /// [`File`]: super::File
+    contents: Option<String>,
+}
+
+impl Recovery {
+    /// Loads a [`Recovery`] for a path, if it applies to the [`Text`]
+    ///
+    /// If it doesn't, it is deleted, since it is no longer valid.
+    pub(super) fn load(path: &Path, text: &Text) -> Option<Self> {
+        let cache = Cache::new();
+        let recovery: Self = cache.load(path).ok()?;
+
+        let is_empty = recovery.changes.is_empty() && recovery.contents.is_none();
+        if recovery.base_hash == text.content_hash() && !is_empty {
+            Some(recovery)
+        } else {
+            cache.delete_for::<Self>(path);
+            None
+        }
+    }
+
+    /// Stores the unsaved [`Change`]s of a [`Text`], or deletes the
+    /// stored [`Recovery`] if there are none
+    ///
+    /// The `disk_hash` is a hash of the contents on disk. If the
+    /// [`Change`]s since the last save can't be retrieved from the
+    /// [`History`] (e.g. after reloading the config), the whole
+    /// contents of the [`Text`] are stored instead.
+    ///
+    /// Returns `true` if something was stored.
+    ///
+    /// [`History`]: crate::text::History
+    pub(super) fn store(path: &Path, text: &Text, disk_hash: Option<u64>) -> bool {
+        let Some(base_hash) = disk_hash.filter(|_| text.has_unsaved_changes()) else {
+            Self::delete(path);
+            return false;
+        };
+
+        let recovery = match text.unsaved_changes() {
+            Some((saved_hash, changes)) if saved_hash == base_hash => {
+                Self { base_hash, changes, contents: None }
+            }
+            _ => Self {
+                base_hash,
+                changes: Vec::new(),
+                contents: Some(text.strs(..).unwrap().to_string()),
+            },
+        };
+
+        match Cache::new().store(path, recovery) {
+            Ok(_) => true,
+            Err(err) => {
+                context::error!("{err}");
+                false
+            }
+        }
+    }
+
+    /// Deletes the stored [`Recovery`] for a path
+    pub(super) fn delete(path: &Path) {
+        Cache::new().delete_for::<Self>(path);
+    }
+}
+
+/// Recovers the unsaved [`Change`]s from a previous session
+///
+/// This is done through the [`History`], so it can be undone.
+/// Triggers the [`FileRecovered`] hook.
+///
+/// [`History`]: crate::text::History
+pub(crate) fn recover<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) -> Result<(), Text> {
+    let file = handle.write(pa);
+    let Some(recovery) = file.recovery.take() else {
+        return Err(txt!("There are no changes to recover").build());
+    };
+
+    if let Some(contents) = recovery.contents {
+        let change = Change::new(contents, [Point::default(), file.text.len()], &file.text);
+        file.text.apply_changes([change]);
+    } else {
+        file.text.apply_changes(recovery.changes);
+    }
+
+    hook::trigger(pa, FileRecovered(handle.clone()));
+    Ok(())
+}
+
+/// Discards the unsaved [`Change`]s from a previous session
+pub(crate) fn discard<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) -> Result<(), Text> {
+    let file = handle.write(pa);
+    if file.recovery.take().is_none() {
+        return Err(txt!("There are no changes to discard").build());
+    }
+
+    if let PathKind::SetExists(path) = &file.path {
+        Recovery::delete(path);
+    }
+
+    Ok(())
+}
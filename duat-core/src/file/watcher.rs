@@ -0,0 +1,124 @@
+//! Watching of [`File`]s for changes on disk
+//!
+//! Since many programs (Duat included) write to files by replacing
+//! them, it is the parent directories of the [`File`]s that are
+//! watched, rather than the files themselves.
+//!
+//! [`File`]: super::File
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    context::{self, sender},
+    ui::DuatEvent,
+};
+
+/// Watches [`File`]s, sending [`DuatEvent::FileChangedOnDisk`] when
+/// they change
+///
+/// [`File`]: super::File
+pub(crate) struct FileWatcher {
+    watcher: Option<RecommendedWatcher>,
+    files: Arc<Mutex<HashSet<PathBuf>>>,
+    dirs: HashSet<PathBuf>,
+    last_seen: HashMap<PathBuf, u64>,
+}
+
+impl FileWatcher {
+    /// Returns a new [`FileWatcher`], watching nothing
+    pub(crate) fn new() -> Self {
+        let files = Arc::new(Mutex::new(HashSet::<PathBuf>::new()));
+
+        let watcher = notify::recommended_watcher({
+            let files = files.clone();
+            move |res: notify::Result<Event>| {
+                let Ok(Event {
+                    kind: EventKind::Create(_) | EventKind::Modify(_),
+                    paths,
+                    ..
+                }) = res
+                else {
+                    return;
+                };
+
+                let files = files.lock().unwrap();
+                for path in paths.into_iter().filter(|path| files.contains(path)) {
+                    let _ = sender().send(DuatEvent::FileChangedOnDisk(path));
+                }
+            }
+        });
+
+        let watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                context::error!("Couldn't watch files for changes: {err}");
+                None
+            }
+        };
+
+        Self {
+            watcher,
+            files,
+            dirs: HashSet::new(),
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Sets the paths of the files that should be watched
+    pub(crate) fn set_files(&mut self, files: impl IntoIterator<Item = PathBuf>) {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return;
+        };
+
+        let files: HashSet<PathBuf> = files.into_iter().collect();
+        if *self.files.lock().unwrap() == files {
+            return;
+        }
+
+        let dirs: HashSet<PathBuf> = files
+            .iter()
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .collect();
+
+        for dir in self.dirs.difference(&dirs) {
+            let _ = watcher.unwatch(dir);
+        }
+        for dir in dirs.difference(&self.dirs) {
+            if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                let dir = dir.to_string_lossy();
+                context::error!("Couldn't watch [a]{dir}[] for changes: {err}");
+            }
+        }
+
+        self.last_seen.retain(|file, _| files.contains(file));
+        self.dirs = dirs;
+        *self.files.lock().unwrap() = files;
+    }
+
+    /// Wether these bytes weren't the last ones seen for this path
+    ///
+    /// This is used in order to ignore the multiple events that
+    /// usually come from a single write.
+    pub(crate) fn is_new_content(&mut self, path: &Path, raw: &[u8]) -> bool {
+        let mut hasher = DefaultHasher::new();
+        raw.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        self.last_seen.insert(path.to_path_buf(), hash) != Some(hash)
+    }
+
+    /// Stops watching for changes
+    ///
+    /// This must be called before unloading the config crate, since
+    /// the watcher runs on a separate thread.
+    pub(crate) fn stop(&mut self) {
+        self.watcher = None;
+        self.files.lock().unwrap().clear();
+    }
+}
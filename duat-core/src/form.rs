@@ -42,8 +42,62 @@ static BASE_FORMS: &[(&str, Form, FormType)] = &[
         Ref(M_SEL_ID.0 as usize),
     ),
     ("cloak", Form::grey().on_black().0, Normal),
+    ("button.hover", Form::underlined().0, Normal),
+    ("fold", Form::grey().0, Normal),
+    (
+        "diagnostic.error",
+        Form::undercurled().underline_red().0,
+        Normal,
+    ),
+    (
+        "diagnostic.warning",
+        Form::undercurled().underline_yellow().0,
+        Normal,
+    ),
+    (
+        "diagnostic.info",
+        Form::underlined().underline_blue().0,
+        Normal,
+    ),
+    (
+        "diagnostic.hint",
+        Form::underdashed().underline_grey().0,
+        Normal,
+    ),
+    ("diagnostic.message.error", Form::red().italic().0, Normal),
+    (
+        "diagnostic.message.warning",
+        Form::yellow().italic().0,
+        Normal,
+    ),
+    ("diagnostic.message.info", Form::blue().italic().0, Normal),
+    ("diagnostic.message.hint", Form::grey().italic().0, Normal),
 ];
 
+/// The [`FormId`] of one of the `BASE_FORMS`, found at compile time
+///
+/// Fails to compile if there is no form with that name, so the
+/// [`FormId`] can't silently point to another form if the order
+/// changes.
+const fn base_form_id(name: &str) -> FormId {
+    let mut i = 0;
+    while i < BASE_FORMS.len() {
+        let form_name = BASE_FORMS[i].0.as_bytes();
+        if form_name.len() == name.len() {
+            let mut j = 0;
+            while j < name.len() && form_name[j] == name.as_bytes()[j] {
+                j += 1;
+            }
+            if j == name.len() {
+                return FormId(i as u16);
+            }
+        }
+        i += 1;
+    }
+
+    panic!("not one of the base forms");
+}
+
 /// The functions that will be exposed for public use.
 mod global {
     use std::{
@@ -1002,6 +1056,8 @@ pub const E_CAR_ID: FormId = FormId(3);
 pub const M_SEL_ID: FormId = FormId(4);
 /// The [`FormId`] of the `"selection.extra"` form
 pub const E_SEL_ID: FormId = FormId(5);
+/// The [`FormId`] of the `"button.hover"` form
+pub const HOVER_ID: FormId = base_form_id("button.hover");
 
 struct InnerPalette {
     main_cursor: Option<CursorShape>,
@@ -1453,6 +1509,18 @@ impl Painter {
         self.remove(E_CAR_ID);
     }
 
+    /// Applies the `"button.hover"` [`Form`]
+    #[inline(always)]
+    pub fn apply_button_hover(&mut self) {
+        self.apply(HOVER_ID, 100);
+    }
+
+    /// Removes the `"button.hover"` [`Form`]
+    #[inline(always)]
+    pub fn remove_button_hover(&mut self) {
+        self.remove(HOVER_ID);
+    }
+
     /// The [`Form`] "caret.extra", and its shape.
     pub fn main_cursor(&self) -> Option<CursorShape> {
         self.inner.main_cursor
@@ -48,10 +48,20 @@
 //!   window.
 //! - [`OnFileClose`] triggers on every file upon closing Duat.
 //! - [`OnFileReload`] triggers on every file upon reloading Duat.
+//! - [`FileChangedOnDisk`] triggers when a [`File`] is changed by
+//!   another program.
+//! - [`FileRecovered`] triggers after recovering unsaved changes
+//!   from a crashed session.
 //! - [`FocusedOn`] lets you act on a [widget] when focused.
 //! - [`UnfocusedFrom`] lets you act on a [widget] when unfocused.
+//! - [`WidgetUpdated`] lets you act on a [widget] right before it is
+//!   printed.
 //! - [`KeysSent`] lets you act on a [dyn Widget], given a [key].
 //! - [`KeysSentTo`] lets you act on a given [widget], given a [key].
+//! - [`Pasted`] triggers after text is pasted.
+//! - [`MouseEventSent`] lets you act on a mouse event.
+//! - [`MouseEventSentTo`] lets you act on the [widget] under the
+//!   pointer, given a mouse event.
 //! - [`FormSet`] triggers whenever a [`Form`] is added/altered.
 //! - [`ModeSwitched`] triggers when you change [`Mode`].
 //! - [`ModeCreated`] lets you act on a [`Mode`] after switching.
@@ -160,7 +170,7 @@ use crate::{
     data::Pass,
     file::File,
     form::{Form, FormId},
-    mode::{KeyEvent, Mode},
+    mode::{KeyEvent, Mode, MouseEvent},
     ui::{Ui, UiBuilder, Widget},
 };
 
@@ -514,6 +524,9 @@ impl<U: Ui> Hookable for OnFileClose<U> {
 /// - A [`Cache`]. This can be used in order to decide wether or not
 ///   some things will be reloaded on the next opening of Duat.
 ///
+/// This also triggers before reloading the [`File`] from disk, which
+/// happens when it is changed by another program.
+///
 /// This will not trigger upon closing Duat. For that, see
 /// [`OnFileClose`].
 pub struct OnFileReload<U: Ui>(pub(crate) (Handle<File<U>, U>, Cache));
@@ -526,6 +539,55 @@ impl<U: Ui> Hookable for OnFileReload<U> {
     }
 }
 
+/// [`Hookable`]: Triggers when a [`File`] is changed on disk
+///
+/// # Arguments
+///
+/// - The [`File`]'s [`Handle`].
+///
+/// This only triggers if the contents on disk are different from
+/// those of the [`File`], so it won't trigger when Duat itself writes
+/// to it.
+///
+/// After this hook is triggered, if the [`File`] still differs from
+/// its contents on disk, it will be reloaded if it has no unsaved
+/// changes. Otherwise, it is left to this hook, which could, for
+/// example, ask if the [`File`] should be reloaded with
+/// [`file::reload_from_disk`]. Since the reload goes through the
+/// [`History`], it can be undone.
+///
+/// [`file::reload_from_disk`]: crate::file::reload_from_disk
+/// [`History`]: crate::text::History
+pub struct FileChangedOnDisk<U: Ui>(pub(crate) Handle<File<U>, U>);
+
+impl<U: Ui> Hookable for FileChangedOnDisk<U> {
+    type Input<'h> = &'h Handle<File<U>, U>;
+
+    fn get_input(&mut self) -> Self::Input<'_> {
+        &self.0
+    }
+}
+
+/// [`Hookable`]: Triggers after recovering a [`File`]'s unsaved
+/// changes
+///
+/// # Arguments
+///
+/// - The [`File`]'s [`Handle`].
+///
+/// If Duat crashes, the unsaved changes of every [`File`] are kept,
+/// and can be recovered with the `recover` command on the next
+/// opening of said [`File`]. This hook triggers right after that.
+pub struct FileRecovered<U: Ui>(pub(crate) Handle<File<U>, U>);
+
+impl<U: Ui> Hookable for FileRecovered<U> {
+    type Input<'h> = &'h Handle<File<U>, U>;
+
+    fn get_input(&mut self) -> Self::Input<'_> {
+        &self.0
+    }
+}
+
 /// [`Hookable`]: Triggers when the [`Widget`] is focused
 ///
 /// # Arguments
@@ -558,6 +620,29 @@ impl<W: Widget<U>, U: Ui> Hookable for UnfocusedFrom<W, U> {
     }
 }
 
+/// [`Hookable`]: Triggers after a [`Widget`] is updated, right
+/// before it is printed
+///
+/// # Arguments
+///
+/// - The [`Handle<W>`] for the updated [`Widget`]
+///
+/// By this point, the [`Widget`] has already scrolled to where it
+/// will be printed, so this is a good place to add [`Tag`]s to the
+/// visible part of its [`Text`].
+///
+/// [`Tag`]: crate::text::Tag
+/// [`Text`]: crate::text::Text
+pub struct WidgetUpdated<W: Widget<U>, U: Ui>(pub(crate) Handle<W, U>);
+
+impl<W: Widget<U>, U: Ui> Hookable for WidgetUpdated<W, U> {
+    type Input<'h> = &'h Handle<W, U>;
+
+    fn get_input(&mut self) -> Self::Input<'_> {
+        &self.0
+    }
+}
+
 /// [`Hookable`]: Triggers when the [`Mode`] is changed
 ///
 /// # Arguments
@@ -689,6 +774,63 @@ impl<W: Widget<U>, U: Ui> Hookable for KeysSentTo<W, U> {
     }
 }
 
+/// [`Hookable`]: Triggers whenever text is pasted
+///
+/// This is triggered after the text is sent to the active [`Mode`],
+/// through [`Mode::paste`].
+///
+/// # Arguments
+///
+/// - The pasted text.
+pub struct Pasted(pub(crate) String);
+
+impl Hookable for Pasted {
+    type Input<'h> = &'h str;
+
+    fn get_input(&mut self) -> Self::Input<'_> {
+        &self.0
+    }
+}
+
+/// [`Hookable`]: Triggers whenever a [mouse event] happens
+///
+/// This is triggered before the event is sent to the [`Widget`] under
+/// the pointer.
+///
+/// # Arguments
+///
+/// - The [mouse event].
+///
+/// [mouse event]: MouseEvent
+pub struct MouseEventSent(pub(crate) MouseEvent);
+
+impl Hookable for MouseEventSent {
+    type Input<'h> = MouseEvent;
+
+    fn get_input(&mut self) -> Self::Input<'_> {
+        self.0
+    }
+}
+
+/// [`Hookable`]: Triggers whenever a [mouse event] is sent to the
+/// [`Widget`]
+///
+/// # Arguments
+///
+/// - The [mouse event].
+/// - An [`Handle<W>`] for the widget.
+///
+/// [mouse event]: MouseEvent
+pub struct MouseEventSentTo<W: Widget<U>, U: Ui>(pub(crate) (MouseEvent, Handle<W, U>));
+
+impl<W: Widget<U>, U: Ui> Hookable for MouseEventSentTo<W, U> {
+    type Input<'h> = (MouseEvent, &'h Handle<W, U>);
+
+    fn get_input(&mut self) -> Self::Input<'_> {
+        (self.0.0, &self.0.1)
+    }
+}
+
 /// [`Hookable`]: Triggers whenever a [`Form`] is set
 ///
 /// This can be a creation or alteration of a [`Form`].
@@ -682,6 +682,7 @@ pub mod file;
 pub mod form;
 pub mod hook;
 pub mod mode;
+pub mod registers;
 #[doc(hidden)]
 pub mod session;
 pub mod text;
@@ -753,8 +754,8 @@ pub mod prelude {
         mode::{self, KeyCode, KeyEvent, KeyMod, Mode, key},
         ranges::Ranges,
         text::{
-            AlignCenter, AlignLeft, AlignRight, Bytes, Conceal, Ghost, Matcheable, Moment, Point,
-            Spacer, Tagger, Text, txt,
+            AlignCenter, AlignLeft, AlignRight, Button, Bytes, Conceal, Ghost, Matcheable, Moment,
+            Point, Spacer, Tagger, Text, txt,
         },
         ui::{Area, BuildInfo, GetAreaId, PushSpecs, Ui, Widget, WidgetCfg},
     };
@@ -780,7 +781,7 @@ mod ranges {
     #[derive(Clone, Default, Debug)]
     pub struct Ranges {
         list: GapBuffer<Range<usize>>,
-        shift_state: (usize, i32),
+        shift_state: (usize, isize),
         min_len: usize,
     }
 
@@ -935,7 +936,7 @@ mod ranges {
         /// accordingly.
         ///
         /// [`Change`]: crate::text::Change
-        pub fn shift_by(&mut self, from: usize, diff: i32) {
+        pub fn shift_by(&mut self, from: usize, diff: isize) {
             let (shift_from, total_diff) = std::mem::take(&mut self.shift_state);
 
             // The range of changes that will be drained
@@ -1063,9 +1064,9 @@ mod ranges {
         }
     }
 
-    /// Shorthand to shift a [`usize`] by an [`i32`]
-    fn sh(n: usize, diff: i32) -> usize {
-        (n as i32 + diff) as usize
+    /// Shorthand to shift a [`usize`] by an [`isize`]
+    fn sh(n: usize, diff: isize) -> usize {
+        n.wrapping_add_signed(diff)
     }
 
     pub type IntoIter = impl ExactSizeIterator<Item = Range<usize>>;
@@ -1158,7 +1159,7 @@ pub mod clipboard {
                 .ok(),
             #[cfg(not(target_os = "android"))]
             Clipboard::Platform(clipb) => clipb.get_text().ok(),
-            Clipboard::Local(clipb) => Some(clipb.clone()).filter(String::is_empty),
+            Clipboard::Local(clipb) => Some(clipb.clone()).filter(|clipb| !clipb.is_empty()),
         }
     }
 
@@ -1389,7 +1390,7 @@ fn get_ends(range: impl std::ops::RangeBounds<usize>, max: usize) -> (usize, usi
 }
 
 /// Adds two shifts together
-pub fn add_shifts(lhs: [i32; 3], rhs: [i32; 3]) -> [i32; 3] {
+pub fn add_shifts(lhs: [isize; 3], rhs: [isize; 3]) -> [isize; 3] {
     let b = lhs[0] + rhs[0];
     let c = lhs[1] + rhs[1];
     let l = lhs[2] + rhs[2];
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use bincode::{Decode, Encode};
+
+use super::{Selection, Selections};
+use crate::text::Change;
+
+/// Named registers of saved [`Selections`]
+///
+/// Each [`Text`] has its own [`Marks`], which can store snapshots of
+/// its [`Selections`] under a [`char`], in order to come back to them
+/// later. Just like the [`Selections`] themselves, the stored
+/// [`Selections`] are shifted by every [`Change`] to the [`Text`], so
+/// they keep pointing to the same places.
+///
+/// The [`Marks`] of a [`File`] are stored in the cache when it is
+/// closed, and are loaded back if it wasn't modified by anything
+/// other than Duat in the meantime.
+///
+/// ```rust
+/// # use duat_core::prelude::*;
+/// fn go_back_to_mark<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) {
+///     let text = handle.text_mut(pa);
+///     text.save_mark('a');
+///
+///     // Do some editing...
+///
+///     text.restore_mark('a');
+/// }
+/// ```
+///
+/// [`Text`]: crate::text::Text
+/// [`File`]: crate::file::File
+#[derive(Default, Clone)]
+pub struct Marks {
+    list: HashMap<char, Selections>,
+    content_hash: Option<u64>,
+}
+
+impl Marks {
+    /// Stores a copy of some [`Selections`] under a name
+    ///
+    /// If there already were [`Selections`] stored under this name,
+    /// they are replaced.
+    pub fn set(&mut self, name: char, selections: &Selections) {
+        let mut stored = Selections::new_empty();
+        for (i, (selection, is_main)) in selections.iter().enumerate() {
+            let selection = Selection::new(selection.caret(), selection.anchor());
+            stored.insert(i, selection, is_main);
+        }
+
+        self.list.insert(name, stored);
+    }
+
+    /// The [`Selections`] stored under a name, if there are any
+    pub fn get(&self, name: char) -> Option<&Selections> {
+        self.list.get(&name)
+    }
+
+    /// Removes the [`Selections`] stored under a name
+    pub fn remove(&mut self, name: char) -> Option<Selections> {
+        self.list.remove(&name)
+    }
+
+    /// The names of all stored [`Selections`], in order
+    pub fn names(&self) -> Vec<char> {
+        let mut names: Vec<char> = self.list.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Wether there are no stored [`Selections`]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    ////////// Internal functions
+
+    /// Shifts all stored [`Selections`] by a [`Change`]
+    pub(crate) fn apply_change(&mut self, change: Change<&str>) {
+        for selections in self.list.values_mut() {
+            selections.apply_change(0, change);
+        }
+    }
+
+    /// Stamps these [`Marks`] with the hash of the [`Text`]
+    ///
+    /// [`Text`]: crate::text::Text
+    pub(crate) fn set_content_hash(&mut self, hash: u64) {
+        self.content_hash = Some(hash);
+    }
+
+    /// Wether these [`Marks`] were cached for a [`Text`] with this
+    /// hash
+    ///
+    /// [`Text`]: crate::text::Text
+    pub(crate) fn matches_content(&self, hash: u64) -> bool {
+        self.content_hash == Some(hash)
+    }
+}
+
+impl Encode for Marks {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        let list: Vec<(char, Vec<Selection>, usize)> = self
+            .list
+            .iter()
+            .map(|(name, selections)| {
+                let list = selections.iter().map(|(sel, _)| sel.clone()).collect();
+                (*name, list, selections.main_index())
+            })
+            .collect();
+
+        Encode::encode(&list, encoder)?;
+        Encode::encode(&self.content_hash, encoder)?;
+        Ok(())
+    }
+}
+
+impl<Context> Decode<Context> for Marks {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let list: Vec<(char, Vec<Selection>, usize)> = Decode::decode(decoder)?;
+        let list = list
+            .into_iter()
+            .map(|(name, list, main)| {
+                let mut selections = Selections::new_empty();
+                for (i, selection) in list.into_iter().enumerate() {
+                    let selection = Selection::new(selection.caret(), selection.anchor());
+                    selections.insert(i, selection, i == main);
+                }
+                (name, selections)
+            })
+            .collect();
+
+        Ok(Marks {
+            list,
+            content_hash: Decode::decode(decoder)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for Marks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.list.iter()).finish()
+    }
+}
@@ -13,14 +13,20 @@ use std::{
 
 use lender::{Lender, Lending};
 
-pub use self::selections::{Selection, Selections, VPoint};
+pub use self::{
+    marks::Marks,
+    selections::{Selection, Selections, VPoint},
+};
 use crate::{
     cfg::PrintCfg,
     file::{File, Parser},
-    text::{Change, Lines, Point, RegexPattern, Searcher, Strs, Text, TextRange},
+    registers,
+    text::{Change, Lines, Point, RegexPattern, SearchOpts, Searcher, Strs, Text, TextRange},
     ui::{Area, Ui, Widget},
 };
 
+/// The [`Marks`] struct
+mod marks;
 /// The [`Selection`] and [`Selections`] structs
 mod selections;
 
@@ -75,11 +81,13 @@ pub struct Cursor<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> {
     widget: &'a mut W,
     area: &'a A,
     next_i: Option<Rc<Cell<usize>>>,
+    edit_denied: &'a Cell<bool>,
     inc_searcher: &'a mut S,
 }
 
 impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
     /// Returns a new instance of [`Cursor`]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         selection: Selection,
         n: usize,
@@ -87,6 +95,7 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
         widget: &'a mut W,
         area: &'a A,
         next_i: Option<Rc<Cell<usize>>>,
+        edit_denied: &'a Cell<bool>,
         searcher: &'a mut S,
     ) -> Self {
         Self {
@@ -97,6 +106,7 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
             widget,
             area,
             next_i,
+            edit_denied,
             inc_searcher: searcher,
         }
     }
@@ -119,6 +129,10 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
     /// [`insert`]: Self::insert
     /// [`append`]: Self::append
     pub fn replace(&mut self, edit: impl ToString) {
+        if self.deny_if_read_only() {
+            return;
+        }
+
         let change = {
             let edit = edit.to_string();
             let [p0, p1] = self.selection.point_range(self.widget.text());
@@ -153,6 +167,10 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
     /// [`replace`]: Self::replace
     /// [`append`]: Self::append
     pub fn insert(&mut self, edit: impl ToString) {
+        if self.deny_if_read_only() {
+            return;
+        }
+
         let range = [self.selection.caret(), self.selection.caret()];
         let change = Change::new(edit.to_string(), range, self.widget.text());
         let (added, taken) = (change.added_end(), change.taken_end());
@@ -180,6 +198,10 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
     /// [`replace`]: Self::replace
     /// [`insert`]: Self::insert
     pub fn append(&mut self, edit: impl ToString) {
+        if self.deny_if_read_only() {
+            return;
+        }
+
         let caret = self.selection.caret();
         let p = caret.fwd(self.widget.text().char_at(caret).unwrap());
         let change = Change::new(edit.to_string(), [p, p], self.widget.text());
@@ -197,6 +219,102 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
         }
     }
 
+    /// Replaces every match of a regex within the selection
+    ///
+    /// The replacement can reference the capture groups of each
+    /// match, either by index, like `$1`, or by name, like `$name` or
+    /// `${name}`. `$0` is the whole match, and `$$` is a literal `$`.
+    ///
+    /// If there was a selection, it will be expanded or shrunk in
+    /// order to still contain all the replaced text. Returns the
+    /// number of matches that were replaced.
+    ///
+    /// Returns an [`Err`] if the regex is not valid, in which case
+    /// nothing is replaced.
+    ///
+    /// ```rust
+    /// # use duat_core::prelude::*;
+    /// fn swap_args<U: Ui, S>(pa: &mut Pass, handle: &mut Handle<File<U>, U, S>) {
+    ///     handle.edit_all(pa, |mut e| {
+    ///         e.replace_matches(r"(\w+), (\w+)", "$2, $1").unwrap();
+    ///     });
+    ///     handle.text_mut(pa).new_moment();
+    /// }
+    /// ```
+    pub fn replace_matches(
+        &mut self,
+        pat: &str,
+        replacement: &str,
+    ) -> Result<usize, Box<regex_syntax::Error>> {
+        self.replace_matches_with(pat, replacement, SearchOpts::default())
+    }
+
+    /// Replaces every match of a pattern within the selection,
+    /// interpreting it with [`SearchOpts`]
+    ///
+    /// This is the same as [`Cursor::replace_matches`], but the
+    /// pattern can be literal, case insensitive, or only match whole
+    /// words, as decided by the [`PrintCfg::word_chars`] of the
+    /// [`Widget`].
+    ///
+    /// Returns an [`Err`] if the pattern is not a valid regex, in
+    /// which case nothing is replaced.
+    pub fn replace_matches_with(
+        &mut self,
+        pat: &str,
+        replacement: &str,
+        opts: SearchOpts,
+    ) -> Result<usize, Box<regex_syntax::Error>> {
+        if self.deny_if_read_only() {
+            return Ok(0);
+        }
+
+        let [start, end] = self.range();
+        let word_chars = self.cfg().word_chars;
+        let text = self.widget.text();
+        let replacements: Vec<([Point; 2], String)> = text
+            .replacements(&opts.pattern(pat), replacement, start..end)?
+            .filter(|(range, _)| !opts.whole_word || text.is_whole_word(*range, word_chars))
+            .collect();
+
+        // Done in reverse, so earlier matches keep their Points.
+        let mut new_end = end;
+        for (range, replaced) in replacements.iter().rev() {
+            let change = Change::new(replaced, *range, self.widget.text());
+            new_end = new_end + change.added_end() - change.taken_end();
+            self.edit(change);
+        }
+
+        if self.anchor().is_some() && !replacements.is_empty() {
+            let anchor_was_on_start = self.anchor_is_start();
+            if new_end > start {
+                self.move_to(start..new_end);
+            } else {
+                self.unset_anchor();
+                self.move_to(start);
+            }
+            if !anchor_was_on_start {
+                self.set_caret_on_start();
+            }
+        }
+
+        Ok(replacements.len())
+    }
+
+    /// Returns `true` if the [`Text`] is read-only
+    ///
+    /// In that case, the edit is not done, and the [`Handle`] that
+    /// created this [`Cursor`] will log an error.
+    ///
+    /// [`Handle`]: crate::context::Handle
+    fn deny_if_read_only(&self) -> bool {
+        let is_read_only = self.widget.text().is_read_only();
+        if is_read_only {
+            self.edit_denied.set(true);
+        }
+        is_read_only
+    }
+
     /// Edits the file with a [`Change`]
     fn edit(&mut self, change: Change) {
         let text = self.widget.text_mut();
@@ -379,6 +497,7 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursor<'a, W, A, S> {
             self.widget,
             self.area,
             self.next_i.clone(),
+            self.edit_denied,
             self.inc_searcher,
         )
     }
@@ -753,6 +872,7 @@ pub struct Cursors<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> {
     next_i: Rc<Cell<usize>>,
     widget: &'a mut W,
     area: &'a A,
+    edit_denied: &'a Cell<bool>,
     inc_searcher: RefMut<'a, S>,
 }
 
@@ -762,15 +882,253 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Cursors<'a, W, A, S> {
         next_i: usize,
         widget: &'a mut W,
         area: &'a A,
+        edit_denied: &'a Cell<bool>,
         inc_searcher: RefMut<'a, S>,
     ) -> Self {
         Self {
             next_i: Rc::new(Cell::new(next_i)),
             widget,
             area,
+            edit_denied,
             inc_searcher,
         }
     }
+
+    ////////// Multi-selection operations
+
+    /// Aligns the carets to the same visual column
+    ///
+    /// This is done by inserting spaces before the start of each
+    /// [`Selection`], until its caret is on the same column as the
+    /// furthest caret. If there are multiple [`Selection`]s on the
+    /// same line, the first ones of each line are aligned with each
+    /// other, then the second ones, and so on.
+    ///
+    /// ```rust
+    /// # use duat_core::prelude::*;
+    /// fn align<U: Ui, S>(pa: &mut Pass, handle: &Handle<File<U>, U, S>) {
+    ///     handle.edit_iter(pa, |cursors| cursors.align_carets());
+    ///     handle.text_mut(pa).new_moment();
+    /// }
+    /// ```
+    pub fn align_carets(mut self) {
+        for col in 0.. {
+            let mut max_vcol = None;
+            self.for_each_on_line(|c, i| {
+                if i == col {
+                    max_vcol = max_vcol.max(Some(c.v_caret().visual_col()));
+                }
+            });
+
+            let Some(max_vcol) = max_vcol else {
+                break;
+            };
+
+            self.for_each_on_line(|mut c, i| {
+                let vcol = c.v_caret().visual_col();
+                if i != col || vcol >= max_vcol {
+                    return;
+                }
+
+                let swapped = c.set_caret_on_start();
+                c.insert(" ".repeat(max_vcol - vcol));
+                c.move_hor((max_vcol - vcol) as i32);
+                if swapped {
+                    c.set_caret_on_end();
+                }
+            });
+        }
+    }
+
+    /// Rotates the contents of the [`Selection`]s
+    ///
+    /// The content of each [`Selection`] is moved to the one `by`
+    /// places ahead of it, wrapping around at the end. A negative
+    /// `by` rotates them backwards. [`Selection`]s without an anchor
+    /// are treated as containing the character under their caret.
+    pub fn rotate_contents(mut self, by: i32) {
+        let contents = self.contents();
+        if contents.len() < 2 {
+            return;
+        }
+
+        let mut i = 0;
+        while let Some(mut c) = self.next() {
+            let from = (i - by).rem_euclid(contents.len() as i32) as usize;
+
+            let anchor_was_set = c.set_anchor_if_needed();
+            c.replace(&contents[from]);
+            if anchor_was_set {
+                c.unset_anchor();
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Splits each [`Selection`] on the matches of a regex
+    ///
+    /// The text in between matches becomes a new [`Selection`]. If a
+    /// [`Selection`] had no matches, it is left as is, and if it was
+    /// entirely matched, it is removed.
+    ///
+    /// Returns an [`Err`] if the regex is not valid, in which case no
+    /// [`Selection`]s are modified.
+    pub fn split_on(mut self, pat: &str) -> Result<(), Box<regex_syntax::Error>> {
+        Text::new().matches(pat, ..)?;
+
+        while let Some(mut c) = self.next() {
+            let [start, end] = c.range();
+            let matches = c.text().search_fwd(pat, start..end).unwrap();
+            let Some(segments) = segments_between([start, end], matches) else {
+                continue;
+            };
+
+            let mut segments = segments.into_iter();
+            let Some(first) = segments.next() else {
+                c.destroy();
+                continue;
+            };
+
+            c.move_to(first);
+            for segment in segments {
+                c.copy().move_to(segment);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keeps only the [`Selection`]s that contain a match of a regex
+    ///
+    /// If no [`Selection`]s contain a match, the last one will be
+    /// kept, since there must always be at least one [`Selection`].
+    ///
+    /// Returns an [`Err`] if the regex is not valid, in which case no
+    /// [`Selection`]s are removed.
+    pub fn keep_matching(self, pat: &str) -> Result<(), Box<regex_syntax::Error>> {
+        self.retain_matching(pat, true)
+    }
+
+    /// Removes the [`Selection`]s that contain a match of a regex
+    ///
+    /// If all [`Selection`]s contain a match, the last one will be
+    /// kept, since there must always be at least one [`Selection`].
+    ///
+    /// Returns an [`Err`] if the regex is not valid, in which case no
+    /// [`Selection`]s are removed.
+    pub fn remove_matching(self, pat: &str) -> Result<(), Box<regex_syntax::Error>> {
+        self.retain_matching(pat, false)
+    }
+
+    /// Merges [`Selection`]s that overlap or are next to each other
+    ///
+    /// Since [`Selection`]s are inclusive, one that ends right before
+    /// the start of another is considered to be touching it, and
+    /// they will be merged into one. The merged [`Selection`] keeps
+    /// the direction of the last one.
+    pub fn merge_overlapping(mut self) {
+        let mut prev: Option<[Point; 2]> = None;
+
+        while let Some(mut c) = self.next() {
+            let [start, end] = c.range();
+            if let Some([prev_start, prev_end]) = prev
+                && start <= prev_end
+            {
+                let end = end.max(prev_end);
+                let anchor_was_on_start = c.anchor_is_start();
+                c.move_to(prev_start..end);
+                if !anchor_was_on_start {
+                    c.set_caret_on_start();
+                }
+                prev = Some([prev_start, end]);
+            } else {
+                prev = Some([start, end]);
+            }
+        }
+    }
+
+    /// Copies the contents of every [`Selection`] to a register
+    ///
+    /// Each [`Selection`] gets its own value in the register, so
+    /// they can later be pasted one-to-one with [`paste_from`]. See
+    /// the [`registers`] module for more information.
+    ///
+    /// [`paste_from`]: Self::paste_from
+    /// [`registers`]: crate::registers
+    pub fn yank_to(self, name: char) {
+        registers::set(name, self.contents());
+    }
+
+    /// Replaces every [`Selection`] with the values of a register
+    ///
+    /// The values are [distributed] among the [`Selection`]s, and
+    /// [`Selection`]s without an anchor will have the value inserted
+    /// before the caret instead. Returns `false` if the register was
+    /// empty.
+    ///
+    /// [distributed]: registers::distribute
+    pub fn paste_from(mut self, name: char) -> bool {
+        let Some(values) = registers::get(name) else {
+            return false;
+        };
+
+        let len = self.widget.text().selections().len();
+        let mut values = registers::distribute(&values, len).into_iter();
+        while let Some(mut c) = self.next() {
+            if let Some(value) = values.next() {
+                c.replace(value);
+            }
+        }
+
+        true
+    }
+
+    /// The contents of every [`Selection`]
+    fn contents(&self) -> Vec<String> {
+        let text = self.widget.text();
+        text.selections()
+            .iter()
+            .map(|(sel, _)| text.strs(sel.range(text)).unwrap().to_string())
+            .collect()
+    }
+
+    /// Keeps or removes [`Selection`]s that contain a match
+    fn retain_matching(mut self, pat: &str, keep: bool) -> Result<(), Box<regex_syntax::Error>> {
+        Text::new().matches(pat, ..)?;
+
+        while let Some(c) = self.next() {
+            let [start, end] = c.range();
+            let has_match = c
+                .text()
+                .search_fwd(pat, start..end)
+                .unwrap()
+                .next()
+                .is_some();
+            if has_match != keep {
+                c.destroy();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` on every [`Cursor`], from the first one
+    ///
+    /// Also passes the index of the [`Cursor`] among those whose
+    /// carets are on the same line.
+    fn for_each_on_line(&mut self, mut f: impl FnMut(Cursor<'_, W, A, S>, usize)) {
+        self.next_i.set(0);
+
+        let mut last_line = None;
+        let mut i = 0;
+        while let Some(c) = self.next() {
+            let line = c.caret().line();
+            i = if last_line == Some(line) { i + 1 } else { 0 };
+            last_line = Some(line);
+            f(c, i);
+        }
+    }
 }
 
 impl<'a, 'lend, W: Widget<A::Ui> + ?Sized, A: Area, S> Lending<'lend> for Cursors<'a, W, A, S> {
@@ -789,11 +1147,38 @@ impl<'a, W: Widget<A::Ui> + ?Sized, A: Area, S> Lender for Cursors<'a, W, A, S>
             self.widget,
             self.area,
             Some(self.next_i.clone()),
+            self.edit_denied,
             &mut self.inc_searcher,
         ))
     }
 }
 
+/// The parts of a range that are in between the matches within it
+///
+/// Returns [`None`] if there were no matches, and an empty list if
+/// the whole range was matched.
+fn segments_between(
+    [start, end]: [Point; 2],
+    matches: impl Iterator<Item = [Point; 2]>,
+) -> Option<Vec<Range<Point>>> {
+    let mut segments = Vec::new();
+    let mut from = start;
+    for [m0, m1] in matches {
+        if m0 > from {
+            segments.push(from..m0);
+        }
+        from = m1;
+    }
+
+    if from == start {
+        return None;
+    } else if from < end {
+        segments.push(from..end);
+    }
+
+    Some(segments)
+}
+
 /// One or two [`Point`]s
 pub trait PointOrPoints {
     /// Internal movement function for monomorphization
@@ -881,3 +1266,33 @@ impl PointOrPoints for RangeFull {
             .move_to(cursor.widget.text().len(), cursor.widget.text());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::segments_between;
+    use crate::text::Text;
+
+    /// The strings in between matches of a pattern in a range
+    fn split(text: &str, pat: &str, range: std::ops::Range<usize>) -> Option<Vec<String>> {
+        let text = Text::from(text);
+        let [start, end] = [range.start, range.end].map(|b| text.point_at_byte(b));
+        let matches = text.search_fwd(pat, start..end).unwrap();
+
+        let segments = segments_between([start, end], matches)?;
+        let strs = segments.into_iter().map(|range| text.strs(range).unwrap());
+        Some(strs.map(|strs| strs.to_string()).collect())
+    }
+
+    #[test]
+    fn split_on_matches() {
+        assert_eq!(split("a, b, c", ", ", 0..7).unwrap(), ["a", "b", "c"]);
+        assert_eq!(split("a, b, c", ", ", 2..7).unwrap(), ["b", "c"]);
+        assert_eq!(split(", a,, b, ", ",", 0..9).unwrap(), [" a", " b", " "]);
+    }
+
+    #[test]
+    fn split_without_matches_or_leftovers() {
+        assert_eq!(split("abc", ",", 0..3), None);
+        assert_eq!(split(",,,", ",", 0..3).unwrap(), Vec::<String>::new());
+    }
+}
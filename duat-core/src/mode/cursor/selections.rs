@@ -5,7 +5,7 @@ use gapbuf::{GapBuffer, gap_buffer};
 pub use self::cursor::{Selection, VPoint};
 use crate::{
     add_shifts, merging_range_by_guess_and_lazy_shift,
-    text::{Change, Point, TextRange},
+    text::{Bytes, Change, Point, TextRange},
 };
 
 /// The list of [`Selection`]s in a [`Text`]
@@ -35,7 +35,7 @@ use crate::{
 pub struct Selections {
     buf: GapBuffer<Selection>,
     main_i: usize,
-    shift_state: Cell<(usize, [i32; 3])>,
+    shift_state: Cell<(usize, [isize; 3])>,
 }
 
 impl Selections {
@@ -97,6 +97,63 @@ impl Selections {
         self.main_i = 0;
     }
 
+    /// Adds the [`Selection`]s of another [`Selections`]
+    ///
+    /// Just like when editing, the [`Selection`]s that intersect will
+    /// be merged into one. The main [`Selection`] is kept.
+    pub fn union(&mut self, other: &Selections) {
+        for (i, (selection, _)) in other.iter().enumerate() {
+            let selection = Selection::new(selection.caret(), selection.anchor());
+            self.insert(i, selection, false);
+        }
+    }
+
+    /// Keeps only the parts that intersect with another
+    /// [`Selections`]
+    ///
+    /// Each [`Selection`] is split into the parts that overlap with
+    /// the [`Selection`]s of `other`. The [`Selection`]s are treated
+    /// as inclusive, so a [`Selection`] without an anchor still
+    /// covers the character under its caret.
+    ///
+    /// Returns `false` if nothing intersected, in which case this
+    /// [`Selections`] is left unchanged.
+    pub fn intersect(&mut self, other: &Selections, bytes: &Bytes) -> bool {
+        let mut new = Selections::new_empty();
+        let mut main_was_set = false;
+
+        for (selection, is_main) in self.iter() {
+            let [s0, s1] = selection.point_range(bytes);
+            let caret_on_start = selection.anchor().is_some_and(|a| a > selection.caret());
+
+            for (_, other_sel, _) in other.iter_within(s0..s1) {
+                let [o0, o1] = other_sel.point_range(bytes);
+                let (start, end) = (s0.max(o0), s1.min(o1));
+                if start >= end {
+                    continue;
+                }
+
+                let (last, _) = bytes.chars_rev(..end).unwrap().next().unwrap();
+                let selection = match (caret_on_start, start < last) {
+                    (_, false) => Selection::new(start, None),
+                    (true, true) => Selection::new(start, Some(last)),
+                    (false, true) => Selection::new(last, Some(start)),
+                };
+
+                let main = is_main && !main_was_set;
+                main_was_set |= main;
+                new.insert(new.len(), selection, main);
+            }
+        }
+
+        if new.is_empty() {
+            false
+        } else {
+            *self = new;
+            true
+        }
+    }
+
     ////////// Querying functions
 
     /// Gets the main [`Selection`], if there is one
@@ -169,9 +226,7 @@ impl Selections {
         let m_range = merging_range_by_guess_and_lazy_shift(
             (&self.buf, self.buf.len()),
             (0, [range.start, range.end]),
-            (shift_from, shift[0], 0, |byte, shift| {
-                (byte as i32 + shift) as usize
-            }),
+            (shift_from, shift[0], 0, usize::wrapping_add_signed),
             (
                 |sel: &Selection| sel.start().byte(),
                 |sel: &Selection| sel.end_excl().byte(),
@@ -630,7 +685,7 @@ mod cursor {
 
         /// Assumes tha both parts of the cursor are ahead of the
         /// shift
-        pub(crate) fn shift_by(&self, shift: [i32; 3]) {
+        pub(crate) fn shift_by(&self, shift: [isize; 3]) {
             let shifted_caret = self.caret().shift_by(shift);
             self.caret.set(LazyVPoint::Unknown(shifted_caret));
             if let Some(anchor) = self.anchor.get() {
@@ -1044,7 +1099,7 @@ mod cursor {
 
 impl std::fmt::Debug for Selections {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        struct DebugShiftState((usize, [i32; 3]));
+        struct DebugShiftState((usize, [isize; 3]));
         impl std::fmt::Debug for DebugShiftState {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "{:?}", self.0)
@@ -1058,3 +1113,69 @@ impl std::fmt::Debug for Selections {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Selection, Selections};
+    use crate::text::Bytes;
+
+    /// Returns [`Selections`] from `(caret, anchor)` byte pairs
+    ///
+    /// The first [`Selection`] is the main one.
+    fn selections(bytes: &Bytes, list: &[(usize, Option<usize>)]) -> Selections {
+        let mut selections = Selections::new_empty();
+        for (i, &(caret, anchor)) in list.iter().enumerate() {
+            let caret = bytes.point_at_byte(caret);
+            let anchor = anchor.map(|anchor| bytes.point_at_byte(anchor));
+            selections.insert(i, Selection::new(caret, anchor), i == 0);
+        }
+        selections
+    }
+
+    /// The inclusive byte ranges of the [`Selections`]
+    fn ranges(selections: &Selections, bytes: &Bytes) -> Vec<std::ops::Range<usize>> {
+        selections.iter().map(|(sel, _)| sel.range(bytes)).collect()
+    }
+
+    #[test]
+    fn union_merges_overlapping_selections() {
+        let bytes = Bytes::new("0123456789\n");
+        let mut sels = selections(&bytes, &[(2, Some(0))]);
+        let other = selections(&bytes, &[(1, Some(4)), (8, Some(7))]);
+
+        sels.union(&other);
+        assert_eq!(ranges(&sels, &bytes), [0..5, 7..9]);
+        assert_eq!(sels.main_index(), 0);
+    }
+
+    #[test]
+    fn intersect_splits_selections() {
+        let bytes = Bytes::new("0123456789\n");
+        let mut sels = selections(&bytes, &[(5, Some(0))]);
+        let other = selections(&bytes, &[(2, Some(3)), (5, Some(7)), (9, None)]);
+
+        assert!(sels.intersect(&other, &bytes));
+        assert_eq!(ranges(&sels, &bytes), [2..4, 5..6]);
+        assert_eq!(sels.main_index(), 0);
+
+        // The intersections keep the direction of the original.
+        let sel = sels.get(0).unwrap();
+        assert_eq!(sel.caret().byte(), 3);
+        assert_eq!(sel.anchor().map(|anchor| anchor.byte()), Some(2));
+        assert!(sels.get(1).unwrap().anchor().is_none());
+
+        let mut sels = selections(&bytes, &[(0, Some(5))]);
+        assert!(sels.intersect(&other, &bytes));
+        assert_eq!(sels.get(0).unwrap().caret().byte(), 2);
+    }
+
+    #[test]
+    fn intersect_without_overlap_does_nothing() {
+        let bytes = Bytes::new("0123456789\n");
+        let mut sels = selections(&bytes, &[(3, Some(1)), (6, None)]);
+        let other = selections(&bytes, &[(4, Some(5)), (8, None)]);
+
+        assert!(!sels.intersect(&other, &bytes));
+        assert_eq!(ranges(&sels, &bytes), [1..4, 6..7]);
+    }
+}
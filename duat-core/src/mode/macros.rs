@@ -0,0 +1,319 @@
+//! Recording and replaying of macros
+//!
+//! A macro is a sequence of [`KeyEvent`]s, recorded as they are sent
+//! to the active [`Mode`], which can later be replayed. Macros are
+//! stored in [`registers`], as a single value with the [keys] written
+//! out like in [`map`], so they can also be pasted, edited and yanked
+//! back into a register.
+//!
+//! The recorded macros are stored in Duat's cache when it closes or
+//! reloads, and are loaded back the first time they are needed.
+//!
+//! [keys]: KeyEvent
+//! [`Mode`]: super::Mode
+//! [`map`]: super::map
+use std::{
+    collections::HashMap,
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use bincode::{Decode, Encode};
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::{Mode, keys_to_string, send_keys_to, str_to_keys};
+use crate::{
+    context::{self, Handle},
+    data::Pass,
+    file::File,
+    hook::{self, ConfigUnloaded, KeysSent},
+    registers,
+    ui::Ui,
+};
+
+static MACROS: LazyLock<Mutex<Macros>> = LazyLock::new(|| {
+    hook::add_no_alias::<ConfigUnloaded>(|_, _| {
+        let recorded = MACROS.lock().unwrap().recorded.clone();
+        if let Err(err) = context::store_global(recorded) {
+            context::error!("Couldn't store the macros: {err}");
+        }
+    });
+
+    hook::add_no_alias::<KeysSent>(|_, keys| {
+        let mut macros = MACROS.lock().unwrap();
+        let Some(recording) = macros.recording.as_mut() else {
+            return;
+        };
+
+        // This batch was sent before the recording started.
+        if recording.skip_batch {
+            recording.skip_batch = false;
+            if !recording.is_stopping {
+                return;
+            }
+        } else if recording.is_stopping {
+            // The last key is the one that stopped the recording.
+            let len = keys.len().saturating_sub(1);
+            recording.keys.extend_from_slice(&keys[..len]);
+        } else {
+            recording.keys.extend_from_slice(keys);
+        }
+
+        if recording.is_stopping {
+            macros.finish_recording();
+        }
+    });
+
+    let recorded: RecordedMacros = context::load_global().unwrap_or_default();
+    for (name, keys) in recorded.0.iter() {
+        if registers::get(*name).is_none() {
+            registers::set(*name, vec![keys.clone()]);
+        }
+    }
+
+    Mutex::new(Macros { recorded, recording: None })
+});
+static IS_REPLAYING: AtomicBool = AtomicBool::new(false);
+
+struct Macros {
+    recorded: RecordedMacros,
+    recording: Option<Recording>,
+}
+
+impl Macros {
+    /// Stores the recorded keys in their register
+    fn finish_recording(&mut self) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+
+        let (name, keys) = (recording.name, macro_string(&recording.keys));
+        registers::set(name, vec![keys.clone()]);
+        self.recorded.0.insert(name, keys);
+        context::info!("Recorded macro [a]{name}");
+    }
+}
+
+/// A macro that is being recorded
+struct Recording {
+    name: char,
+    keys: Vec<KeyEvent>,
+    skip_batch: bool,
+    is_stopping: bool,
+}
+
+/// The macros recorded by the user, written out as keys
+#[derive(Default, Clone, Encode, Decode)]
+struct RecordedMacros(HashMap<char, String>);
+
+/// An action on macros, which can be [mapped] to a sequence of keys
+///
+/// ```rust
+/// # use duat_core::doc_duat as duat;
+/// # mod kak {
+/// #     use duat_core::prelude::*;
+/// #     #[derive(Clone, Copy, Debug)]
+/// #     pub struct Normal;
+/// #     impl<U: Ui> Mode<U> for Normal {
+/// #         type Widget = File<U>;
+/// #         fn send_key(&mut self, _: &mut Pass, _: KeyEvent, _: Handle<Self::Widget, U>) {}
+/// #     }
+/// # }
+/// setup_duat!(setup);
+/// use duat::prelude::*;
+/// use duat_core::mode::Macro;
+///
+/// fn setup() {
+///     map::<kak::Normal>("Q", Macro::Record('@'));
+///     map::<kak::Normal>("<A-q>", Macro::StopRecording);
+///     map::<kak::Normal>("q", Macro::Replay('@'));
+/// }
+/// ```
+///
+/// [mapped]: super::map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Macro {
+    /// Starts recording a macro into a register
+    Record(char),
+    /// Stops recording the current macro
+    StopRecording,
+    /// Replays the macro in a register
+    ///
+    /// If a [count] was typed before the mapped keys, the macro is
+    /// replayed that many times.
+    ///
+    /// [count]: super::count
+    Replay(char),
+}
+
+impl Macro {
+    /// Executes this action
+    ///
+    /// The `count` is only used when replaying.
+    pub(super) fn execute<U: Ui>(&self, pa: &mut Pass, count: usize) {
+        match *self {
+            Macro::Record(name) => start_recording(name, false),
+            Macro::StopRecording => stop_recording(false),
+            Macro::Replay(name) => replay_now::<U>(pa, name, count),
+        }
+    }
+}
+
+// This implementation exists only to allow Macros to be passed to
+// remaps.
+impl<U: Ui> Mode<U> for Macro {
+    // Doesn't matter
+    type Widget = File<U>;
+
+    fn send_key(&mut self, _: &mut Pass, _: KeyEvent, _: Handle<Self::Widget, U>) {
+        unreachable!("Macros are only meant to be sent as AsGives, turning into actions");
+    }
+
+    fn just_macro(&self) -> Option<Macro> {
+        Some(*self)
+    }
+}
+
+/// Starts recording a macro into a register
+///
+/// Every key sent to a [`Mode`] from this point onwards will be
+/// recorded, until [`stop_recording_macro`] is called. The recording
+/// starts with the keys sent after the current one, so this function
+/// is meant to be called from [`Mode::send_key`]. If you want to
+/// start recording through a mapping, see [`Macro::Record`].
+///
+/// If a macro was already being recorded, it is finished first.
+///
+/// [`Mode`]: super::Mode
+/// [`Mode::send_key`]: super::Mode::send_key
+pub fn record_macro(name: char) {
+    start_recording(name, true);
+}
+
+/// Stops recording the current macro
+///
+/// The key that is currently being sent is not included in the
+/// macro, so this function is meant to be called from
+/// [`Mode::send_key`]. If you want to stop recording through a
+/// mapping, see [`Macro::StopRecording`].
+///
+/// [`Mode::send_key`]: super::Mode::send_key
+pub fn stop_recording_macro() {
+    stop_recording(true);
+}
+
+/// The register of the macro being recorded, if there is one
+pub fn recording_macro() -> Option<char> {
+    let macros = MACROS.lock().unwrap();
+    macros
+        .recording
+        .as_ref()
+        .filter(|recording| !recording.is_stopping)
+        .map(|recording| recording.name)
+}
+
+/// Replays the macro in a register `count` times
+///
+/// The keys are sent directly to the active [`Mode`], without going
+/// through any mappings, since those were already applied when the
+/// macro was recorded.
+///
+/// All changes done to the [`File`] during the replay are grouped in
+/// a single [`Moment`], so they can be reverted with a single undo.
+///
+/// Since keys can't be sent while a [`Mode`] is handling another
+/// key, the replay is queued, and will happen right after the current
+/// key is done being handled.
+///
+/// [`Mode`]: super::Mode
+/// [`File`]: crate::file::File
+/// [`Moment`]: crate::text::Moment
+pub fn replay_macro<U: Ui>(name: char, count: usize) {
+    context::queue(move |pa| replay_now::<U>(pa, name, count));
+}
+
+/// Wether a macro is currently being replayed
+///
+/// While this is `true`, calls to [`Text::new_moment`] on the
+/// [`File`]s that were open when the replay started are ignored, so
+/// the whole replay becomes a single [`Moment`] in each of them.
+///
+/// [`Text::new_moment`]: crate::text::Text::new_moment
+/// [`File`]: crate::file::File
+/// [`Moment`]: crate::text::Moment
+pub fn is_replaying_macro() -> bool {
+    IS_REPLAYING.load(Ordering::Relaxed)
+}
+
+/// Replays a macro, without waiting for the current key to be handled
+fn replay_now<U: Ui>(pa: &mut Pass, name: char, count: usize) {
+    LazyLock::force(&MACROS);
+
+    if IS_REPLAYING.load(Ordering::Relaxed) {
+        context::error!("Can't replay macro [a]{name}[] while replaying another");
+        return;
+    }
+
+    let Some(values) = registers::get(name) else {
+        context::error!("Register [a]{name}[] is empty");
+        return;
+    };
+
+    let keys = str_to_keys(&values.concat());
+    let keys: Vec<KeyEvent> = (0..count.max(1)).flat_map(|_| keys.clone()).collect();
+
+    // Only the Files have their moments grouped, not things like the
+    // PromptLine, which could be used by the macro.
+    let handles = context::windows::<U>().file_handles(pa);
+    for handle in handles.iter() {
+        handle.text_mut(pa).start_replay();
+    }
+
+    IS_REPLAYING.store(true, Ordering::Relaxed);
+    send_keys_to(pa, keys);
+    IS_REPLAYING.store(false, Ordering::Relaxed);
+
+    for handle in handles.iter() {
+        handle.text_mut(pa).end_replay();
+    }
+}
+
+/// Starts recording, skipping the current batch of keys if needed
+fn start_recording(name: char, skip_batch: bool) {
+    let mut macros = MACROS.lock().unwrap();
+    macros.finish_recording();
+    macros.recording = Some(Recording {
+        name,
+        keys: Vec::new(),
+        skip_batch,
+        is_stopping: false,
+    });
+}
+
+/// Stops recording, waiting for the current batch of keys if needed
+fn stop_recording(wait_for_batch: bool) {
+    let mut macros = MACROS.lock().unwrap();
+    if wait_for_batch {
+        if let Some(recording) = macros.recording.as_mut() {
+            recording.is_stopping = true;
+        }
+    } else {
+        macros.finish_recording();
+    }
+}
+
+/// Writes out the keys of a macro, so they can be read back by
+/// [`str_to_keys`]
+fn macro_string(keys: &[KeyEvent]) -> String {
+    keys.iter()
+        .map(|key| {
+            if key.code == KeyCode::Char('<') && key.modifiers.is_empty() {
+                String::from("<lt>")
+            } else {
+                keys_to_string(std::slice::from_ref(key))
+            }
+        })
+        .collect()
+}
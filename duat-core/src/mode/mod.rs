@@ -22,13 +22,17 @@
 use core::str;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-pub use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+pub use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
 
 /// Key modifiers, like Shift, Alt, Super, Shift + Alt, etc
 pub type KeyMod = crossterm::event::KeyModifiers;
 
 pub use self::{
-    cursor::{Cursor, Cursors, PointOrPoints, Selection, Selections, VPoint},
+    cursor::{Cursor, Cursors, Marks, PointOrPoints, Selection, Selections, VPoint},
+    macros::*,
+    mouse::*,
     remap::*,
     switch::*,
 };
@@ -40,6 +44,8 @@ use crate::{
 };
 
 mod cursor;
+mod macros;
+mod mouse;
 mod remap;
 mod switch;
 
@@ -445,11 +451,85 @@ pub trait Mode<U: Ui>: Sized + Clone + Send + 'static {
     /// [`before_exit`]: Mode::before_exit
     fn before_exit(&mut self, pa: &mut Pass, handle: Handle<Self::Widget, U>) {}
 
+    /// The digit that a [`KeyEvent`] adds to the count prefix, if any
+    ///
+    /// If this returns [`Some`], the key is not sent to this
+    /// [`Mode`], and is instead added to the count, which can be
+    /// retrieved with [`mode::count`] when the next key is sent. A
+    /// `0` is only added if other digits came before it, so it can
+    /// still be used as a regular key.
+    ///
+    /// By default, [`Mode`]s don't take counts.
+    ///
+    /// ```rust
+    /// # use duat_core::prelude::*;
+    /// #[derive(Clone)]
+    /// struct Scroller;
+    ///
+    /// impl<U: Ui> Mode<U> for Scroller {
+    ///     type Widget = File<U>;
+    ///
+    ///     fn send_key(&mut self, pa: &mut Pass, key: KeyEvent, handle: Handle<File<U>, U>) {
+    ///         if let key!(KeyCode::Char('j')) = key {
+    ///             let count = mode::count().unwrap_or(1);
+    ///             handle.scroll_ver(pa, count as i32);
+    ///         }
+    ///     }
+    ///
+    ///     fn count_digit(&self, key: KeyEvent) -> Option<u32> {
+    ///         match key {
+    ///             key!(KeyCode::Char(char)) => char.to_digit(10),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`mode::count`]: count
+    fn count_digit(&self, key: KeyEvent) -> Option<u32> {
+        None
+    }
+
+    /// Sends pasted text to this [`Mode`]
+    ///
+    /// When the [`Ui`] supports it, pasted text arrives all at once,
+    /// instead of as a sequence of [`KeyEvent`]s, so it isn't
+    /// affected by [mappings] or things like automatic indentation.
+    ///
+    /// The text's line endings are normalized to `'\n'`, and, by
+    /// default, it is inserted before every caret, as a single
+    /// [`Moment`], unless the [`Text`] is [read-only].
+    ///
+    /// [mappings]: map
+    /// [`Moment`]: crate::text::Moment
+    /// [`Text`]: crate::text::Text
+    /// [read-only]: crate::text::Text::is_read_only
+    fn paste(&mut self, pa: &mut Pass, text: String, handle: Handle<Self::Widget, U>) {
+        if handle.text(pa).is_read_only() {
+            return;
+        }
+
+        let chars = text.chars().count() as i32;
+
+        handle.text_mut(pa).new_moment();
+        handle.edit_all(pa, |mut c| {
+            c.insert(&text);
+            c.move_hor(chars);
+        });
+        handle.text_mut(pa).new_moment();
+    }
+
     /// DO NOT IMPLEMENT THIS FUNCTION, IT IS MEANT FOR `&str` ONLY
     #[doc(hidden)]
     fn just_keys(&self) -> Option<&str> {
         None
     }
+
+    /// DO NOT IMPLEMENT THIS FUNCTION, IT IS MEANT FOR [`Macro`] ONLY
+    #[doc(hidden)]
+    fn just_macro(&self) -> Option<Macro> {
+        None
+    }
 }
 
 // This implementation exists only to allow &strs to be passed to
@@ -0,0 +1,247 @@
+//! Routing of [`MouseEvent`]s to [`Widget`]s
+//!
+//! Unlike [keys], which are sent to the active [`Mode`], mouse events
+//! are sent to the [`Widget`] under the pointer, through
+//! [`Widget::on_mouse_event`]. When dragging, the events keep going
+//! to the [`Widget`] where the button was pressed, even if the
+//! pointer leaves it.
+//!
+//! [keys]: super::KeyEvent
+//! [`Mode`]: super::Mode
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+use super::reset_to;
+use crate::{
+    context::{self, Handle},
+    data::Pass,
+    file::File,
+    hook::{self, MouseEventSent},
+    text::{Point, ToggleId},
+    ui::{Area, AreaId, GetAreaId, Ui, Widget},
+};
+
+/// How many lines are scrolled by each turn of the mouse wheel
+const SCROLL_LINES: i32 = 3;
+/// The maximum time between two clicks for a double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// The [`AreaId`] of the [`Widget`] where a button was pressed
+static PRESSED_ON: Mutex<Option<AreaId>> = Mutex::new(None);
+/// The [`Button`] under the pointer, and the [`AreaId`] of its
+/// [`Widget`]
+///
+/// [`Button`]: crate::text::Button
+static HOVERED: Mutex<Option<(AreaId, ToggleId)>> = Mutex::new(None);
+/// When and where the last left click happened
+static LAST_CLICK: Mutex<Option<(Instant, u16, u16)>> = Mutex::new(None);
+
+/// Sends a [`MouseEvent`] to the [`Widget`] under the pointer
+///
+/// If there is a [`Button`] under the pointer, the event is sent to
+/// it instead, unless it is a scroll.
+///
+/// [`Button`]: crate::text::Button
+pub(crate) fn send_mouse_event<U: Ui>(pa: &mut Pass, event: MouseEvent) {
+    hook::trigger(pa, MouseEventSent(event));
+
+    let (x, y) = (event.column as u32, event.row as u32);
+    let pressed_on = match event.kind {
+        MouseEventKind::Drag(_) | MouseEventKind::Up(_) => *PRESSED_ON.lock().unwrap(),
+        _ => None,
+    };
+
+    let win = context::cur_window();
+    let node = context::windows::<U>()
+        .entries(pa)
+        .filter(|(node_win, ..)| *node_win == win)
+        .map(|(.., node)| node)
+        .filter(|node| match pressed_on {
+            Some(id) => node.area_id() == id,
+            None => node.area(pa).contains_coord(x, y),
+        })
+        // Floating Widgets come last, and are printed on top.
+        .last()
+        .cloned();
+
+    let hovered = node.as_ref().and_then(|node| {
+        let id = node.area(pa).toggle_at_coord(x, y)?;
+        Some((node.area_id(), id))
+    });
+    let prev_hovered = std::mem::replace(&mut *HOVERED.lock().unwrap(), hovered);
+    if prev_hovered != hovered {
+        // Both Widgets need to be reprinted with the new "button.hover".
+        for (area_id, _) in prev_hovered.into_iter().chain(hovered) {
+            let mut entries = context::windows::<U>().entries(pa);
+            if let Some((.., node)) = entries.find(|(.., n)| n.area_id() == area_id) {
+                node.widget().declare_written();
+            }
+        }
+    }
+
+    let Some(node) = node else {
+        return;
+    };
+
+    match event.kind {
+        MouseEventKind::Down(button) => {
+            *PRESSED_ON.lock().unwrap() = Some(node.area_id());
+
+            if button == MouseButton::Left
+                && hovered.is_none()
+                && context::cur_widget::<U>(pa).is_ok_and(|cur| cur.node(pa) != node)
+                && let Some(handle) = node.try_downcast::<File<U>>()
+            {
+                reset_to(handle);
+            }
+        }
+        MouseEventKind::Up(_) => *PRESSED_ON.lock().unwrap() = None,
+        _ => {}
+    }
+
+    let is_scroll = matches!(
+        event.kind,
+        MouseEventKind::ScrollDown
+            | MouseEventKind::ScrollUp
+            | MouseEventKind::ScrollLeft
+            | MouseEventKind::ScrollRight
+    );
+
+    if let Some((_, id)) = hovered
+        && !is_scroll
+    {
+        let (toggle, point) = {
+            let widget = node.widget().read(pa);
+            let text = widget.text();
+            let Some(toggle) = text.get_toggle(id).cloned() else {
+                return;
+            };
+            let points = node
+                .area(pa)
+                .points_at_coord(text, x, y, widget.print_cfg());
+            (toggle, points.map(|(p, _)| p).unwrap_or_default())
+        };
+
+        toggle(point, event.kind);
+    } else {
+        node.on_mouse_event(pa, event);
+    }
+}
+
+/// The [`ToggleId`] of the [`Button`] under the pointer
+///
+/// This is used by [`Ui`]s in order to print the `"button.hover"`
+/// [`Form`] on top of said [`Button`].
+///
+/// [`Button`]: crate::text::Button
+/// [`Form`]: crate::form::Form
+pub fn hovered_button() -> Option<ToggleId> {
+    HOVERED.lock().unwrap().map(|(_, id)| id)
+}
+
+/// Scrolls a [`Widget`] with the mouse wheel
+///
+/// This is the default behaviour of [`Widget::on_mouse_event`].
+/// Every other [`MouseEvent`] is ignored.
+pub fn scroll_with_mouse<W: Widget<U> + ?Sized, U: Ui>(
+    pa: &Pass,
+    handle: &Handle<W, U>,
+    event: MouseEvent,
+) {
+    match event.kind {
+        MouseEventKind::ScrollDown => handle.scroll_ver(pa, SCROLL_LINES),
+        MouseEventKind::ScrollUp => handle.scroll_ver(pa, -SCROLL_LINES),
+        _ => {}
+    }
+}
+
+/// Moves the main [`Selection`] of a [`Widget`] with the mouse
+///
+/// This is what the [`File`] does in [`Widget::on_mouse_event`]:
+///
+/// - A left click removes the extra [`Selection`]s and places the
+///   caret under the pointer;
+/// - Dragging with the left button selects from where the click
+///   happened;
+/// - A double click selects the word under the pointer;
+/// - The mouse wheel scrolls, like in [`scroll_with_mouse`].
+///
+/// [`Selection`]: super::Selection
+pub fn select_with_mouse<W: Widget<U> + ?Sized, U: Ui>(
+    pa: &mut Pass,
+    handle: &Handle<W, U>,
+    event: MouseEvent,
+) {
+    let points = {
+        let widget = handle.read(pa);
+        let (x, y) = (event.column as u32, event.row as u32);
+        handle
+            .area(pa)
+            .points_at_coord(widget.text(), x, y, widget.print_cfg())
+    };
+
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let Some((point, _)) = points else {
+                return;
+            };
+
+            handle.selections_mut(pa).remove_extras();
+
+            if is_double_click(event) {
+                handle.edit_main(pa, |mut c| {
+                    c.unset_anchor();
+                    c.move_to(point);
+
+                    let word_chars = c.cfg().word_chars;
+                    let is_word = |(_, char): &(Point, char)| word_chars.contains(*char);
+                    if !c.chars_fwd().next().is_some_and(|pc| is_word(&pc)) {
+                        return;
+                    }
+
+                    let start = c.chars_rev().take_while(is_word).last();
+                    let end = c.chars_fwd().take_while(is_word).last();
+                    let start = start.map_or(c.caret(), |(p, _)| p);
+                    let end = end.map_or(c.caret(), |(p, _)| p);
+                    c.move_to(start..=end);
+                });
+            } else {
+                handle.edit_main(pa, |mut c| {
+                    c.unset_anchor();
+                    c.move_to(point);
+                });
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            let Some((point, _)) = points else {
+                return;
+            };
+
+            handle.edit_main(pa, |mut c| {
+                c.set_anchor_if_needed();
+                c.move_to(point);
+            });
+        }
+        _ => scroll_with_mouse(pa, handle, event),
+    }
+}
+
+/// Wether a left click is the second one of a double click
+fn is_double_click(event: MouseEvent) -> bool {
+    let mut last_click = LAST_CLICK.lock().unwrap();
+    let now = Instant::now();
+
+    if let Some((instant, column, row)) = last_click.take()
+        && now.duration_since(instant) < DOUBLE_CLICK_TIME
+        && (column, row) == (event.column, event.row)
+    {
+        true
+    } else {
+        *last_click = Some((now, event.column, event.row));
+        false
+    }
+}
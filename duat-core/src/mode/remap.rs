@@ -6,7 +6,7 @@ use std::{
 use crossterm::event::KeyEvent;
 
 pub use self::global::*;
-use super::Mode;
+use super::{Macro, Mode};
 use crate::{
     context,
     data::{Pass, RwData},
@@ -16,7 +16,11 @@ use crate::{
 };
 
 mod global {
-    use std::{cell::RefCell, str::Chars, sync::LazyLock};
+    use std::{
+        cell::RefCell,
+        str::Chars,
+        sync::{LazyLock, Mutex},
+    };
 
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers as KeyMod};
 
@@ -30,6 +34,7 @@ mod global {
     };
 
     static REMAPPER: MainThreadOnly<Remapper> = MainThreadOnly::new(Remapper::new());
+    static COUNT: Mutex<Option<usize>> = Mutex::new(None);
     static SEND_KEY: LazyLock<MainThreadOnly<RefCell<fn(&mut Pass, KeyEvent)>>> =
         LazyLock::new(|| MainThreadOnly::new(RefCell::new(|_, _| {})));
 
@@ -43,7 +48,8 @@ mod global {
     ///
     /// - `<Enter> => Enter`,
     /// - `<Tab> => Tab`,
-    /// - `<Bspc> => Backspace`,
+    /// - `<BTab> => BackTab`,
+    /// - `<Bspc>` or `<BS> => Backspace`,
     /// - `<Del> => Delete`,
     /// - `<Esc> => Esc`,
     /// - `<Up> => Up`,
@@ -56,6 +62,7 @@ mod global {
     /// - `<End> => End`,
     /// - `<Ins> => Insert`,
     /// - `<F{1-12}> => F({1-12})`,
+    /// - `<lt> => <`,
     ///
     /// And the following modifiers are available:
     ///
@@ -66,6 +73,12 @@ mod global {
     /// - `super => Super`,
     /// - `hyper => Hyper`,
     ///
+    /// A count typed before the sequence is not passed on to the
+    /// mapped keys, unless they include a `<count>` placeholder,
+    /// which is replaced by the digits of the count. For example,
+    /// with `map::<Normal>("<C-d>", "<count>0j")`, typing `3<C-d>`
+    /// would send `30j`, while `<C-d>` alone would send `0j`.
+    ///
     /// If another sequence already exists on the same mode, which
     /// would intersect with this one, the new sequence will not be
     /// added.
@@ -114,6 +127,51 @@ mod global {
         remapper.cur_seq.map(pa, |seq| seq.clone())
     }
 
+    /// The count typed before the current key, if there is one
+    ///
+    /// A count is typed as a sequence of digits before some other
+    /// key, on [`Mode`]s that accept them through
+    /// [`Mode::count_digit`]. It is kept until the next key is sent.
+    ///
+    /// If that key completes a [mapped] sequence, the count is
+    /// cleared before the mapped keys are sent, so a mapping like
+    /// `map::<Normal>("<C-d>", "10j")` always moves 10 lines. In
+    /// order to use the typed count, the mapped keys can include a
+    /// `<count>` placeholder.
+    ///
+    /// The count is capped at [`i32::MAX`], so it can always be
+    /// converted to an `i32`, for things like moving vertically.
+    ///
+    /// [mapped]: map
+    pub fn count() -> Option<usize> {
+        *COUNT.lock().unwrap()
+    }
+
+    /// Adds a digit to the count, returns `false` if it was a
+    /// leading `0`
+    pub(in crate::mode) fn add_to_count(_: &mut Pass, digit: u32) -> bool {
+        let mut count = COUNT.lock().unwrap();
+        if digit == 0 && count.is_none() {
+            return false;
+        }
+
+        let new = count.unwrap_or(0).saturating_mul(10);
+        *count = Some(new.saturating_add(digit as usize).min(i32::MAX as usize));
+        // SAFETY: This function takes a Pass.
+        unsafe { REMAPPER.get() }.cur_seq.declare_written();
+        true
+    }
+
+    /// Takes the count, leaving [`None`] in its place
+    pub(in crate::mode) fn take_count(_: &mut Pass) -> Option<usize> {
+        let count = COUNT.lock().unwrap().take();
+        if count.is_some() {
+            // SAFETY: This function takes a Pass.
+            unsafe { REMAPPER.get() }.cur_seq.declare_written();
+        }
+        count
+    }
+
     /// Turns a sequence of [`KeyEvent`]s into a [`Text`]
     pub fn keys_to_text(keys: &[KeyEvent]) -> Builder {
         use crossterm::event::KeyCode::*;
@@ -228,6 +286,8 @@ mod global {
             ("Enter", KeyCode::Enter),
             ("Tab", KeyCode::Tab),
             ("Bspc", KeyCode::Backspace),
+            ("BS", KeyCode::Backspace),
+            ("BTab", KeyCode::BackTab),
             ("Del", KeyCode::Delete),
             ("Esc", KeyCode::Esc),
             ("Up", KeyCode::Up),
@@ -251,6 +311,7 @@ mod global {
             ("F10", KeyCode::F(10)),
             ("F11", KeyCode::F(11)),
             ("F12", KeyCode::F(12)),
+            ("lt", KeyCode::Char('<')),
         ];
         const MODS: &[(&str, KeyMod)] = &[
             ("C", KeyMod::CONTROL),
@@ -353,7 +414,9 @@ mod global {
     impl<M: Mode<U>, U: Ui> AsGives<U> for M {
         fn into_gives(self) -> Gives {
             if let Some(keys) = self.just_keys() {
-                Gives::Taggers(str_to_keys(keys))
+                Gives::Taggers(keys.split("<count>").map(str_to_keys).collect())
+            } else if let Some(action) = self.just_macro() {
+                Gives::Macro(action)
             } else {
                 Gives::Mode(Box::new(move || crate::mode::set(self.clone())))
             }
@@ -464,8 +527,8 @@ impl Remapper {
                     clear_cur_seq(pa);
 
                     match &remap.gives {
-                        Gives::Taggers(keys) => {
-                            let keys = keys.clone();
+                        Gives::Taggers(segments) => {
+                            let keys = keys_with_count(pa, segments);
                             // Lock dropped here, before any .awaits
                             mode::send_keys_to(pa, keys)
                         }
@@ -475,6 +538,10 @@ impl Remapper {
                                 mode_fn(pa);
                             }
                         }
+                        Gives::Macro(action) => {
+                            let count = super::take_count(pa);
+                            action.execute::<U>(pa, count.unwrap_or(1));
+                        }
                     }
                 } else if remap.is_alias {
                     remapper.cur_seq.write(pa).1 = true;
@@ -520,8 +587,21 @@ impl Remap {
 ///
 #[doc(hidden)]
 pub enum Gives {
-    Taggers(Vec<KeyEvent>),
+    /// Keys, split on every `<count>` placeholder
+    Taggers(Vec<Vec<KeyEvent>>),
     Mode(Box<dyn Fn()>),
+    Macro(Macro),
+}
+
+/// Joins the segments of mapped keys, taking the typed count
+///
+/// The typed count is only sent through the `<count>` placeholders
+/// between the segments, so it doesn't affect the mapped keys
+/// otherwise.
+fn keys_with_count(pa: &mut Pass, segments: &[Vec<KeyEvent>]) -> Vec<KeyEvent> {
+    let count = super::take_count(pa);
+    let count = count.map(|count| str_to_keys(&count.to_string()));
+    segments.join(count.as_deref().unwrap_or(&[]))
 }
 
 fn remove_alias_and<U: Ui>(pa: &mut Pass, f: impl FnOnce(&mut dyn Widget<U>, usize)) {
@@ -538,3 +618,53 @@ fn remove_alias_and<U: Ui>(pa: &mut Pass, f: impl FnOnce(&mut dyn Widget<U>, usi
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyEvent;
+
+    use super::{add_to_count, count, keys_with_count, str_to_keys, take_count};
+    use crate::data::Pass;
+
+    /// Sends mapped keys, as if they were given to [`map`]
+    ///
+    /// [`map`]: super::map
+    fn mapped(pa: &mut Pass, keys: &str) -> Vec<KeyEvent> {
+        let segments: Vec<_> = keys.split("<count>").map(str_to_keys).collect();
+        keys_with_count(pa, &segments)
+    }
+
+    // The count is global, so all of it is tested sequentially.
+    #[test]
+    fn count_parsing() {
+        let pa = &mut unsafe { Pass::new() };
+        take_count(pa);
+
+        // A leading 0 isn't part of the count.
+        assert!(!add_to_count(pa, 0));
+        assert_eq!(count(), None);
+        assert!(add_to_count(pa, 1));
+        assert!(add_to_count(pa, 0));
+        assert_eq!(count(), Some(10));
+        assert_eq!(take_count(pa), Some(10));
+        assert_eq!(count(), None);
+
+        // Very large counts saturate at i32::MAX.
+        for _ in 0..30 {
+            add_to_count(pa, 9);
+        }
+        assert_eq!(count(), Some(i32::MAX as usize));
+        assert_eq!(i32::try_from(take_count(pa).unwrap()), Ok(i32::MAX));
+
+        // The count is cleared before sending mapped keys.
+        add_to_count(pa, 5);
+        assert_eq!(mapped(pa, "10j"), str_to_keys("10j"));
+        assert_eq!(count(), None);
+
+        // Unless it is sent through <count>.
+        add_to_count(pa, 5);
+        assert_eq!(mapped(pa, "<count>j"), str_to_keys("5j"));
+        assert_eq!(count(), None);
+        assert_eq!(mapped(pa, "<count>j"), str_to_keys("j"));
+    }
+}
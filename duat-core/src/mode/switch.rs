@@ -13,7 +13,7 @@ use crate::{
     data::Pass,
     duat_name,
     file::File,
-    hook::{self, KeysSent, KeysSentTo, ModeCreated, ModeSwitched},
+    hook::{self, KeysSent, KeysSentTo, ModeCreated, ModeSwitched, Pasted},
     main_thread_only::MainThreadOnly,
     ui::{DuatEvent, Node, Ui, Widget},
 };
@@ -26,6 +26,8 @@ static MODE: LazyLock<MainThreadOnly<RefCell<Box<dyn Any>>>> =
     LazyLock::new(|| MainThreadOnly::new(RefCell::new(Box::new("no mode") as Box<dyn Any>)));
 static BEFORE_EXIT: MainThreadOnly<RefCell<fn(&mut Pass)>> =
     MainThreadOnly::new(RefCell::new(|_| {}));
+static SEND_PASTE: MainThreadOnly<RefCell<fn(&mut Pass, String)>> =
+    MainThreadOnly::new(RefCell::new(|_, _| {}));
 
 type KeyFn = fn(&mut Pass, &mut IntoIter<KeyEvent>) -> Option<ModeFn>;
 type ModeFn = Box<dyn FnOnce(&mut Pass) -> bool + Send>;
@@ -218,6 +220,38 @@ pub(super) fn send_keys_to(pa: &mut Pass, keys: Vec<KeyEvent>) {
     send_keys.replace(Some(sk));
 }
 
+/// Sends pasted text to the active [`Mode`]
+pub(crate) fn send_paste(pa: &mut Pass, text: String) {
+    // The Text only ever has '\n' line endings.
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+    if let Some(set_mode) = take_set_mode_fn(pa) {
+        set_mode(pa);
+    }
+
+    // SAFETY: There is a Pass argument.
+    let send_paste = *unsafe { SEND_PASTE.get() }.borrow();
+    send_paste(pa, text)
+}
+
+/// Static dispatch function that sends pasted text to a [`Mode`]
+fn send_paste_fn<M: Mode<U>, U: Ui>(pa: &mut Pass, text: String) {
+    let handle = context::cur_widget::<U>(pa)
+        .map(|cw| cw.node(pa))
+        .unwrap()
+        .try_downcast()
+        .unwrap();
+
+    {
+        // SAFETY: This function's caller has a Pass argument.
+        let mut mode = unsafe { MODE.get() }.borrow_mut();
+        let mode: &mut M = mode.downcast_mut().unwrap();
+        mode.paste(pa, text.clone(), handle);
+    }
+
+    hook::trigger(pa, Pasted(text));
+}
+
 /// Static dispatch function that sends keys to a [`Mode`]
 #[allow(clippy::await_holding_refcell_ref)]
 fn send_keys_fn<M: Mode<U>, U: Ui>(pa: &mut Pass, keys: &mut IntoIter<KeyEvent>) -> Option<ModeFn> {
@@ -241,7 +275,14 @@ fn send_keys_fn<M: Mode<U>, U: Ui>(pa: &mut Pass, keys: &mut IntoIter<KeyEvent>)
             let Some(key) = keys.next() else { break None };
             sent_keys.push(key);
 
+            if let Some(digit) = mode.count_digit(key)
+                && crate::mode::add_to_count(pa, digit)
+            {
+                continue;
+            }
+
             mode.send_key(pa, key, handle.clone());
+            crate::mode::take_count(pa);
         }
     };
 
@@ -297,6 +338,9 @@ fn set_mode_fn<M: Mode<U>, U: Ui>(pa: &mut Pass, mode: M) -> bool {
         crate::mode::set_send_key::<M, U>();
     }
 
+    // Counts don't carry over to other Modes.
+    crate::mode::take_count(pa);
+
     let new_name = duat_name::<M>();
     let old_name = std::mem::replace(context::raw_mode_name().write(pa), new_name);
 
@@ -308,6 +352,9 @@ fn set_mode_fn<M: Mode<U>, U: Ui>(pa: &mut Pass, mode: M) -> bool {
             .get()
             .replace(Some(|pa, keys| send_keys_fn::<M, U>(pa, keys)));
         BEFORE_EXIT.get().replace(|pa| before_exit_fn::<M, U>(pa));
+        SEND_PASTE
+            .get()
+            .replace(|pa, text| send_paste_fn::<M, U>(pa, text));
     }
 
     true
@@ -0,0 +1,237 @@
+//! Named registers for storing text
+//!
+//! Unlike the [`clipboard`], which holds a single [`String`],
+//! registers hold one value for each [`Selection`] that was yanked.
+//! This means that copying from five [`Selection`]s and pasting into
+//! five [`Selection`]s will map each value to its own [`Selection`].
+//! How the values are mapped in other cases is decided by
+//! [`distribute`].
+//!
+//! Registers are named by a [`char`], and a few of them are special:
+//!
+//! - [`DEFAULT`] (`"`): The register used when no other is given. It
+//!   is the newest entry of the yank ring, and is synced with the
+//!   system [`clipboard`]. If the [`clipboard`] was changed by some
+//!   other program, its contents are used instead.
+//! - [`BLACK_HOLE`] (`_`): A register that is always empty, meant for
+//!   discarding text.
+//!
+//! Every other [`char`] is a regular named register.
+//!
+//! ```rust
+//! # use duat_core::prelude::*;
+//! # use duat_core::registers;
+//! fn swap_with_register<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) {
+//!     let old = registers::get('a');
+//!     handle.edit_iter(pa, |cursors| cursors.yank_to('a'));
+//!
+//!     if let Some(old) = old {
+//!         registers::set('b', old);
+//!         handle.edit_iter(pa, |cursors| cursors.paste_from('b'));
+//!     }
+//! }
+//! ```
+//!
+//! [`clipboard`]: crate::clipboard
+//! [`Selection`]: crate::mode::Selection
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{LazyLock, Mutex},
+};
+
+use crate::clipboard;
+
+/// The register used when no other is given
+pub const DEFAULT: char = '"';
+/// A register that is always empty
+pub const BLACK_HOLE: char = '_';
+/// How many entries are kept in the yank ring
+const RING_LEN: usize = 30;
+
+static REGISTERS: LazyLock<Mutex<Registers>> = LazyLock::new(Mutex::default);
+
+#[derive(Default)]
+struct Registers {
+    named: HashMap<char, Vec<String>>,
+    ring: VecDeque<Vec<String>>,
+}
+
+/// Sets the values of a register
+///
+/// If the register is [`DEFAULT`], the values are pushed to the yank
+/// ring, and are also sent to the system [`clipboard`], one value per
+/// line.
+///
+/// [`clipboard`]: crate::clipboard
+pub fn set(name: char, values: Vec<String>) {
+    let mut registers = REGISTERS.lock().unwrap();
+    match name {
+        BLACK_HOLE => {}
+        DEFAULT => {
+            clipboard::set_text(values.join("\n"));
+            registers.ring.push_front(values);
+            registers.ring.truncate(RING_LEN);
+        }
+        _ => {
+            registers.named.insert(name, values);
+        }
+    }
+}
+
+/// The values of a register, if it has any
+///
+/// If the register is [`DEFAULT`] and the system [`clipboard`] has
+/// something other than what was last yanked, that will be returned
+/// instead, as a single value.
+///
+/// [`clipboard`]: crate::clipboard
+pub fn get(name: char) -> Option<Vec<String>> {
+    let registers = REGISTERS.lock().unwrap();
+    match name {
+        BLACK_HOLE => None,
+        DEFAULT => {
+            let newest = registers.ring.front();
+            if let Some(text) = clipboard::get_text()
+                && !text.is_empty()
+                && newest.is_none_or(|values| values.join("\n") != text)
+            {
+                Some(vec![text])
+            } else {
+                newest.cloned()
+            }
+        }
+        _ => registers.named.get(&name).cloned(),
+    }
+}
+
+/// Removes the values of a register
+///
+/// For the [`DEFAULT`] register, this removes the newest entry of the
+/// yank ring.
+pub fn clear(name: char) {
+    let mut registers = REGISTERS.lock().unwrap();
+    match name {
+        BLACK_HOLE => {}
+        DEFAULT => {
+            registers.ring.pop_front();
+        }
+        _ => {
+            registers.named.remove(&name);
+        }
+    }
+}
+
+/// The names of all registers with values, in order
+///
+/// The [`DEFAULT`] register is included if the yank ring isn't empty.
+pub fn names() -> Vec<char> {
+    let registers = REGISTERS.lock().unwrap();
+    let mut names: Vec<char> = registers.named.keys().copied().collect();
+    if !registers.ring.is_empty() {
+        names.push(DEFAULT);
+    }
+    names.sort_unstable();
+    names
+}
+
+////////// Yank ring functions
+
+/// The entries of the yank ring, from newest to oldest
+///
+/// Every time something is [`set`] to the [`DEFAULT`] register, it
+/// is pushed to the front of the yank ring. Only the last 30 entries
+/// are kept.
+pub fn ring() -> Vec<Vec<String>> {
+    REGISTERS.lock().unwrap().ring.iter().cloned().collect()
+}
+
+/// Rotates the yank ring, changing what the [`DEFAULT`] register has
+///
+/// A positive `by` brings older entries to the front, while a
+/// negative one brings back newer entries. This is useful for going
+/// through the previously yanked values right after pasting.
+pub fn rotate_ring(by: i32) {
+    let mut registers = REGISTERS.lock().unwrap();
+    if registers.ring.is_empty() {
+        return;
+    }
+
+    let by = by.rem_euclid(registers.ring.len() as i32) as usize;
+    registers.ring.rotate_left(by);
+    if let Some(values) = registers.ring.front() {
+        clipboard::set_text(values.join("\n"));
+    }
+}
+
+////////// Pasting functions
+
+/// Distributes the values of a register among `n` [`Selection`]s
+///
+/// - If there are as many values as [`Selection`]s, each one gets its
+///   own value;
+/// - If there is only one [`Selection`], it gets all values, one per
+///   line;
+/// - Otherwise, each [`Selection`] gets the value with the same
+///   index, with the last value being repeated on the remaining
+///   [`Selection`]s.
+///
+/// [`Selection`]: crate::mode::Selection
+pub fn distribute(values: &[String], n: usize) -> Vec<String> {
+    if values.len() == n {
+        values.to_vec()
+    } else if n == 1 {
+        vec![values.join("\n")]
+    } else if let Some(last) = values.last() {
+        (0..n)
+            .map(|i| values.get(i).unwrap_or(last).clone())
+            .collect()
+    } else {
+        vec![String::new(); n]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BLACK_HOLE, clear, distribute, get, set};
+
+    /// Owned values, like the ones that are yanked
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn distribute_to_as_many_selections() {
+        let yanked = values(&["a", "b", "c"]);
+        assert_eq!(distribute(&yanked, 3), yanked);
+    }
+
+    #[test]
+    fn distribute_to_more_selections() {
+        let yanked = values(&["a", "b"]);
+        assert_eq!(distribute(&yanked, 4), values(&["a", "b", "b", "b"]));
+        assert_eq!(distribute(&[], 2), values(&["", ""]));
+    }
+
+    #[test]
+    fn distribute_to_fewer_selections() {
+        let yanked = values(&["a", "b", "c"]);
+        assert_eq!(distribute(&yanked, 2), values(&["a", "b"]));
+        assert_eq!(distribute(&yanked, 1), values(&["a\nb\nc"]));
+        assert_eq!(distribute(&yanked, 0), values(&[]));
+    }
+
+    #[test]
+    fn named_registers() {
+        set('q', values(&["a", "b"]));
+        assert_eq!(get('q'), Some(values(&["a", "b"])));
+
+        set('q', values(&["c"]));
+        assert_eq!(get('q'), Some(values(&["c"])));
+
+        clear('q');
+        assert_eq!(get('q'), None);
+
+        set(BLACK_HOLE, values(&["a"]));
+        assert_eq!(get(BLACK_HOLE), None);
+    }
+}
@@ -8,13 +8,13 @@ use crate::{
     cfg::PrintCfg,
     clipboard::Clipboard,
     cmd,
-    context::{self, Cache, sender},
+    context::{self, Cache, Handle, sender},
     data::Pass,
-    file::{File, FileCfg, PathKind},
+    file::{self, File, FileCfg, FileFormat, FileWatcher, PathKind},
     form,
     hook::{
-        self, ConfigLoaded, ConfigUnloaded, ExitedDuat, FocusedOnDuat, OnFileClose, OnFileReload,
-        UnfocusedFromDuat,
+        self, ConfigLoaded, ConfigUnloaded, ExitedDuat, FileChangedOnDisk, FocusedOnDuat,
+        OnFileClose, OnFileReload, UnfocusedFromDuat,
     },
     mode,
     text::Bytes,
@@ -65,6 +65,7 @@ impl<U: Ui> SessionCfg<U> {
             ms,
             file_cfg: self.file_cfg,
             layout_fn: self.layout_fn,
+            watcher: FileWatcher::new(),
         };
 
         for file in args {
@@ -93,7 +94,8 @@ impl<U: Ui> SessionCfg<U> {
                 let bytes = file_ret.bytes;
                 let pk = file_ret.path_kind;
                 let unsaved = file_ret.has_unsaved_changes;
-                let file_cfg = file_cfg.clone().take_from_prev(bytes, pk, unsaved);
+                let format = file_ret.format;
+                let file_cfg = file_cfg.clone().take_from_prev(bytes, pk, unsaved, format);
                 (file_cfg, file_ret.is_active)
             });
             (i, cfgs)
@@ -103,6 +105,7 @@ impl<U: Ui> SessionCfg<U> {
             ms,
             file_cfg: self.file_cfg,
             layout_fn: self.layout_fn,
+            watcher: FileWatcher::new(),
         };
 
         let mut hasnt_set_cur = true;
@@ -144,12 +147,13 @@ pub struct Session<U: Ui> {
     ms: &'static U::MetaStatics,
     file_cfg: FileCfg<U>,
     layout_fn: Box<dyn Fn() -> Box<dyn Layout<U> + 'static>>,
+    watcher: FileWatcher,
 }
 
 impl<U: Ui> Session<U> {
     /// Start the application, initiating a read/response loop.
     pub fn start(
-        self,
+        mut self,
         duat_rx: mpsc::Receiver<DuatEvent>,
     ) -> (
         Vec<Vec<FileRet>>,
@@ -180,6 +184,8 @@ impl<U: Ui> Session<U> {
         }
 
         U::flush_layout(self.ms);
+        let mut files_changed = context::windows::<U>().files_checker();
+        self.watch_files(wins_pa);
 
         let mut reload_instant = None;
         let mut reprint_screen = false;
@@ -197,6 +203,8 @@ impl<U: Ui> Session<U> {
                 idle_count = 0;
                 match event {
                     DuatEvent::Tagger(key) => mode::send_key(pa, key),
+                    DuatEvent::Mouse(event) => mode::send_mouse_event::<U>(pa, event),
+                    DuatEvent::Paste(text) => mode::send_paste(pa, text),
                     DuatEvent::QueuedFunction(f) => f(pa),
                     DuatEvent::Resized | DuatEvent::FormChange => {
                         reprint_screen = true;
@@ -214,6 +222,7 @@ impl<U: Ui> Session<U> {
                         context::set_cur_window(win);
                         U::switch_window(self.ms, win);
                     }
+                    DuatEvent::FileChangedOnDisk(path) => self.file_changed_on_disk(pa, path),
                     DuatEvent::FocusedOnDuat => {
                         hook::trigger(pa, FocusedOnDuat(()));
                     }
@@ -224,6 +233,7 @@ impl<U: Ui> Session<U> {
                     DuatEvent::ReloadConfig => {
                         hook::trigger(pa, ConfigUnloaded(()));
                         context::order_reload_or_quit();
+                        self.watcher.stop();
                         wait_for_threads_to_despawn();
 
                         for handle in context::windows::<U>().file_handles(wins_pa) {
@@ -239,9 +249,11 @@ impl<U: Ui> Session<U> {
                         hook::trigger(pa, ConfigUnloaded(()));
                         hook::trigger(pa, ExitedDuat(()));
                         context::order_reload_or_quit();
+                        self.watcher.stop();
                         wait_for_threads_to_despawn();
 
                         for handle in context::windows::<U>().file_handles(wins_pa) {
+                            handle.read(pa).delete_recovery();
                             hook::trigger(pa, OnFileClose((handle, Cache::new())));
                         }
 
@@ -256,6 +268,15 @@ impl<U: Ui> Session<U> {
                 reprint_screen = false;
                 continue;
             } else {
+                if files_changed(wins_pa) {
+                    self.watch_files(wins_pa);
+                }
+                for handle in context::windows::<U>().file_handles(wins_pa) {
+                    // Writing would make dependents of the File update.
+                    if handle.read(pa).needs_recovery() {
+                        handle.write(pa).store_recovery();
+                    }
+                }
                 idle_count += 1;
             }
 
@@ -294,9 +315,10 @@ impl<U: Ui> Session<U> {
                     let has_unsaved_changes = text.has_unsaved_changes();
                     let bytes = text.take_bytes();
                     let pk = file.path_kind();
+                    let format = file.format();
                     let is_active = area.is_active();
 
-                    FileRet::new(bytes, pk, is_active, has_unsaved_changes)
+                    FileRet::new(bytes, pk, is_active, has_unsaved_changes, format)
                 });
                 files.collect()
             })
@@ -352,6 +374,46 @@ impl<U: Ui> Session<U> {
             self.file_cfg.clone(),
         );
     }
+
+    fn watch_files(&mut self, pa: &Pass) {
+        let paths = context::windows::<U>()
+            .file_handles(pa)
+            .filter_map(|handle| match handle.read(pa).path_kind() {
+                PathKind::SetExists(path) => Some(path),
+                _ => None,
+            });
+        self.watcher.set_files(paths);
+    }
+
+    fn file_changed_on_disk(&mut self, pa: &mut Pass, path: PathBuf) {
+        let Ok(raw) = std::fs::read(&path) else {
+            return;
+        };
+        if !self.watcher.is_new_content(&path, &raw) {
+            return;
+        }
+
+        let path_kind = PathKind::SetExists(path.clone());
+        let is_file = |handle: &Handle<File<U>, U>| handle.read(pa).path_kind() == path_kind;
+        let Some(handle) = context::windows::<U>().file_handles(pa).find(is_file) else {
+            return;
+        };
+
+        if handle.read(pa).matches_disk(&raw) {
+            return;
+        }
+
+        hook::trigger(pa, FileChangedOnDisk(handle.clone()));
+
+        // The hook may have already dealt with the changes, and Files with
+        // unsaved changes are left for it to deal with.
+        let file = handle.read(pa);
+        if file.matches_disk(&raw) || file.text().has_unsaved_changes() {
+            return;
+        } else if let Err(err) = file::reload_from_disk(pa, &handle) {
+            context::error!("{err}");
+        }
+    }
 }
 
 fn wait_for_threads_to_despawn() {
@@ -375,15 +437,23 @@ pub struct FileRet {
     path_kind: PathKind,
     is_active: bool,
     has_unsaved_changes: bool,
+    format: FileFormat,
 }
 
 impl FileRet {
-    fn new(bytes: Bytes, path_kind: PathKind, is_active: bool, has_unsaved_changes: bool) -> Self {
+    fn new(
+        bytes: Bytes,
+        path_kind: PathKind,
+        is_active: bool,
+        has_unsaved_changes: bool,
+        format: FileFormat,
+    ) -> Self {
         Self {
             bytes,
             path_kind,
             is_active,
             has_unsaved_changes,
+            format,
         }
     }
 }
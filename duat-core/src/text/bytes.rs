@@ -1,32 +1,67 @@
-use std::{iter::FusedIterator, ops::RangeBounds, str::Utf8Error};
+use std::{
+    iter::FusedIterator,
+    ops::{Index, RangeBounds},
+    str::Utf8Error,
+    sync::Arc,
+};
 
 use gapbuf::GapBuffer;
 use lender::{DoubleEndedLender, ExactSizeLender, Lender, Lending};
+use memmap2::Mmap;
 
 use super::{Point, TextRange, records::Records};
 use crate::cfg::PrintCfg;
 
+/// The distance in bytes between the records added on [`Bytes::new`]
+const RECORD_STEP: usize = 1 << 16;
+
 /// The bytes of a [`Text`], encoded in UTF-8
 ///
 /// [`Text`]: super::Text
 #[derive(Default, Clone)]
 pub struct Bytes {
-    buf: GapBuffer<u8>,
+    buf: Buf,
     records: Records,
 }
 
 impl Bytes {
     /// Returns a new instance of a [`Buffer`]
+    ///
+    /// While counting the chars and lines, this will also add a
+    /// record every [`RECORD_STEP`] bytes, so that [`Point`]s can be
+    /// found quickly, even in very large texts.
     pub(crate) fn new(string: &str) -> Self {
-        let buf = GapBuffer::from_iter(string.bytes());
+        let buf = Buf::Owned(GapBuffer::from_iter(string.bytes()));
+        Self { buf, records: records_of(string) }
+    }
 
-        let len = buf.len();
-        let chars = string.chars().count();
-        let lines = buf.iter().filter(|b| **b == b'\n').count();
-        Self {
-            buf,
-            records: Records::new([len, chars, lines]),
-        }
+    /// Returns a new instance of [`Bytes`], backed by a memory map
+    ///
+    /// Nothing is copied from the map until the first [`Change`] is
+    /// applied, so even files that are larger than the available
+    /// memory can be viewed and searched. If the map doesn't end in a
+    /// `'\n'`, one is added after it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the map isn't valid UTF-8.
+    ///
+    /// [`Change`]: super::Change
+    pub(crate) fn from_map(map: Mmap) -> Result<Self, Utf8Error> {
+        let string = str::from_utf8(&map)?;
+        let mut records = records_of(string);
+
+        let tail: &'static [u8] = if string.ends_with('\n') {
+            b""
+        } else {
+            records.append([1, 1, 1]);
+            b"\n"
+        };
+
+        Ok(Self {
+            buf: Buf::Mapped(Arc::new(map), tail),
+            records,
+        })
     }
 
     ////////// Querying functions
@@ -89,7 +124,7 @@ impl Bytes {
     ///
     /// [`strs`]: Self::strs
     pub fn buffers(&self, range: impl RangeBounds<usize>) -> Buffers<'_> {
-        let (s0, s1) = self.buf.range(range).as_slices();
+        let (s0, s1) = self.buf.range(range);
         Buffers([s0.iter(), s1.iter()])
     }
 
@@ -489,7 +524,7 @@ impl Bytes {
         let start = change.start();
 
         let range = start.byte()..change.taken_end().byte();
-        self.buf.splice(range, edit.bytes());
+        self.buf.to_mut().splice(range, edit.bytes());
 
         let start_rec = [start.byte(), start.char(), start.line()];
         let old_len = [
@@ -509,7 +544,8 @@ impl Bytes {
 
     /// Extends this [`Bytes`] with another
     pub(super) fn extend(&mut self, other: Self) {
-        self.buf.extend(other.buf);
+        let (s0, s1) = other.buf.as_slices();
+        self.buf.to_mut().extend(s0.iter().chain(s1).copied());
         self.records
             .transform(self.records.max(), [0, 0, 0], other.records.max())
     }
@@ -540,6 +576,83 @@ impl Bytes {
     }
 }
 
+/// Where the bytes of [`Bytes`] are stored
+#[derive(Clone)]
+enum Buf {
+    /// Bytes that can be edited in place
+    Owned(GapBuffer<u8>),
+    /// A memory map, followed by the `'\n'` it might be missing
+    ///
+    /// Just like a [`GapBuffer`], this is split in two slices, with
+    /// the gap being at the end of the map.
+    Mapped(Arc<Mmap>, &'static [u8]),
+}
+
+impl Buf {
+    /// The two slices of the [`Buf`]
+    fn as_slices(&self) -> (&[u8], &[u8]) {
+        match self {
+            Buf::Owned(buf) => buf.as_slices(),
+            Buf::Mapped(map, tail) => (&map[..], tail),
+        }
+    }
+
+    /// The two slices of the [`Buf`] in a byte range
+    fn range(&self, range: impl RangeBounds<usize>) -> (&[u8], &[u8]) {
+        match self {
+            Buf::Owned(buf) => buf.range(range).as_slices(),
+            Buf::Mapped(map, tail) => {
+                let (start, end) = crate::get_ends(range, map.len() + tail.len());
+                let r0 = start.min(map.len())..end.min(map.len());
+                let r1 = start.saturating_sub(map.len())..end.saturating_sub(map.len());
+                (&map[r0], &tail[r1])
+            }
+        }
+    }
+
+    /// The position of the gap, where the second slice starts
+    fn gap(&self) -> usize {
+        match self {
+            Buf::Owned(buf) => buf.gap(),
+            Buf::Mapped(map, _) => map.len(),
+        }
+    }
+
+    /// The byte at a given position, if there is one
+    fn get(&self, i: usize) -> Option<&u8> {
+        let (s0, s1) = self.as_slices();
+        s0.get(i).or_else(|| s1.get(i.checked_sub(s0.len())?))
+    }
+
+    /// The [`GapBuffer`] of the [`Buf`], copying the map into one
+    /// if necessary
+    fn to_mut(&mut self) -> &mut GapBuffer<u8> {
+        if let Buf::Mapped(map, tail) = self {
+            let bytes = map.iter().chain(tail.iter()).copied();
+            *self = Buf::Owned(GapBuffer::from_iter(bytes));
+        }
+
+        match self {
+            Buf::Owned(buf) => buf,
+            Buf::Mapped(..) => unreachable!(),
+        }
+    }
+}
+
+impl Default for Buf {
+    fn default() -> Self {
+        Buf::Owned(GapBuffer::new())
+    }
+}
+
+impl Index<usize> for Buf {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
 /// A [`Lender`] over the lines on [`Bytes`]
 ///
 /// The reason for this being a [`Lender`], rather than a regular
@@ -815,6 +928,36 @@ impl PartialEq<String> for Strs<'_> {
     }
 }
 
+/// The [`Records`] of a `str`, with one every [`RECORD_STEP`] bytes
+fn records_of(string: &str) -> Records {
+    let mut checkpoints = Vec::new();
+    let [mut b, mut c, mut l] = [0; 3];
+    while b < string.len() {
+        let mut end = (b + RECORD_STEP).min(string.len());
+        while !string.is_char_boundary(end) {
+            end += 1;
+        }
+
+        let chunk = &string.as_bytes()[b..end];
+        c += chunk
+            .iter()
+            .filter(|byte| !(0x80..0xc0).contains(*byte))
+            .count();
+        l += chunk.iter().filter(|byte| **byte == b'\n').count();
+        b = end;
+        checkpoints.push([b, c, l]);
+    }
+
+    let mut records = Records::new([b, c, l]);
+    // The last checkpoint is just the end of the text.
+    checkpoints.pop();
+    for checkpoint in checkpoints {
+        records.insert(checkpoint);
+    }
+
+    records
+}
+
 /// Given a first byte, determines how many bytes are in this UTF-8
 /// character.
 #[must_use]
@@ -842,3 +985,93 @@ pub const fn utf8_char_width(b: u8) -> usize {
     ];
     UTF8_CHAR_WIDTH[b as usize] as usize
 }
+
+#[cfg(test)]
+mod tests {
+    use memmap2::MmapMut;
+
+    use super::Bytes;
+    use crate::{
+        mode::Selections,
+        text::{Point, Text},
+    };
+
+    /// [`Bytes`] backed by an anonymous memory map
+    fn mapped(string: &str) -> Bytes {
+        let mut map = MmapMut::map_anon(string.len()).unwrap();
+        map.copy_from_slice(string.as_bytes());
+        Bytes::from_map(map.make_read_only().unwrap()).unwrap()
+    }
+
+    /// Lines of a log, spanning a few records
+    fn log() -> String {
+        (0..20_000).map(|i| format!("line {i}\n")).collect()
+    }
+
+    #[test]
+    fn mapped_bytes_find_the_same_points() {
+        let log = log();
+        let (mapped, owned) = (mapped(&log), Bytes::new(&log));
+        assert_eq!(mapped.len(), owned.len());
+
+        for l in [0, 1, 9_999, 15_000, 20_000] {
+            assert_eq!(mapped.point_at_line(l), owned.point_at_line(l));
+        }
+        for b in [0, 70_000, 150_001, log.len()] {
+            assert_eq!(mapped.point_at_byte(b), owned.point_at_byte(b));
+        }
+    }
+
+    #[test]
+    fn mapped_bytes_end_in_a_newline() {
+        let bytes = mapped("ab\ncd");
+        assert_eq!(bytes, "ab\ncd\n");
+        assert_eq!(bytes.len(), Point::from_raw(6, 6, 2));
+
+        let line = [Point::from_raw(3, 3, 1), Point::from_raw(6, 6, 2)];
+        assert_eq!(bytes.points_of_line(1), line);
+        assert_eq!(bytes.point_at_line(2), bytes.len());
+    }
+
+    #[test]
+    fn search_mapped_bytes() {
+        let bytes = mapped(&log());
+
+        let matches: Vec<[Point; 2]> = bytes.search_fwd(r"line 1999\d\n", ..).unwrap().collect();
+        assert_eq!(matches.len(), 10);
+        assert_eq!(matches[0][0], bytes.point_at_line(19_990));
+        assert_eq!(matches[9][1], bytes.len());
+
+        let [start, _] = bytes.search_rev("line 7", ..).unwrap().next().unwrap();
+        assert_eq!(start, bytes.point_at_line(7_999));
+    }
+
+    #[test]
+    fn scroll_through_mapped_bytes() {
+        let text = Text::from_bytes(mapped(&log()), Selections::new_empty(), false);
+        let line_start = text.point_at_line(15_000);
+
+        let below: String = text
+            .iter_fwd(line_start)
+            .filter_map(|item| item.part.as_char())
+            .take(11)
+            .collect();
+        assert_eq!(below, "line 15000\n");
+
+        let mut above: Vec<char> = text
+            .iter_rev(line_start)
+            .filter_map(|item| item.part.as_char())
+            .take(11)
+            .collect();
+        above.reverse();
+        assert_eq!(String::from_iter(above), "line 14999\n");
+    }
+
+    #[test]
+    fn editing_mapped_bytes_copies_them() {
+        let mut text = Text::from_bytes(mapped("ab\ncd"), Selections::new_empty(), false);
+        text.replace_range(3..5, "ef");
+        assert_eq!(*text.bytes(), "ab\nef\n");
+        assert_eq!(text.point_at_line(1), Point::from_raw(3, 3, 1));
+    }
+}
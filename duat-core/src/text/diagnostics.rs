@@ -0,0 +1,328 @@
+//! Messages attached to ranges of a [`Text`]
+//!
+//! Diagnostics are meant to be supplied by linters, compilers,
+//! language servers and the like. Each [`Diagnostic`] has a
+//! [`Severity`], a range, a message and a source, which is the name
+//! of whatever produced it.
+//!
+//! In the [`Text`], a [`Diagnostic`]'s range is underlined, and its
+//! message is shown as a [`Ghost`] at the end of the line where it
+//! starts. Just like [`Tag`]s, [`Diagnostic`]s are shifted by every
+//! [`Change`] to the [`Text`], and they are removed if their range
+//! is removed.
+//!
+//! As an example, a plugin could run `cargo check
+//! --message-format=json`, turn the spans of each message into
+//! [`Diagnostic`]s and add them through [`Text::set_diagnostics`],
+//! with `"cargo"` as the source. Running it again would then replace
+//! all the [`Diagnostic`]s from the previous run.
+//!
+//! [`Text`]: super::Text
+//! [`Text::set_diagnostics`]: super::Text::set_diagnostics
+//! [`Ghost`]: super::Ghost
+//! [`Tag`]: super::Tag
+use std::ops::Range;
+
+use super::{Change, TextRange};
+use crate::form::{self, FormId};
+
+/// The severity of a [`Diagnostic`]
+///
+/// These are ordered from least to most severe, so you can use
+/// [`Iterator::max`] to get the most severe one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// A suggestion, shown with the `"diagnostic.hint"` form
+    Hint,
+    /// Some information, shown with the `"diagnostic.info"` form
+    Info,
+    /// A warning, shown with the `"diagnostic.warning"` form
+    Warning,
+    /// An error, shown with the `"diagnostic.error"` form
+    Error,
+}
+
+impl Severity {
+    /// The [`FormId`] used to underline a [`Diagnostic`]'s range
+    pub(crate) fn form_id(&self) -> FormId {
+        match self {
+            Severity::Hint => form::id_of!("diagnostic.hint"),
+            Severity::Info => form::id_of!("diagnostic.info"),
+            Severity::Warning => form::id_of!("diagnostic.warning"),
+            Severity::Error => form::id_of!("diagnostic.error"),
+        }
+    }
+
+    /// The [`FormId`] used to show a [`Diagnostic`]'s message
+    pub(crate) fn message_form_id(&self) -> FormId {
+        match self {
+            Severity::Hint => form::id_of!("diagnostic.message.hint"),
+            Severity::Info => form::id_of!("diagnostic.message.info"),
+            Severity::Warning => form::id_of!("diagnostic.message.warning"),
+            Severity::Error => form::id_of!("diagnostic.message.error"),
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Hint => "hint",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A message attached to a range of a [`Text`]
+///
+/// You can add these to a [`Text`] through
+/// [`Text::set_diagnostics`]:
+///
+/// ```rust
+/// # use duat_core::prelude::*;
+/// use duat_core::text::{Diagnostic, Severity};
+///
+/// fn report_todos<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) {
+///     let text = handle.text_mut(pa);
+///
+///     let diagnostics: Vec<Diagnostic> = text
+///         .search_fwd("TODO", ..)
+///         .unwrap()
+///         .map(|[start, end]| Diagnostic::new(start..end, Severity::Info, "Something to do"))
+///         .collect();
+///
+///     text.set_diagnostics("todos", diagnostics);
+/// }
+/// ```
+///
+/// [`Text`]: super::Text
+/// [`Text::set_diagnostics`]: super::Text::set_diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    range: Range<usize>,
+    severity: Severity,
+    message: String,
+    source: String,
+}
+
+impl Diagnostic {
+    /// Returns a new [`Diagnostic`]
+    ///
+    /// Its source is set by [`Text::set_diagnostics`].
+    ///
+    /// [`Text::set_diagnostics`]: super::Text::set_diagnostics
+    pub fn new(range: impl TextRange, severity: Severity, message: impl ToString) -> Self {
+        Self {
+            range: range.to_range(usize::MAX),
+            severity,
+            message: message.to_string(),
+            source: String::new(),
+        }
+    }
+
+    /// The range of this [`Diagnostic`], in bytes
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The [`Severity`] of this [`Diagnostic`]
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// The message of this [`Diagnostic`]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// What produced this [`Diagnostic`], like `"rustc"` or
+    /// `"clippy"`
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// The [`Diagnostic`]s of a [`Text`]
+///
+/// These are sorted by their ranges. In order to change them, use
+/// [`Text::set_diagnostics`] and [`Text::clear_diagnostics`], which
+/// will also update how they are shown.
+///
+/// [`Text`]: super::Text
+/// [`Text::set_diagnostics`]: super::Text::set_diagnostics
+/// [`Text::clear_diagnostics`]: super::Text::clear_diagnostics
+#[derive(Default, Debug, Clone)]
+pub struct Diagnostics {
+    list: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// An [`Iterator`] over all [`Diagnostic`]s, sorted by their
+    /// ranges
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Diagnostic> + '_ {
+        self.list.iter()
+    }
+
+    /// The [`Diagnostic`]s whose ranges contain a byte
+    ///
+    /// Empty ranges are considered to contain the byte they are in.
+    pub fn at(&self, b: usize) -> impl Iterator<Item = &Diagnostic> + '_ {
+        self.list
+            .iter()
+            .take_while(move |diag| diag.range.start <= b)
+            .filter(move |diag| diag.range.contains(&b) || diag.range.start == b)
+    }
+
+    /// The first [`Diagnostic`] that starts after a byte
+    pub fn next_after(&self, b: usize) -> Option<&Diagnostic> {
+        self.list.iter().find(|diag| diag.range.start > b)
+    }
+
+    /// The last [`Diagnostic`] that starts before a byte
+    pub fn prev_before(&self, b: usize) -> Option<&Diagnostic> {
+        self.list.iter().rev().find(|diag| diag.range.start < b)
+    }
+
+    /// How many [`Diagnostic`]s of a given [`Severity`] there are
+    pub fn count(&self, severity: Severity) -> usize {
+        self.list
+            .iter()
+            .filter(|diag| diag.severity == severity)
+            .count()
+    }
+
+    /// How many [`Diagnostic`]s there are
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Wether there are no [`Diagnostic`]s
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    ////////// Internal functions
+
+    /// Replaces all [`Diagnostic`]s from a source
+    ///
+    /// The ranges of the new [`Diagnostic`]s are clamped to `max`.
+    pub(super) fn replace(
+        &mut self,
+        source: &str,
+        new: impl IntoIterator<Item = Diagnostic>,
+        max: usize,
+    ) {
+        self.list.retain(|diag| diag.source != source);
+        for diag in new {
+            let range = diag.range.start.min(max)..diag.range.end.min(max);
+            let source = source.to_string();
+            self.list.push(Diagnostic { range, source, ..diag });
+        }
+
+        let key = |diag: &Diagnostic| (diag.range.start, diag.range.end);
+        self.list.sort_by_key(key);
+    }
+
+    /// Removes all [`Diagnostic`]s from a source
+    ///
+    /// Returns `false` if there were none.
+    pub(super) fn clear(&mut self, source: &str) -> bool {
+        let prev_len = self.list.len();
+        self.list.retain(|diag| diag.source != source);
+        self.list.len() != prev_len
+    }
+
+    /// Shifts all [`Diagnostic`]s by a [`Change`]
+    ///
+    /// Text inserted right at the end of a [`Diagnostic`] is not
+    /// added to its range. [`Diagnostic`]s whose ranges were removed
+    /// are dropped, in which case this returns `true`.
+    pub(super) fn apply_change(&mut self, change: Change<&str>) -> bool {
+        let start = change.start().byte();
+        let taken_end = change.taken_end().byte();
+        let added_end = change.added_end().byte();
+
+        let shift = |b: usize| {
+            if b >= taken_end {
+                b - taken_end + added_end
+            } else {
+                b.min(start)
+            }
+        };
+        let is_insertion_at = |b: usize| b == start && b == taken_end;
+
+        let prev_len = self.list.len();
+        self.list.retain(|diag| {
+            start == taken_end || diag.range.start < start || diag.range.end > taken_end
+        });
+
+        for diag in self.list.iter_mut() {
+            let new_start = shift(diag.range.start);
+            let new_end = if is_insertion_at(diag.range.end) {
+                diag.range.end.max(new_start)
+            } else {
+                shift(diag.range.end)
+            };
+            diag.range = new_start..new_end;
+        }
+
+        self.list.len() != prev_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::{Diagnostic, Diagnostics, Severity};
+    use crate::text::{Change, Text};
+
+    /// The ranges of [`Diagnostic`]s after a range of
+    /// `"let x = 10;\n"` is replaced, and wether any were dropped
+    fn shifted(
+        ranges: &[Range<usize>],
+        replaced: Range<usize>,
+        edit: &str,
+    ) -> (Vec<Range<usize>>, bool) {
+        let text = Text::from("let x = 10;\n");
+        let mut diags = Diagnostics::default();
+        let new = ranges
+            .iter()
+            .map(|range| Diagnostic::new(range.clone(), Severity::Error, "oops"));
+        diags.replace("test", new, text.len().byte());
+
+        let points = [replaced.start, replaced.end].map(|b| text.point_at_byte(b));
+        let dropped = diags.apply_change(Change::new(edit, points, &text).as_ref());
+        (diags.iter().map(Diagnostic::range).collect(), dropped)
+    }
+
+    #[test]
+    fn diagnostics_shift_with_edits_around_them() {
+        assert_eq!(shifted(&[4..5], 0..0, "xx"), (vec![6..7], false));
+        assert_eq!(shifted(&[4..5], 4..4, "y"), (vec![5..6], false));
+        assert_eq!(shifted(&[4..5], 8..10, "1"), (vec![4..5], false));
+    }
+
+    #[test]
+    fn diagnostics_shift_with_edits_inside_them() {
+        assert_eq!(shifted(&[8..10], 9..9, "00"), (vec![8..12], false));
+        assert_eq!(shifted(&[0..11], 4..5, "yz"), (vec![0..12], false));
+    }
+
+    #[test]
+    fn diagnostics_shift_with_edits_touching_them() {
+        // Inserting at the end doesn't add to the range.
+        assert_eq!(shifted(&[4..5], 5..5, "y"), (vec![4..5], false));
+        assert_eq!(shifted(&[10..10], 10..10, " "), (vec![11..11], false));
+        assert_eq!(shifted(&[4..9], 2..6, ""), (vec![2..5], false));
+        assert_eq!(shifted(&[4..9], 6..11, ""), (vec![4..6], false));
+    }
+
+    #[test]
+    fn diagnostics_are_dropped_with_edits_covering_them() {
+        assert_eq!(shifted(&[4..5], 4..5, "y"), (vec![], true));
+        assert_eq!(shifted(&[4..5, 8..10], 3..6, ""), (vec![5..7], true));
+    }
+}
@@ -0,0 +1,268 @@
+//! Folding of lines in a [`Text`]
+//!
+//! A fold hides a range of lines, except for the first one, which is
+//! followed by a [`Ghost`] showing how many lines were hidden. Folds
+//! are made out of [`Conceal`] and [`Ghost`] [`Tag`]s, so they
+//! follow [`Change`]s to the [`Text`] just like any other [`Tag`],
+//! and can be nested within one another.
+//!
+//! Which ranges _can_ be folded is decided by fold providers, which
+//! are usually [`Parser`]s that add ranges to the [`Folds`] of the
+//! [`File`], through [`FileParts::folds`]. These could be based on
+//! indentation, on a syntax tree, or anything else really.
+//!
+//! [`Ghost`]: super::Ghost
+//! [`Conceal`]: super::Conceal
+//! [`Tag`]: super::Tag
+//! [`Text`]: super::Text
+//! [`Parser`]: crate::file::Parser
+//! [`File`]: crate::file::File
+//! [`FileParts::folds`]: crate::file::FileParts::folds
+use std::ops::Range;
+
+use bincode::{Decode, Encode};
+
+use super::{Change, TextRange};
+
+/// The foldable ranges of a [`Text`]
+///
+/// These are supplied by fold providers, and are used by
+/// [`Text::fold_at`] in order to figure out what to fold. Just like
+/// [`Selections`], the ranges are shifted by every [`Change`] to the
+/// [`Text`].
+///
+/// Folds are done by lines, so a range that doesn't span multiple
+/// lines can't be folded. Here's a fold provider that makes every
+/// indented block foldable:
+///
+/// ```rust
+/// use std::ops::Range;
+///
+/// use duat_core::prelude::*;
+///
+/// struct IndentFolds;
+///
+/// impl<U: Ui> Parser<U> for IndentFolds {
+///     fn update_range(&mut self, mut parts: FileParts<U>, _: Option<Range<Point>>) {
+///         parts.folds.clear();
+///
+///         // The indentation and first line of every open block.
+///         let mut blocks: Vec<(usize, usize)> = Vec::new();
+///         let mut last = 0;
+///         let mut lines = parts.bytes.lines(..);
+///
+///         while let Some((l, line)) = lines.next() {
+///             let trimmed = line.trim_start();
+///             if trimmed.is_empty() {
+///                 continue;
+///             }
+///             let indent = line.len() - trimmed.len();
+///
+///             while let Some(&(block_indent, start)) = blocks.last()
+///                 && block_indent >= indent
+///             {
+///                 blocks.pop();
+///                 if last > start {
+///                     let end = parts.bytes.point_at_line(last + 1);
+///                     parts.folds.add(parts.bytes.point_at_line(start)..end);
+///                 }
+///             }
+///
+///             blocks.push((indent, l));
+///             last = l;
+///         }
+///
+///         for (_, start) in blocks.into_iter().filter(|(_, start)| last > *start) {
+///             let end = parts.bytes.point_at_line(last + 1);
+///             parts.folds.add(parts.bytes.point_at_line(start)..end);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Text`]: super::Text
+/// [`Text::fold_at`]: super::Text::fold_at
+/// [`Selections`]: crate::mode::Selections
+#[derive(Default, Clone)]
+pub struct Folds {
+    foldable: Vec<Range<usize>>,
+    /// The lines of the folded ranges, only used when caching
+    closed: Vec<Range<usize>>,
+    content_hash: Option<u64>,
+}
+
+impl Folds {
+    /// Adds a foldable range
+    ///
+    /// If the range was already added, nothing happens.
+    pub fn add(&mut self, range: impl TextRange) {
+        let range = range.to_range(usize::MAX);
+        let key = |r: &Range<usize>| (r.start, std::cmp::Reverse(r.end));
+
+        let i = self.foldable.partition_point(|r| key(r) < key(&range));
+        if self.foldable.get(i) != Some(&range) {
+            self.foldable.insert(i, range);
+        }
+    }
+
+    /// Removes the foldable ranges that start within a range
+    ///
+    /// This doesn't unfold anything that was already folded.
+    pub fn remove(&mut self, within: impl TextRange) {
+        let within = within.to_range(usize::MAX);
+        self.foldable.retain(|r| !within.contains(&r.start));
+    }
+
+    /// Removes all foldable ranges
+    ///
+    /// This doesn't unfold anything that was already folded.
+    pub fn clear(&mut self) {
+        self.foldable.clear();
+    }
+
+    /// The foldable ranges, in bytes
+    ///
+    /// They are sorted by their starts, with the outer ranges coming
+    /// before the inner ones.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Range<usize>> + '_ {
+        self.foldable.iter().cloned()
+    }
+
+    /// Wether there are no foldable ranges
+    pub fn is_empty(&self) -> bool {
+        self.foldable.is_empty()
+    }
+
+    ////////// Internal functions
+
+    /// Shifts all foldable ranges by a [`Change`]
+    ///
+    /// Text inserted right at the end of a range is not added to it.
+    /// Ranges that end up empty are removed.
+    pub(crate) fn apply_change(&mut self, change: Change<&str>) {
+        let start = change.start().byte();
+        let taken_end = change.taken_end().byte();
+        let added_end = change.added_end().byte();
+
+        let shift = |b: usize| {
+            if b >= taken_end {
+                b - taken_end + added_end
+            } else {
+                b.min(start)
+            }
+        };
+        let is_insertion_at = |b: usize| b == start && b == taken_end;
+
+        for range in self.foldable.iter_mut() {
+            let new_start = shift(range.start);
+            let new_end = if is_insertion_at(range.end) {
+                range.end.max(new_start)
+            } else {
+                shift(range.end)
+            };
+            *range = new_start..new_end;
+        }
+        self.foldable.retain(|range| range.start < range.end);
+    }
+
+    /// Stores the lines of the folded ranges, for caching
+    pub(crate) fn set_closed(&mut self, closed: Vec<Range<usize>>, hash: u64) {
+        self.closed = closed;
+        self.content_hash = Some(hash);
+    }
+
+    /// Takes the lines of the folded ranges, after loading from the
+    /// cache
+    pub(crate) fn take_closed(&mut self) -> Vec<Range<usize>> {
+        std::mem::take(&mut self.closed)
+    }
+
+    /// Wether these [`Folds`] were cached for a [`Text`] with this
+    /// hash
+    ///
+    /// [`Text`]: super::Text
+    pub(crate) fn matches_content(&self, hash: u64) -> bool {
+        self.content_hash == Some(hash)
+    }
+}
+
+impl Encode for Folds {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        // The foldable ranges will be supplied again by the providers.
+        let closed: Vec<(usize, usize)> = self.closed.iter().map(|r| (r.start, r.end)).collect();
+
+        Encode::encode(&closed, encoder)?;
+        Encode::encode(&self.content_hash, encoder)?;
+        Ok(())
+    }
+}
+
+impl<Context> Decode<Context> for Folds {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let closed: Vec<(usize, usize)> = Decode::decode(decoder)?;
+
+        Ok(Folds {
+            foldable: Vec::new(),
+            closed: closed.into_iter().map(|(start, end)| start..end).collect(),
+            content_hash: Decode::decode(decoder)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for Folds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.foldable.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::Folds;
+    use crate::text::{Change, Text};
+
+    /// The foldable ranges after a range of `"a\nb\nc\nd\ne\n"` is
+    /// replaced
+    fn shifted(foldable: Range<usize>, replaced: Range<usize>, edit: &str) -> Vec<Range<usize>> {
+        let text = Text::from("a\nb\nc\nd\ne\n");
+        let mut folds = Folds::default();
+        folds.add(foldable);
+
+        let points = [replaced.start, replaced.end].map(|b| text.point_at_byte(b));
+        folds.apply_change(Change::new(edit, points, &text).as_ref());
+        folds.iter().collect()
+    }
+
+    #[test]
+    fn folds_shift_with_edits_around_them() {
+        assert_eq!(shifted(2..8, 0..0, "xx"), [4..10]);
+        assert_eq!(shifted(2..8, 2..2, "x\n"), [4..10]);
+        assert_eq!(shifted(2..8, 9..10, ""), [2..8]);
+    }
+
+    #[test]
+    fn folds_shift_with_edits_inside_them() {
+        assert_eq!(shifted(2..8, 4..5, "ccc"), [2..10]);
+        assert_eq!(shifted(2..8, 3..7, ""), [2..4]);
+    }
+
+    #[test]
+    fn folds_shift_with_edits_touching_them() {
+        // Inserting at the end doesn't add lines to the fold.
+        assert_eq!(shifted(2..8, 8..8, "zz\n"), [2..8]);
+        assert_eq!(shifted(2..8, 0..3, ""), [0..5]);
+        assert_eq!(shifted(2..8, 7..9, ""), [2..7]);
+    }
+
+    #[test]
+    fn folds_shrink_or_vanish_with_edits_covering_them() {
+        assert!(shifted(2..8, 1..9, "").is_empty());
+        assert_eq!(shifted(2..8, 2..8, "x"), [2..3]);
+    }
+}
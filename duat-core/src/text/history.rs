@@ -9,25 +9,78 @@
 //! well as Kakoune style undoing (multiple [`Change`]s per
 //! [`Moment`]).
 //!
+//! The [`Moment`]s are stored in a tree, so making a new edit after
+//! undoing doesn't discard the undone [`Moment`]s, it just starts a
+//! new branch. You can then move between branches with
+//! [`Text::switch_branch`], or go to the state at a given time with
+//! [`Text::move_to_time`].
+//!
 //! [`undo`]: Text::undo
 //! [`redo`]: Text::redo
-use std::ops::Range;
+use std::{
+    hash::{DefaultHasher, Hasher},
+    ops::Range,
+    time::SystemTime,
+};
 
 use bincode::{Decode, Encode};
 use parking_lot::Mutex;
 
-use super::{Point, Text};
-use crate::{add_shifts, merging_range_by_guess_and_lazy_shift};
+use super::{Bytes, Point, Text};
+use crate::{add_shifts, merging_range_by_guess_and_lazy_shift, mode::Selections};
 
 /// The history of edits, contains all moments
-#[derive(Default, Debug)]
+///
+/// The [`Moment`]s are arranged in a tree, where each one is
+/// identified by a number, in order of creation. The moment `0` is
+/// the root of the tree, representing the state before any [`Moment`]
+/// was added, and every other moment `n` represents the state after
+/// applying the `n`th [`Moment`] on top of its parent.
+///
+/// ```rust
+/// use duat_core::text::{Change, History, Point, Text};
+/// let text = Text::new();
+/// let change = || Change::new("a", [Point::default(); 2], &text);
+///
+/// let mut history = History::new();
+/// history.apply_change(None, change());
+/// history.new_moment();
+/// history.apply_change(None, change());
+/// history.new_moment();
+/// assert_eq!(history.cur_moment(), 2);
+///
+/// // Editing after an undo creates a new branch.
+/// history.move_backwards();
+/// history.apply_change(None, change());
+/// history.new_moment();
+/// assert_eq!(history.cur_moment(), 3);
+/// assert_eq!(history.children(1).collect::<Vec<_>>(), [2, 3]);
+/// assert_eq!(history.branches(), [2, 3]);
+///
+/// // And the old one is still reachable.
+/// let moments = history.switch_branch(-1);
+/// assert_eq!(history.cur_moment(), 2);
+/// assert!(moments[0].is_rev() && !moments[1].is_rev());
+///
+/// history.move_backwards();
+/// history.move_forward();
+/// assert_eq!(history.cur_moment(), 2);
+/// ```
+#[derive(Debug)]
 pub struct History {
     moments: Vec<Moment>,
+    nodes: Vec<Node>,
     cur_moment: usize,
-    new_changes: Option<(Vec<Change>, (usize, [i32; 3]))>,
+    /// A hash of the [`Text`] when this [`History`] was cached
+    content_hash: Option<u64>,
+    /// The moment that matches the file on disk, if known
+    saved_moment: Mutex<Option<usize>>,
+    new_changes: Option<(Vec<Change>, (usize, [isize; 3]))>,
     /// Used to update ranges on the File
-    unproc_changes: Mutex<Option<(Vec<Change>, (usize, [i32; 3]))>>,
+    unproc_changes: Mutex<Option<(Vec<Change>, (usize, [isize; 3]))>>,
     unproc_moments: Mutex<Vec<Moment>>,
+    /// How many macro replays are grouping new moments into one
+    replay_depth: usize,
 }
 
 impl History {
@@ -61,31 +114,46 @@ impl History {
             });
         }
 
-        self.moments.truncate(self.cur_moment);
-
         self.moments.push(Moment {
             changes: Box::leak(Box::from(new_changes)),
             is_rev: false,
         });
-        self.cur_moment += 1;
+        self.nodes.push(Node::new(self.cur_moment));
+
+        let new = self.moments.len();
+        self.nodes[self.cur_moment].redo = Some(new);
+        self.cur_moment = new;
+    }
+
+    /// Starts grouping new moments into the current one
+    ///
+    /// This is done while a macro is being replayed, so that the
+    /// whole replay becomes a single [`Moment`] in this [`History`].
+    pub(crate) fn start_replay(&mut self) {
+        self.replay_depth += 1;
+    }
+
+    /// Stops grouping new moments, see [`History::start_replay`]
+    pub(crate) fn end_replay(&mut self) {
+        self.replay_depth = self.replay_depth.saturating_sub(1);
+    }
+
+    /// Wether new moments are being grouped into the current one
+    pub(crate) fn is_replaying(&self) -> bool {
+        self.replay_depth > 0
     }
 
     /// Redoes the next [`Moment`], returning its [`Change`]s
     ///
+    /// If there are multiple branches after the current moment, the
+    /// one that was most recently visited will be picked.
+    ///
     /// Applying these [`Change`]s in the order that they're given
     /// will result in a correct redoing.
     pub fn move_forward(&mut self) -> Option<Moment> {
         self.new_moment();
-        if self.cur_moment == self.moments.len() {
-            None
-        } else {
-            self.cur_moment += 1;
-
-            let moment = self.moments[self.cur_moment - 1];
-            self.unproc_moments.get_mut().push(moment);
-
-            Some(moment)
-        }
+        let next = self.nodes[self.cur_moment].redo?;
+        Some(self.redo_to(next))
     }
 
     /// Undoes a [`Moment`], returning its reversed [`Change`]s
@@ -95,20 +163,292 @@ impl History {
     /// modifications, will result in a correct undoing.
     pub fn move_backwards(&mut self) -> Option<Moment> {
         self.new_moment();
-        if self.cur_moment == 0 {
-            None
-        } else {
-            self.cur_moment -= 1;
+        (self.cur_moment > 0).then(|| self.undo_one())
+    }
+
+    /// Moves to any moment in the tree, returning the [`Moment`]s to
+    /// apply
+    ///
+    /// This will undo [`Moment`]s until reaching a common ancestor of
+    /// the current moment and `moment`, and then redo them until
+    /// reaching `moment`. If `moment` is greater than the number of
+    /// [`Moment`]s, moves to the last one.
+    ///
+    /// The returned [`Moment`]s should be applied in order, each one
+    /// of them already shifted correctly.
+    pub fn move_to(&mut self, moment: usize) -> Vec<Moment> {
+        self.new_moment();
+        let target = moment.min(self.moments.len());
+        let (undos, redos) = self.path(self.cur_moment, target);
+
+        let mut moments: Vec<Moment> = undos.into_iter().map(|_| self.undo_one()).collect();
+        moments.extend(redos.into_iter().map(|next| self.redo_to(next)));
+
+        moments
+    }
+
+    /// Moves to a sibling branch of the current moment
+    ///
+    /// The siblings are ordered by creation, and `by` can be negative
+    /// in order to move to older branches, wrapping around at the
+    /// ends. Returns the [`Moment`]s that should be applied, like
+    /// [`History::move_to`].
+    pub fn switch_branch(&mut self, by: i32) -> Vec<Moment> {
+        self.new_moment();
+        let Some(parent) = self.parent(self.cur_moment) else {
+            return Vec::new();
+        };
+
+        let siblings: Vec<usize> = self.children(parent).collect();
+        let i = siblings.iter().position(|s| *s == self.cur_moment).unwrap();
+        let target = (i as i32 + by).rem_euclid(siblings.len() as i32) as usize;
+
+        self.move_to(siblings[target])
+    }
+
+    /// Moves to the state of the [`History`] at a given time
+    ///
+    /// This will be the last moment that was created at or before
+    /// `time`, regardless of which branch it belongs to. If `time`
+    /// is before the creation of the first [`Moment`], moves to the
+    /// root. Returns the [`Moment`]s that should be applied, like
+    /// [`History::move_to`].
+    pub fn move_to_time(&mut self, time: SystemTime) -> Vec<Moment> {
+        self.new_moment();
+        let target = self.nodes[1..]
+            .iter()
+            .rposition(|node| node.time <= time)
+            .map_or(0, |i| i + 1);
+
+        self.move_to(target)
+    }
 
-            let mut moment = self.moments[self.cur_moment];
-            moment.is_rev = true;
-            self.unproc_moments.get_mut().push(moment);
+    ////////// Querying functions
 
-            Some(moment)
+    /// The current moment in the tree
+    ///
+    /// This is `0` if there are no [`Moment`]s or everything has
+    /// been undone.
+    pub fn cur_moment(&self) -> usize {
+        self.cur_moment
+    }
+
+    /// The parent of a moment, if it is not the root
+    pub fn parent(&self, moment: usize) -> Option<usize> {
+        self.nodes
+            .get(moment)
+            .filter(|_| moment > 0)
+            .map(|node| node.parent)
+    }
+
+    /// The children of a moment, in order of creation
+    ///
+    /// Every child past the first one represents an alternative
+    /// branch of edits made from `moment`.
+    pub fn children(&self, moment: usize) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .skip(moment + 1)
+            .filter_map(move |(i, node)| (node.parent == moment).then_some(i))
+    }
+
+    /// The tips of all branches in the tree, in order of creation
+    ///
+    /// These are the moments without any children, i.e., the latest
+    /// state of each branch.
+    pub fn branches(&self) -> Vec<usize> {
+        let mut has_children = vec![false; self.nodes.len()];
+        for node in &self.nodes[1..] {
+            has_children[node.parent] = true;
         }
+
+        (1..self.nodes.len())
+            .filter(|i| !has_children[*i])
+            .collect()
+    }
+
+    /// The time at which a moment was created
+    ///
+    /// For the root, this is the time of creation of the [`History`].
+    pub fn time_of(&self, moment: usize) -> Option<SystemTime> {
+        self.nodes.get(moment).map(|node| node.time)
+    }
+
+    /// The [`Change`]s that take the text from one moment to another
+    ///
+    /// Unlike the [`Moment`]s returned by [`History::move_to`], these
+    /// are merged into a single list, in the same way that the
+    /// [`Change`]s of a [`Moment`] are, so they can be used as a diff
+    /// between the two moments. Returns [`None`] if either moment
+    /// doesn't exist.
+    ///
+    /// This doesn't move the [`History`].
+    pub fn changes_between(&self, from: usize, to: usize) -> Option<Vec<Change>> {
+        if from.max(to) > self.moments.len() {
+            return None;
+        }
+
+        let (undos, redos) = self.path(from, to);
+        let undos = undos
+            .into_iter()
+            .map(|m| changes_of(self.moments[m - 1].changes, true));
+        let redos = redos
+            .into_iter()
+            .map(|m| changes_of(self.moments[m - 1].changes, false));
+
+        Some(merge_changes(undos.chain(redos).flatten()))
+    }
+
+    /// The [`Change`]s that take the text from a moment to its
+    /// current state
+    ///
+    /// This is like [`History::changes_between`], but it also takes
+    /// into account the [`Change`]s that are not yet part of a
+    /// [`Moment`]. This can be used, for example, to show what has
+    /// changed since the last save.
+    pub fn changes_since(&self, moment: usize) -> Option<Vec<Change>> {
+        let pending = self.pending_changes();
+        let changes = self.changes_between(moment, self.cur_moment)?;
+
+        Some(merge_changes(
+            changes_of(&changes, false).chain(changes_of(&pending, false)),
+        ))
+    }
+
+    /// The [`Change`]s that take the text from its current state to
+    /// a moment
+    pub(super) fn changes_to(&self, moment: usize) -> Option<Vec<Change>> {
+        let pending = self.pending_changes();
+        let changes = self.changes_between(self.cur_moment, moment)?;
+
+        Some(merge_changes(
+            changes_of(&pending, true).chain(changes_of(&changes, false)),
+        ))
+    }
+
+    /// The moment that matches the last time the file was saved
+    ///
+    /// Returns [`None`] if the file was saved with [`Change`]s that
+    /// were not part of a [`Moment`], or if it was never saved while
+    /// this [`History`] existed.
+    pub fn saved_moment(&self) -> Option<usize> {
+        *self.saved_moment.lock()
+    }
+
+    /// Wether there are [`Change`]s that are not yet part of a
+    /// [`Moment`]
+    pub fn has_pending_changes(&self) -> bool {
+        self.new_changes
+            .as_ref()
+            .is_some_and(|(changes, _)| !changes.is_empty())
+    }
+
+    /// Declares that the current state of the text was saved
+    pub(super) fn declare_saved(&self) {
+        let saved = (!self.has_pending_changes()).then_some(self.cur_moment);
+        *self.saved_moment.lock() = saved;
+    }
+
+    /// Sets the moment that matches the file on disk
+    pub(super) fn set_saved_moment(&mut self, moment: Option<usize>) {
+        *self.saved_moment.get_mut() = moment;
+    }
+
+    /// The selections that were last recorded at a moment
+    ///
+    /// These are recorded whenever a [`Moment`] is finished through
+    /// [`Text::new_moment`], and are returned as a caret, an optional
+    /// anchor, and wether the selection was the main one.
+    pub fn selections_of(
+        &self,
+        moment: usize,
+    ) -> impl Iterator<Item = (Point, Option<Point>, bool)> + '_ {
+        let node = self.nodes.get(moment);
+        node.into_iter().flat_map(|node| {
+            node.selections
+                .iter()
+                .enumerate()
+                .map(move |(i, &(caret, anchor))| (caret, anchor, i == node.main))
+        })
+    }
+
+    /// Records the [`Selections`] of the current moment
+    pub(super) fn set_selections(&mut self, selections: &Selections) {
+        let node = &mut self.nodes[self.cur_moment];
+        node.selections = selections
+            .iter()
+            .map(|(selection, _)| (selection.caret(), selection.anchor()))
+            .collect();
+        node.main = selections.main_index();
+    }
+
+    /// Stamps this [`History`] with the hash of some [`Bytes`]
+    pub(super) fn set_content_hash(&mut self, bytes: &Bytes) {
+        self.content_hash = Some(hash_bytes(bytes));
+    }
+
+    /// Wether this [`History`] was cached for these [`Bytes`]
+    pub(super) fn matches_content(&self, bytes: &Bytes) -> bool {
+        self.content_hash == Some(hash_bytes(bytes))
+    }
+
+    /// The moments to undo and redo in order to go from one moment
+    /// to another
+    fn path(&self, mut from: usize, mut to: usize) -> (Vec<usize>, Vec<usize>) {
+        let (mut undos, mut redos) = (Vec::new(), Vec::new());
+
+        // Since parents are always created before their children, the
+        // greater of the two can't be an ancestor of the other one.
+        while from != to {
+            if from > to {
+                undos.push(from);
+                from = self.nodes[from].parent;
+            } else {
+                redos.push(to);
+                to = self.nodes[to].parent;
+            }
+        }
+        redos.reverse();
+
+        (undos, redos)
+    }
+
+    /// The [`Change`]s that are not yet part of a [`Moment`]
+    fn pending_changes(&self) -> Vec<Change> {
+        let Some((mut changes, (sh_from, shift))) = self.new_changes.clone() else {
+            return Vec::new();
+        };
+        finish_shifting(&mut changes, sh_from, shift);
+        changes
+    }
+
+    /// Undoes the current [`Moment`], assuming it isn't the root
+    fn undo_one(&mut self) -> Moment {
+        let cur = self.cur_moment;
+        let parent = self.nodes[cur].parent;
+        self.nodes[parent].redo = Some(cur);
+        self.cur_moment = parent;
+
+        let mut moment = self.moments[cur - 1];
+        moment.is_rev = true;
+        self.unproc_moments.get_mut().push(moment);
+
+        moment
     }
 
-	/// The list of moments that have yet to be processed
+    /// Redoes a child of the current moment
+    fn redo_to(&mut self, child: usize) -> Moment {
+        self.nodes[self.cur_moment].redo = Some(child);
+        self.cur_moment = child;
+
+        let moment = self.moments[child - 1];
+        self.unproc_moments.get_mut().push(moment);
+
+        moment
+    }
+
+    /// The list of moments that have yet to be processed
     pub(crate) fn unprocessed_moments(&self) -> Vec<Moment> {
         let fresh_moment =
             self.unproc_changes
@@ -137,7 +477,10 @@ impl Encode for History {
         encoder: &mut E,
     ) -> Result<(), bincode::error::EncodeError> {
         Encode::encode(&self.moments, encoder)?;
+        Encode::encode(&self.nodes, encoder)?;
         Encode::encode(&self.cur_moment, encoder)?;
+        Encode::encode(&self.content_hash, encoder)?;
+        Encode::encode(&*self.saved_moment.lock(), encoder)?;
         Encode::encode(&self.new_changes, encoder)?;
         Encode::encode(&*self.unproc_changes.lock(), encoder)?;
         Encode::encode(&*self.unproc_moments.lock(), encoder)?;
@@ -151,22 +494,69 @@ impl<Context> Decode<Context> for History {
     ) -> Result<Self, bincode::error::DecodeError> {
         Ok(History {
             moments: Decode::decode(decoder)?,
+            nodes: Decode::decode(decoder)?,
             cur_moment: Decode::decode(decoder)?,
+            content_hash: Decode::decode(decoder)?,
+            saved_moment: Mutex::new(Decode::decode(decoder)?),
             new_changes: Decode::decode(decoder)?,
             unproc_changes: Mutex::new(Decode::decode(decoder)?),
             unproc_moments: Mutex::new(Decode::decode(decoder)?),
+            replay_depth: 0,
         })
     }
 }
 
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            moments: Vec::new(),
+            nodes: vec![Node::new(0)],
+            cur_moment: 0,
+            content_hash: None,
+            saved_moment: Mutex::new(Some(0)),
+            new_changes: None,
+            unproc_changes: Mutex::default(),
+            unproc_moments: Mutex::default(),
+            replay_depth: 0,
+        }
+    }
+}
+
 impl Clone for History {
     fn clone(&self) -> Self {
         Self {
             moments: self.moments.clone(),
+            nodes: self.nodes.clone(),
             cur_moment: self.cur_moment,
+            content_hash: self.content_hash,
+            saved_moment: Mutex::new(*self.saved_moment.lock()),
             new_changes: self.new_changes.clone(),
             unproc_changes: Mutex::new(self.unproc_changes.lock().clone()),
             unproc_moments: Mutex::new(self.unproc_moments.lock().clone()),
+            replay_depth: self.replay_depth,
+        }
+    }
+}
+
+/// A node in the tree of [`Moment`]s
+#[derive(Clone, Debug, Encode, Decode)]
+struct Node {
+    parent: usize,
+    /// The child that will be picked when redoing
+    redo: Option<usize>,
+    time: SystemTime,
+    selections: Vec<(Point, Option<Point>)>,
+    main: usize,
+}
+
+impl Node {
+    fn new(parent: usize) -> Self {
+        Self {
+            parent,
+            redo: None,
+            time: SystemTime::now(),
+            selections: Vec::new(),
+            main: 0,
         }
     }
 }
@@ -188,19 +578,7 @@ impl Moment {
     /// These may represent forward or backwards [`Change`]s, forward
     /// for newly introduced [`Change`]s and backwards when undoing.
     pub fn changes(&self) -> impl ExactSizeIterator<Item = Change<&str>> + '_ {
-        let mut shift = [0; 3];
-        self.changes.iter().map(move |change| {
-            if self.is_rev {
-                let mut change = change.as_ref().reverse();
-                change.shift_by(shift);
-
-                shift = add_shifts(shift, change.shift());
-
-                change
-            } else {
-                change.as_ref()
-            }
-        })
+        changes_of(self.changes, self.is_rev)
     }
 
     /// Returns the number of [`Change`]s in this [`Moment`]
@@ -223,6 +601,13 @@ impl Moment {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Wether this [`Moment`] is being undone
+    ///
+    /// If that is the case, its [`Change`]s will already be reversed.
+    pub fn is_rev(&self) -> bool {
+        self.is_rev
+    }
 }
 
 impl<Context> Decode<Context> for Moment {
@@ -236,13 +621,48 @@ impl<Context> Decode<Context> for Moment {
     }
 }
 
+/// The [`Change`]s of a [`Moment`], reversed and shifted if undoing
+fn changes_of(
+    changes: &[Change],
+    is_rev: bool,
+) -> impl ExactSizeIterator<Item = Change<&str>> + '_ {
+    let mut shift = [0; 3];
+    changes.iter().map(move |change| {
+        if is_rev {
+            let mut change = change.as_ref().reverse();
+            change.shift_by(shift);
+
+            shift = add_shifts(shift, change.shift());
+
+            change
+        } else {
+            change.as_ref()
+        }
+    })
+}
+
+/// Merges sequential [`Change`]s into a list, like that of a
+/// [`Moment`]
+fn merge_changes<'a>(changes: impl Iterator<Item = Change<&'a str>>) -> Vec<Change> {
+    let mut merged = Vec::new();
+    let mut shift_state = (0, [0; 3]);
+
+    for change in changes.map(Change::to_owned_change) {
+        add_change(&mut merged, None, change, &mut shift_state);
+    }
+
+    let (sh_from, shift) = shift_state;
+    finish_shifting(&mut merged, sh_from, shift);
+    merged
+}
+
 /// First try to merge this change with as many changes as
 /// possible, then add it in
 fn add_change(
     changes: &mut Vec<Change>,
     guess_i: Option<usize>,
     mut change: Change,
-    shift_state: &mut (usize, [i32; 3]),
+    shift_state: &mut (usize, [isize; 3]),
 ) -> usize {
     let (sh_from, shift) = std::mem::take(shift_state);
     let new_shift = change.shift();
@@ -387,6 +807,17 @@ impl<'a> Change<&'a str> {
         }
     }
 
+    /// Returns an owned version of this [`Change`]
+    pub fn to_owned_change(self) -> Change {
+        Change {
+            start: self.start,
+            added: self.added.to_string(),
+            taken: self.taken.to_string(),
+            added_end: self.added_end,
+            taken_end: self.taken_end,
+        }
+    }
+
     pub(super) fn remove_last_nl(len: Point) -> Self {
         Self {
             start: len.rev('\n'),
@@ -416,7 +847,7 @@ impl<S: std::borrow::Borrow<str>> Change<S> {
     }
 
     /// Shifts the [`Change`] by a "signed point"
-    pub(crate) fn shift_by(&mut self, shift: [i32; 3]) {
+    pub(crate) fn shift_by(&mut self, shift: [isize; 3]) {
         self.start = self.start.shift_by(shift);
         self.added_end = self.added_end.shift_by(shift);
         self.taken_end = self.taken_end.shift_by(shift);
@@ -453,11 +884,11 @@ impl<S: std::borrow::Borrow<str>> Change<S> {
     }
 
     /// The total shift caused by this [`Change`]
-    pub fn shift(&self) -> [i32; 3] {
+    pub fn shift(&self) -> [isize; 3] {
         [
-            self.added_end().byte() as i32 - self.taken_end().byte() as i32,
-            self.added_end().char() as i32 - self.taken_end().char() as i32,
-            self.added_end().line() as i32 - self.taken_end().line() as i32,
+            self.added_end().byte() as isize - self.taken_end().byte() as isize,
+            self.added_end().char() as isize - self.taken_end().char() as isize,
+            self.added_end().line() as isize - self.taken_end().line() as isize,
         ]
     }
 }
@@ -467,10 +898,258 @@ fn has_start_of(lhs: Range<Point>, rhs: Range<Point>) -> bool {
     lhs.start <= rhs.start && rhs.start <= lhs.end
 }
 
-fn finish_shifting(changes: &mut [Change], sh_from: usize, shift: [i32; 3]) {
+/// A hash of the contents of some [`Bytes`]
+pub(super) fn hash_bytes(bytes: &Bytes) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for slice in bytes.buffers(..).to_array() {
+        hasher.write(slice);
+    }
+    hasher.finish()
+}
+
+fn finish_shifting(changes: &mut [Change], sh_from: usize, shift: [isize; 3]) {
     if shift != [0; 3] {
         for change in changes[sh_from..].iter_mut() {
             change.shift_by(shift);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use bincode::config::{Configuration, Fixint, LittleEndian, NoLimit};
+
+    use super::History;
+    use crate::text::Text;
+
+    /// Appends a string to the [`Text`], as a new moment
+    ///
+    /// The sleep ensures that each moment has a distinct time.
+    fn append(text: &mut Text, str: &str) {
+        std::thread::sleep(Duration::from_millis(2));
+        let end = text.len().byte() - 1;
+        text.replace_range(end..end, str);
+        text.new_moment();
+    }
+
+    /// Builds the following tree, where moment `2` was undone
+    /// before adding moment `3`:
+    ///
+    /// ```text
+    /// 0: "" -> 1: "a" -> 2: "ab"
+    ///                 -> 3: "ac" -> 4: "acd"
+    /// ```
+    fn branching_text() -> Text {
+        let mut text = Text::new_with_history();
+        append(&mut text, "a");
+        append(&mut text, "b");
+        text.undo();
+        append(&mut text, "c");
+        append(&mut text, "d");
+        text
+    }
+
+    fn cur_moment(text: &Text) -> usize {
+        text.history().unwrap().cur_moment()
+    }
+
+    #[test]
+    fn undo_and_redo_follow_the_last_branch() {
+        let mut text = branching_text();
+        assert_eq!(text.to_string(), "acd");
+        assert_eq!(text.history().unwrap().branches(), [2, 4]);
+
+        text.undo();
+        assert_eq!(text.to_string(), "ac");
+        text.undo();
+        assert_eq!(text.to_string(), "a");
+        text.undo();
+        assert_eq!(text.to_string(), "");
+        assert_eq!(cur_moment(&text), 0);
+
+        // Undoing at the root does nothing.
+        text.undo();
+        assert_eq!(text.to_string(), "");
+
+        text.redo();
+        assert_eq!(text.to_string(), "a");
+        text.redo();
+        assert_eq!(text.to_string(), "ac");
+        text.redo();
+        assert_eq!(text.to_string(), "acd");
+
+        // Redoing at the tip does nothing.
+        text.redo();
+        assert_eq!(text.to_string(), "acd");
+        assert_eq!(cur_moment(&text), 4);
+    }
+
+    #[test]
+    fn switch_branch_moves_between_siblings() {
+        let mut text = branching_text();
+        text.undo();
+        assert_eq!(cur_moment(&text), 3);
+
+        text.switch_branch(-1);
+        assert_eq!(text.to_string(), "ab");
+        assert_eq!(cur_moment(&text), 2);
+
+        // Redoing from the parent now picks the visited branch.
+        text.undo();
+        assert_eq!(text.to_string(), "a");
+        text.redo();
+        assert_eq!(text.to_string(), "ab");
+
+        // Switching wraps around.
+        text.switch_branch(1);
+        assert_eq!(text.to_string(), "ac");
+        text.switch_branch(1);
+        assert_eq!(text.to_string(), "ab");
+
+        // The root has no siblings.
+        text.undo();
+        text.undo();
+        text.switch_branch(1);
+        assert_eq!(text.to_string(), "");
+        assert_eq!(cur_moment(&text), 0);
+    }
+
+    #[test]
+    fn move_to_time_crosses_branches() {
+        let mut text = branching_text();
+        let history = text.history().unwrap();
+        let [time_1, time_2, time_3] = [1, 2, 3].map(|m| history.time_of(m).unwrap());
+
+        text.move_to_time(time_2);
+        assert_eq!(text.to_string(), "ab");
+        assert_eq!(cur_moment(&text), 2);
+
+        text.move_to_time(time_3);
+        assert_eq!(text.to_string(), "ac");
+
+        text.move_to_time(time_1);
+        assert_eq!(text.to_string(), "a");
+
+        text.move_to_time(SystemTime::UNIX_EPOCH);
+        assert_eq!(text.to_string(), "");
+        assert_eq!(cur_moment(&text), 0);
+
+        text.move_to_time(SystemTime::now());
+        assert_eq!(text.to_string(), "acd");
+        assert_eq!(cur_moment(&text), 4);
+    }
+
+    #[test]
+    fn replays_become_a_single_moment() {
+        let mut text = Text::new_with_history();
+        append(&mut text, "a");
+
+        text.start_replay();
+        append(&mut text, "b");
+        append(&mut text, "c");
+        text.end_replay();
+        assert_eq!(text.to_string(), "abc");
+        assert_eq!(cur_moment(&text), 2);
+
+        // Other Texts aren't affected by the replay.
+        let mut other = Text::new_with_history();
+        text.start_replay();
+        append(&mut other, "d");
+        append(&mut other, "e");
+        text.end_replay();
+        assert_eq!(cur_moment(&other), 2);
+
+        text.undo();
+        assert_eq!(text.to_string(), "a");
+    }
+
+    /// Encodes and decodes a [`History`], like the cache does
+    fn through_cache(history: &History) -> History {
+        let config = Configuration::<LittleEndian, Fixint, NoLimit>::default();
+        let encoded = bincode::encode_to_vec(history, config).unwrap();
+        bincode::decode_from_slice(&encoded, config).unwrap().0
+    }
+
+    /// The contents of the [`Text`] at a moment
+    fn string_at(text: &Text, moment: usize) -> String {
+        text.bytes_at(moment).unwrap().strs(..).unwrap().to_string()
+    }
+
+    /// The taken and added strings between two moments
+    fn diff(text: &Text, from: usize, to: usize) -> Vec<[String; 2]> {
+        let changes = text.history().unwrap().changes_between(from, to).unwrap();
+        changes
+            .iter()
+            .map(|change| [change.taken_str(), change.added_str()].map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn cached_history_only_matches_its_content() {
+        let mut text = branching_text();
+        let history = through_cache(&text.cacheable_history().unwrap());
+
+        assert!(history.matches_content(text.bytes()));
+        assert_eq!(history.cur_moment(), cur_moment(&text));
+        assert_eq!(history.branches(), [2, 4]);
+        assert!(!History::default().matches_content(text.bytes()));
+
+        // Modifying the file outside of Duat invalidates the History.
+        text.replace_range(0..0, "e");
+        assert!(!history.matches_content(text.bytes()));
+    }
+
+    #[test]
+    fn cached_history_keeps_the_selections() {
+        let mut text = branching_text();
+        let history = through_cache(&text.cacheable_history().unwrap());
+
+        let recorded: Vec<_> = history.selections_of(history.cur_moment()).collect();
+        let current: Vec<_> = text
+            .selections()
+            .iter()
+            .map(|(sel, is_main)| (sel.caret(), sel.anchor(), is_main))
+            .collect();
+        assert!(!recorded.is_empty());
+        assert_eq!(recorded, current);
+    }
+
+    #[test]
+    fn bytes_at_moments_across_branches() {
+        let mut text = branching_text();
+        assert_eq!(string_at(&text, 0), "\n");
+        assert_eq!(string_at(&text, 2), "ab\n");
+        assert_eq!(string_at(&text, 3), "ac\n");
+        assert_eq!(string_at(&text, 4), "acd\n");
+        assert!(text.bytes_at(5).is_none());
+
+        // Changes that aren't part of a moment yet are left out.
+        let end = text.len().byte() - 1;
+        text.replace_range(end..end, "e");
+        assert_eq!(text.to_string(), "acde");
+        assert_eq!(string_at(&text, 4), "acd\n");
+        assert_eq!(string_at(&text, 2), "ab\n");
+    }
+
+    #[test]
+    fn diffs_between_moments() {
+        let mut text = branching_text();
+        let history = text.history().unwrap();
+        assert!(history.changes_between(4, 4).unwrap().is_empty());
+        assert!(history.changes_between(0, 5).is_none());
+
+        assert_eq!(diff(&text, 0, 4), [["", "acd"]]);
+        assert_eq!(diff(&text, 4, 0), [["acd", ""]]);
+        assert_eq!(diff(&text, 2, 3), [["b", "c"]]);
+
+        // Pending changes are included when diffing to the present.
+        let end = text.len().byte() - 1;
+        text.replace_range(end..end, "e");
+        let history = text.history().unwrap();
+        let changes = history.changes_since(4).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].added_str(), "e");
+    }
+}
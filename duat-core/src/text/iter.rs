@@ -186,7 +186,7 @@ impl<'a> FwdIter<'a> {
                 }
             }
             RawTag::ConcealUntil(b) => {
-                let point = self.text.point_at_byte(*b as usize);
+                let point = self.text.point_at_byte(*b);
                 *self = FwdIter::new_at(self.text, point);
                 return false;
             }
@@ -376,7 +376,7 @@ impl<'a> RevIter<'a> {
             }
             RawTag::EndConceal(_) => self.conceals += 1,
             RawTag::ConcealUntil(b) => {
-                let point = self.text.point_at_byte(*b as usize);
+                let point = self.text.point_at_byte(*b);
                 *self = RevIter::new_at(self.text, point);
                 return false;
             }
@@ -583,13 +583,13 @@ pub enum Part {
     ///
     /// [`Spacer`]: super::Spacer
     Spacer,
-    /// Starts a toggleable region for the given [`ToggleId`]
+    /// Starts a [`Button`] region for the given [`ToggleId`]
     ///
-    /// Not yet implemented
+    /// [`Button`]: super::Button
     ToggleStart(ToggleId),
-    /// Ends a toggleable region for the given [`ToggleId`]
+    /// Ends a [`Button`] region for the given [`ToggleId`]
     ///
-    /// Not yet implemented
+    /// [`Button`]: super::Button
     ToggleEnd(ToggleId),
     /// Resets all [`FormId`]s, [`ToggleId`]s and alignments
     ///
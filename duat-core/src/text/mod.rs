@@ -10,6 +10,8 @@
 //! - Be [colored] in any way, at any point;
 //! - Have any arbitrary range concealed, that is, hidden from view,
 //!   but still in there;
+//! - [Fold] ranges of lines, hiding all but the first one;
+//! - Show [diagnostics] from linters and compilers;
 //! - Arbitrary [ghost text], that is, [`Text`] that shows up, but is
 //!   not actually part of the [`Text`], i.e., it can be easily
 //!   ignored by external modifiers (like an LSP or tree-sitter) of
@@ -77,6 +79,8 @@
 //! [right]: AlignRight
 //! [center]: AlignCenter
 //! [Spacers]: Spacer
+//! [Fold]: Text::fold
+//! [diagnostics]: Text::set_diagnostics
 //! [undo]: Text::undo
 //! [redo]: Text::redo
 //! [gap buffers]: gapbuf::GapBuffer
@@ -89,6 +93,8 @@
 //! [`Mode`]: crate::mode::Mode
 mod builder;
 mod bytes;
+mod diagnostics;
+mod folds;
 mod history;
 mod iter;
 mod ops;
@@ -98,31 +104,35 @@ mod shift_list;
 mod tags;
 
 use std::{
+    ops::Range,
     path::Path,
     rc::Rc,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
+    time::SystemTime,
 };
 
 use self::tags::{FwdTags, InnerTags, RevTags};
 pub use self::{
     builder::{Builder, BuilderPart, txt},
     bytes::{Buffers, Bytes, Lines, Strs},
+    diagnostics::{Diagnostic, Diagnostics, Severity},
+    folds::Folds,
     history::{Change, History, Moment},
     iter::{FwdIter, Item, Part, RevIter},
     ops::{Point, TextRange, TextRangeOrPoint, TwoPoints, utf8_char_width},
-    search::{Matcheable, RegexPattern, Searcher},
+    search::{Case, Matcheable, RegexPattern, SearchOpts, Searcher},
     tags::{
-        AlignCenter, AlignLeft, AlignRight, Conceal, ExtraCaret, FormTag, Ghost, GhostId,
-        MainCaret, RawTag, Spacer, Tag, Tagger, Taggers, Tags, ToggleId,
+        AlignCenter, AlignLeft, AlignRight, Button, Conceal, ExtraCaret, FormTag, Ghost, GhostId,
+        MainCaret, RawTag, Spacer, Tag, Tagger, Taggers, Tags, Toggle, ToggleId,
     },
 };
 use crate::{
     cfg::PrintCfg,
     context, form,
-    mode::{Selection, Selections},
+    mode::{Marks, Selection, Selections},
     ui::Area,
 };
 
@@ -142,10 +152,14 @@ struct InnerText {
     bytes: Bytes,
     tags: InnerTags,
     selections: Selections,
+    marks: Marks,
+    folds: Folds,
+    diagnostics: Diagnostics,
     // Specific to Files
     history: Option<History>,
     has_changed: bool,
     has_unsaved_changes: AtomicBool,
+    is_read_only: bool,
 }
 
 impl Text {
@@ -198,8 +212,51 @@ impl Text {
             .has_unsaved_changes
             .store(has_unsaved_changes, Ordering::Relaxed);
 
-        if let Ok(history) = context::Cache::new().load(path.as_ref()) {
-            text.0.history = Some(history);
+        let cache = context::Cache::new();
+        match cache.load::<History>(path.as_ref()) {
+            Ok(mut history) if history.matches_content(&text.0.bytes) => {
+                let cur = history.cur_moment();
+                // Without unsaved changes, the text matches the disk.
+                if !has_unsaved_changes {
+                    history.set_saved_moment(Some(cur));
+                }
+
+                if history.selections_of(cur).next().is_some() {
+                    text.0.selections = Selections::new_empty();
+                    for (i, (caret, anchor, is_main)) in history.selections_of(cur).enumerate() {
+                        let selection = Selection::new(caret, anchor);
+                        text.0.selections.insert(i, selection, is_main);
+                    }
+                }
+                text.0.history = Some(history);
+            }
+            res => {
+                if res.is_ok() {
+                    cache.delete_for::<History>(path.as_ref());
+                }
+
+                // Without a History, no moment matches the disk.
+                if has_unsaved_changes && let Some(history) = text.0.history.as_mut() {
+                    history.set_saved_moment(None);
+                }
+            }
+        }
+
+        match cache.load::<Marks>(path.as_ref()) {
+            Ok(marks) if marks.matches_content(text.content_hash()) => text.0.marks = marks,
+            Ok(_) => cache.delete_for::<Marks>(path.as_ref()),
+            Err(_) => {}
+        }
+
+        match cache.load::<Folds>(path.as_ref()) {
+            Ok(mut folds) if folds.matches_content(text.content_hash()) => {
+                let len = text.len().line();
+                let mut closed = folds.take_closed();
+                closed.retain(|lines| lines.start + 1 < lines.end && lines.end <= len);
+                text.set_closed_folds(closed);
+            }
+            Ok(_) => cache.delete_for::<Folds>(path.as_ref()),
+            Err(_) => {}
         }
 
         text
@@ -217,9 +274,13 @@ impl Text {
             bytes,
             tags,
             selections,
+            marks: Marks::default(),
+            folds: Folds::default(),
+            diagnostics: Diagnostics::default(),
             history: with_history.then(History::new),
             has_changed: false,
             has_unsaved_changes: AtomicBool::new(false),
+            is_read_only: false,
         }))
     }
 
@@ -229,9 +290,13 @@ impl Text {
             bytes: Bytes::default(),
             tags: InnerTags::new(0),
             selections: Selections::new_empty(),
+            marks: Marks::default(),
+            folds: Folds::default(),
+            diagnostics: Diagnostics::default(),
             history: None,
             has_changed: false,
             has_unsaved_changes: AtomicBool::new(false),
+            is_read_only: false,
         }))
     }
 
@@ -285,7 +350,7 @@ impl Text {
     /// This function is used when you want to [insert]/[remove]
     /// [`Tag`]s (i.e., borrow the inner `InnerTags` mutably via
     /// [`Tags`]), while still being able to read from the
-    /// [`Bytes`] and [`Selections`].
+    /// [`Bytes`] and [`Selections`], or modifying the [`Folds`].
     ///
     /// [insert]: Tags::insert
     /// [remove]: Tags::remove
@@ -295,6 +360,7 @@ impl Text {
             bytes: &self.0.bytes,
             tags: Tags(&mut self.0.tags),
             selections: &self.0.selections,
+            folds: &mut self.0.folds,
         }
     }
 
@@ -383,6 +449,11 @@ impl Text {
         self.0.tags.get_ghost(id)
     }
 
+    /// Gets the [`Toggle`] of a [`Button`] with a given [`ToggleId`]
+    pub fn get_toggle(&self, id: ToggleId) -> Option<&Toggle> {
+        self.0.tags.get_toggle(id)
+    }
+
     ////////// Modification functions
 
     /// Replaces a [range] in the [`Text`]
@@ -394,6 +465,10 @@ impl Text {
     ///
     /// [range]: TextRange
     pub fn replace_range(&mut self, range: impl TextRange, edit: impl ToString) {
+        if self.0.is_read_only {
+            return;
+        }
+
         let range = range.to_range(self.len().byte());
         let (start, end) = (
             self.point_at_byte(range.start),
@@ -414,6 +489,10 @@ impl Text {
         guess_i: Option<usize>,
         change: Change,
     ) -> (Option<usize>, usize) {
+        if self.0.is_read_only {
+            return (guess_i, 0);
+        }
+
         self.0.has_changed = true;
 
         let selections_taken = self.apply_change_inner(guess_i.unwrap_or(0), change.as_ref());
@@ -432,6 +511,11 @@ impl Text {
         );
 
         *self.0.has_unsaved_changes.get_mut() = true;
+        self.0.marks.apply_change(change);
+        self.0.folds.apply_change(change);
+        if self.0.diagnostics.apply_change(change) {
+            self.show_diagnostics();
+        }
         self.0.selections.apply_change(guess_i, change)
     }
 
@@ -455,7 +539,16 @@ impl Text {
     }
 
     /// Inserts a [`Text`] into this [`Text`], in a specific [`Point`]
+    ///
+    /// Like other editing functions, this does nothing if the
+    /// [`Text`] [is read-only].
+    ///
+    /// [is read-only]: Text::is_read_only
     pub fn insert_text(&mut self, p: Point, text: Text) {
+        if self.0.is_read_only {
+            return;
+        }
+
         let insert = if p.char() == 1 && self.0.bytes == "\n" {
             let change = Change::new(
                 text.0.bytes.strs(..).unwrap().to_string(),
@@ -478,10 +571,41 @@ impl Text {
         }
     }
 
+    ////////// Read-only functions
+
+    /// Sets wether the [`Text`] is read-only
+    ///
+    /// A read-only [`Text`] can't be edited through
+    /// [`Text::replace_range`], [`Text::insert_text`] or a
+    /// [`Cursor`], nor can its changes be undone or redone. Trying to
+    /// do so does nothing, and editing through a [`Handle`] logs an
+    /// error.
+    ///
+    /// In order to programmatically edit a read-only [`Text`], like
+    /// the one of the `LogBook`, you can unset this temporarily.
+    ///
+    /// [`Cursor`]: crate::mode::Cursor
+    /// [`Handle`]: crate::context::Handle
+    pub fn set_read_only(&mut self, is_read_only: bool) {
+        self.0.is_read_only = is_read_only;
+    }
+
+    /// Wether the [`Text`] is read-only
+    ///
+    /// See [`Text::set_read_only`] for more information.
+    pub fn is_read_only(&self) -> bool {
+        self.0.is_read_only
+    }
+
     ////////// History functions
 
     /// Undoes the last moment, if there was one
     pub fn undo(&mut self) {
+        if self.0.is_read_only {
+            return;
+        }
+
+        self.finish_moment();
         let mut history = self.0.history.take();
 
         if let Some(history) = history.as_mut()
@@ -496,6 +620,11 @@ impl Text {
 
     /// Redoes the last moment in the history, if there is one
     pub fn redo(&mut self) {
+        if self.0.is_read_only {
+            return;
+        }
+
+        self.finish_moment();
         let mut history = self.0.history.take();
 
         if let Some(history) = history.as_mut()
@@ -508,11 +637,135 @@ impl Text {
         self.0.history = history;
     }
 
+    /// Moves to a moment in the [`History`], undoing and redoing
+    ///
+    /// The [`History`] is a tree, so this can reach [`Moment`]s that
+    /// are in a different branch from the current one. To know which
+    /// moments are available, see [`History::children`] and
+    /// [`History::branches`].
+    pub fn move_to_moment(&mut self, moment: usize) {
+        self.move_through_history(|history| history.move_to(moment));
+    }
+
+    /// Switches to a sibling branch of the current moment
+    ///
+    /// `by` can be negative, in order to switch to older branches.
+    pub fn switch_branch(&mut self, by: i32) {
+        self.move_through_history(|history| history.switch_branch(by));
+    }
+
+    /// Moves to the state of the [`Text`] at a given time
+    ///
+    /// ```rust
+    /// # use std::time::{Duration, SystemTime};
+    /// # use duat_core::text::Text;
+    /// # fn test(text: &mut Text) {
+    /// // Go back to how the Text was 5 minutes ago.
+    /// text.move_to_time(SystemTime::now() - Duration::from_secs(5 * 60));
+    /// # }
+    /// ```
+    pub fn move_to_time(&mut self, time: SystemTime) {
+        self.move_through_history(|history| history.move_to_time(time));
+    }
+
     /// Finishes the current moment and adds a new one to the history
+    ///
+    /// This also records the current [`Selections`], which will be
+    /// stored alongside the [`History`] in the cache.
+    ///
+    /// While a [macro] is being replayed on this [`Text`], this does
+    /// nothing, so that the whole replay becomes a single
+    /// [`Moment`].
+    ///
+    /// [macro]: crate::mode::replay_macro
     pub fn new_moment(&mut self) {
+        if !self.0.history.as_ref().is_some_and(History::is_replaying) {
+            self.finish_moment();
+        }
+    }
+
+    /// Finishes the current moment, even if a macro is being replayed
+    pub(crate) fn finish_moment(&mut self) {
+        if let Some(h) = self.0.history.as_mut() {
+            h.new_moment();
+            h.set_selections(&self.0.selections);
+        }
+    }
+
+    /// Starts grouping new moments, for the replay of a macro
+    ///
+    /// This also finishes the current moment, so the replay doesn't
+    /// get grouped with what came before it.
+    pub(crate) fn start_replay(&mut self) {
+        self.finish_moment();
         if let Some(h) = self.0.history.as_mut() {
-            h.new_moment()
+            h.start_replay();
+        }
+    }
+
+    /// Stops grouping new moments, finishing the one of the replay
+    pub(crate) fn end_replay(&mut self) {
+        if let Some(h) = self.0.history.as_mut() {
+            h.end_replay();
+        }
+        self.new_moment();
+    }
+
+    /// A copy of the [`History`], ready to be cached
+    ///
+    /// This finishes the current [`Moment`] and stamps the
+    /// [`History`] with a hash of this [`Text`]'s contents. When
+    /// loading it back, the [`History`] will be discarded if the hash
+    /// doesn't match, which happens if the file was modified by
+    /// something other than Duat.
+    pub fn cacheable_history(&mut self) -> Option<History> {
+        self.finish_moment();
+        let mut history = self.0.history.clone()?;
+        history.set_content_hash(&self.0.bytes);
+        Some(history)
+    }
+
+    /// A read-only snapshot of the [`Bytes`] at a moment in the
+    /// [`History`]
+    ///
+    /// This doesn't change this [`Text`] in any way, it just applies
+    /// the [`Change`]s from [`History::changes_between`] on a copy
+    /// of the [`Bytes`]. Returns [`None`] if there is no [`History`]
+    /// or if the moment doesn't exist.
+    pub fn bytes_at(&self, moment: usize) -> Option<Bytes> {
+        let changes = self.0.history.as_ref()?.changes_to(moment)?;
+
+        let mut bytes = self.0.bytes.clone();
+        for change in &changes {
+            bytes.apply_change(change.as_ref());
         }
+
+        Some(bytes)
+    }
+
+    /// The [`Change`]s since the [`Text`] was last saved, and a hash
+    /// of its contents at that point
+    pub(crate) fn unsaved_changes(&self) -> Option<(u64, Vec<Change>)> {
+        let history = self.0.history.as_ref()?;
+        let saved = history.saved_moment()?;
+        let changes = history.changes_since(saved)?;
+        let base = self.bytes_at(saved)?;
+
+        Some((history::hash_bytes(&base), changes))
+    }
+
+    /// Applies a list of [`Change`]s, as a new [`Moment`]
+    pub(crate) fn apply_changes(&mut self, changes: impl IntoIterator<Item = Change>) {
+        self.finish_moment();
+        for change in changes {
+            self.apply_change(None, change);
+        }
+        self.finish_moment();
+    }
+
+    /// A hash of the contents of the [`Text`]
+    pub(crate) fn content_hash(&self) -> u64 {
+        history::hash_bytes(&self.0.bytes)
     }
 
     /// Returns a [`Moment`] containing all [`Change`]s since the last
@@ -524,6 +777,24 @@ impl Text {
         self.0.history.as_ref().map(|h| h.unprocessed_moments())
     }
 
+    fn move_through_history(&mut self, f: impl FnOnce(&mut History) -> Vec<Moment>) {
+        if self.0.is_read_only {
+            return;
+        }
+
+        self.finish_moment();
+        let mut history = self.0.history.take();
+
+        if let Some(history) = history.as_mut() {
+            for moment in f(history) {
+                self.apply_and_process_changes(moment);
+                self.0.has_changed = true;
+            }
+        }
+
+        self.0.history = history;
+    }
+
     fn apply_and_process_changes(&mut self, moment: Moment) {
         self.0.selections.clear();
 
@@ -561,22 +832,50 @@ impl Text {
 
     /// Writes the contents of this [`Text`] to a [writer]
     ///
+    /// This doesn't declare the [`Text`] as saved, since the write
+    /// could still fail further down the line.
+    ///
     /// [writer]: std::io::Write
     pub fn write_to(&self, mut writer: impl std::io::Write) -> std::io::Result<usize> {
-        self.0.has_unsaved_changes.store(false, Ordering::Relaxed);
         let [s0, s1] = self.0.bytes.buffers(..).to_array();
-        Ok(writer.write(s0)? + writer.write(s1)?)
+        writer.write_all(s0)?;
+        writer.write_all(s1)?;
+        Ok(s0.len() + s1.len())
     }
 
-    /// Wether or not the content has changed since the last [write]
+    /// Declares that the [`Text`] doesn't match what is on disk
+    ///
+    /// This is only meaningful for [`Text`]s without a [`History`].
+    pub(crate) fn declare_unsaved(&self) {
+        self.0.has_unsaved_changes.store(true, Ordering::Relaxed);
+    }
+
+    /// Declares that the [`Text`] matches what is on disk
+    pub(crate) fn declare_saved(&self) {
+        self.0.has_unsaved_changes.store(false, Ordering::Relaxed);
+        if let Some(history) = self.0.history.as_ref() {
+            history.declare_saved();
+        }
+    }
+
+    /// Wether or not the content has changed since it was last saved
     ///
     /// Returns `true` only if the actual bytes of the [`Text`] have
     /// been changed, ignoring [`Tag`]s and all the other things,
     /// since those are not written to the filesystem.
     ///
-    /// [write]: Text::write_to
+    /// If this [`Text`] has a [`History`], undoing or redoing back to
+    /// the [saved moment] will also return `false`.
+    ///
+    /// [saved moment]: History::saved_moment
     pub fn has_unsaved_changes(&self) -> bool {
-        self.0.has_unsaved_changes.load(Ordering::Relaxed)
+        if let Some(history) = self.0.history.as_ref()
+            && let Some(saved) = history.saved_moment()
+        {
+            history.has_pending_changes() || history.cur_moment() != saved
+        } else {
+            self.0.has_unsaved_changes.load(Ordering::Relaxed)
+        }
     }
 
     ////////// Tag addition/deletion functions
@@ -674,6 +973,306 @@ impl Text {
         self.remove_tags(Tagger::for_selections(), ..);
     }
 
+    /////////// Mark functions
+
+    /// Saves a copy of the current [`Selections`] as a mark
+    ///
+    /// The mark will be shifted by future [`Change`]s, so it can be
+    /// restored later with [`Text::restore_mark`].
+    pub fn save_mark(&mut self, name: char) {
+        self.0.marks.set(name, &self.0.selections);
+    }
+
+    /// Replaces the [`Selections`] with the ones from a mark
+    ///
+    /// Returns `false` if there was no such mark.
+    pub fn restore_mark(&mut self, name: char) -> bool {
+        match self.0.marks.get(name) {
+            Some(selections) => {
+                self.0.selections = selections.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds the [`Selections`] from a mark to the current ones
+    ///
+    /// Returns `false` if there was no such mark.
+    pub fn union_with_mark(&mut self, name: char) -> bool {
+        match self.0.marks.get(name) {
+            Some(selections) => {
+                self.0.selections.union(selections);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keeps only the parts of the [`Selections`] within a mark
+    ///
+    /// Returns `false` if there was no such mark, or if no
+    /// [`Selection`] [intersected] with it.
+    ///
+    /// [intersected]: Selections::intersect
+    pub fn intersect_with_mark(&mut self, name: char) -> bool {
+        match self.0.marks.get(name) {
+            Some(selections) => self.0.selections.intersect(selections, &self.0.bytes),
+            None => false,
+        }
+    }
+
+    /// A copy of the [`Marks`], ready to be cached
+    ///
+    /// Just like with the [`History`], the [`Marks`] are stamped with
+    /// a hash of this [`Text`]'s contents, and will be discarded when
+    /// loading if it doesn't match.
+    pub fn cacheable_marks(&self) -> Marks {
+        let mut marks = self.0.marks.clone();
+        marks.set_content_hash(self.content_hash());
+        marks
+    }
+
+    /////////// Fold functions
+
+    /// Folds the lines of a [range]
+    ///
+    /// The first line of the range stays visible, followed by a
+    /// [`Ghost`] showing how many lines were hidden. If the range
+    /// ends at the start of a line, that line is not folded.
+    ///
+    /// Returns `false` if the range doesn't span multiple lines, or
+    /// if its lines were already folded.
+    ///
+    /// [range]: TextRange
+    pub fn fold(&mut self, range: impl TextRange) -> bool {
+        let Some(lines) = self.fold_lines(range) else {
+            return false;
+        };
+
+        let mut closed = self.closed_folds();
+        if closed.contains(&lines) {
+            return false;
+        }
+        closed.push(lines);
+        self.set_closed_folds(closed);
+        true
+    }
+
+    /// Folds the innermost foldable range around a [`Point`]
+    ///
+    /// The foldable ranges are the ones in the [`Folds`], supplied
+    /// by fold providers. Ranges that are already folded are
+    /// skipped, so calling this repeatedly will fold the enclosing
+    /// ranges.
+    ///
+    /// Returns `false` if there was nothing to fold.
+    pub fn fold_at(&mut self, p: Point) -> bool {
+        let mut closed = self.closed_folds();
+        let lines = self
+            .0
+            .folds
+            .iter()
+            .rev()
+            .filter_map(|range| self.fold_lines(range))
+            .find(|lines| lines.contains(&p.line()) && !closed.contains(lines));
+
+        let Some(lines) = lines else {
+            return false;
+        };
+        closed.push(lines);
+        self.set_closed_folds(closed);
+        true
+    }
+
+    /// Unfolds the innermost fold around a [`Point`]
+    ///
+    /// Returns `false` if there was no fold there.
+    pub fn unfold_at(&mut self, p: Point) -> bool {
+        let mut closed = self.closed_folds();
+        match closed.iter().rposition(|lines| lines.contains(&p.line())) {
+            Some(i) => {
+                closed.remove(i);
+                self.set_closed_folds(closed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Folds every foldable range in the [`Folds`]
+    ///
+    /// Returns how many ranges were folded.
+    pub fn fold_all(&mut self) -> usize {
+        let mut closed = self.closed_folds();
+        let prev_len = closed.len();
+
+        for range in self.0.folds.iter() {
+            if let Some(lines) = self.fold_lines(range)
+                && !closed.contains(&lines)
+            {
+                closed.push(lines);
+            }
+        }
+
+        let folded = closed.len() - prev_len;
+        self.set_closed_folds(closed);
+        folded
+    }
+
+    /// Unfolds everything
+    pub fn unfold_all(&mut self) {
+        self.remove_tags(Tagger::for_folds(), ..);
+    }
+
+    /// The lines of all folded ranges
+    ///
+    /// The ranges are sorted by their first line, with outer folds
+    /// coming before the inner ones. Their ends are exclusive, so
+    /// the last folded line is `range.end - 1`.
+    ///
+    /// Since folds are made out of [`Tag`]s, this has to look through
+    /// all of them, so you should avoid calling it too often.
+    pub fn closed_folds(&self) -> Vec<Range<usize>> {
+        let mut starts = Vec::new();
+        let mut closed = Vec::new();
+
+        for (b, tag) in self.raw_tags_fwd(0) {
+            match tag {
+                RawTag::StartConceal(tagger) if tagger == Tagger::for_folds() => starts.push(b),
+                RawTag::EndConceal(tagger) if tagger == Tagger::for_folds() => {
+                    if let Some(start) = starts.pop() {
+                        let start = self.point_at_byte(start).line();
+                        closed.push(start..self.point_at_byte(b).line() + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        closed.sort_by_key(|lines| (lines.start, std::cmp::Reverse(lines.end)));
+        closed
+    }
+
+    /// A copy of the [`Folds`], ready to be cached
+    ///
+    /// Alongside the foldable ranges, this stores which lines are
+    /// folded, stamped with a hash of this [`Text`]'s contents, just
+    /// like the [`Marks`].
+    pub fn cacheable_folds(&self) -> Folds {
+        let mut folds = self.0.folds.clone();
+        folds.set_closed(self.closed_folds(), self.content_hash());
+        folds
+    }
+
+    /// The lines that would be folded by a range, if it can be
+    /// folded
+    fn fold_lines(&self, range: impl TextRange) -> Option<Range<usize>> {
+        let range = range.to_range(self.len().byte());
+        let start = self.point_at_byte(range.start).line();
+        let end = self.point_at_byte(range.end);
+
+        let last = if end.line() > start && self.point_at_line(end.line()) == end {
+            end.line() - 1
+        } else {
+            end.line()
+        };
+
+        (last > start).then_some(start..last + 1)
+    }
+
+    /// Replaces all folds with new ones, given their lines
+    fn set_closed_folds(&mut self, mut closed: Vec<Range<usize>>) {
+        self.remove_tags(Tagger::for_folds(), ..);
+
+        closed.sort_by_key(|lines| (lines.start, std::cmp::Reverse(lines.end)));
+        closed.dedup();
+
+        let tagger = Tagger::for_folds();
+        let mut prev_start = None;
+
+        for lines in closed {
+            // Folds are shown from the '\n' of the first line until the
+            // '\n' of the last one.
+            let start = self.points_of_line(lines.start)[1].byte() - 1;
+            let end = self.points_of_line(lines.end - 1)[1].byte() - 1;
+            self.0.tags.insert(tagger, start..end, Conceal);
+
+            // Only the outermost of the folds on the same line is shown.
+            if prev_start != Some(lines.start) {
+                let hidden = lines.len() - 1;
+                let ghost = if hidden == 1 {
+                    txt!(" [fold]⋯ 1 line").build()
+                } else {
+                    txt!(" [fold]⋯ {hidden} lines").build()
+                };
+                self.0.tags.insert(tagger, start, Ghost(ghost));
+            }
+            prev_start = Some(lines.start);
+        }
+    }
+
+    /////////// Diagnostic functions
+
+    /// Replaces the [`Diagnostic`]s from a source
+    ///
+    /// Every [`Diagnostic`] previously set by the same `source` is
+    /// removed, so producers should always set all of theirs at
+    /// once. The ranges of the [`Diagnostic`]s are underlined, and
+    /// the message of the most severe one on each line is shown at
+    /// the end of said line.
+    pub fn set_diagnostics(
+        &mut self,
+        source: &str,
+        diagnostics: impl IntoIterator<Item = Diagnostic>,
+    ) {
+        let max = self.len().byte();
+        self.0.diagnostics.replace(source, diagnostics, max);
+        self.show_diagnostics();
+    }
+
+    /// Removes all [`Diagnostic`]s from a source
+    pub fn clear_diagnostics(&mut self, source: &str) {
+        if self.0.diagnostics.clear(source) {
+            self.show_diagnostics();
+        }
+    }
+
+    /// Replaces the [`Tag`]s of all [`Diagnostic`]s
+    fn show_diagnostics(&mut self) {
+        let tagger = Tagger::for_diagnostics();
+        self.0.tags.remove_from(tagger, ..);
+
+        // The most severe Diagnostic of each line.
+        let mut shown: Vec<(usize, &Diagnostic)> = Vec::new();
+
+        for diag in self.0.diagnostics.iter() {
+            let range = diag.range();
+            let line = self.0.bytes.point_at_byte(range.start).line();
+            if !range.is_empty() {
+                let tag = diag.severity().form_id().to_tag(0);
+                self.0.tags.insert(tagger, range, tag);
+            }
+
+            match shown.last_mut() {
+                Some((last, most_severe)) if *last == line => {
+                    if diag.severity() > most_severe.severity() {
+                        *most_severe = diag;
+                    }
+                }
+                _ => shown.push((line, diag)),
+            }
+        }
+
+        for (line, diag) in shown {
+            let nl = self.0.bytes.points_of_line(line)[1].byte() - 1;
+            let message = diag.message().lines().next().unwrap_or_default();
+            let form = diag.severity().message_form_id();
+            let ghost = txt!("  {form}{message}").build();
+            self.0.tags.insert(tagger, nl, Ghost(ghost));
+        }
+    }
+
     /////////// Iterator methods
 
     /// A forward iterator of the [chars and tags] of the [`Text`]
@@ -773,6 +1372,33 @@ impl Text {
     pub fn history(&self) -> Option<&History> {
         self.0.history.as_ref()
     }
+
+    /// The [`Marks`] of this [`Text`]
+    pub fn marks(&self) -> &Marks {
+        &self.0.marks
+    }
+
+    /// A mut reference to this [`Text`]'s [`Marks`]
+    pub fn marks_mut(&mut self) -> &mut Marks {
+        &mut self.0.marks
+    }
+
+    /// The [`Folds`] of this [`Text`]
+    pub fn folds(&self) -> &Folds {
+        &self.0.folds
+    }
+
+    /// A mut reference to this [`Text`]'s [`Folds`]
+    pub fn folds_mut(&mut self) -> &mut Folds {
+        &mut self.0.folds
+    }
+
+    /// The [`Diagnostics`] of this [`Text`]
+    ///
+    /// In order to change them, see [`Text::set_diagnostics`].
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.0.diagnostics
+    }
 }
 
 impl std::ops::Deref for Text {
@@ -807,6 +1433,7 @@ impl Clone for Text {
             history: self.0.history.clone(),
             has_changed: self.0.has_changed,
             has_unsaved_changes: AtomicBool::new(false),
+            is_read_only: self.0.is_read_only,
         }))
     }
 }
@@ -940,4 +1567,9 @@ pub struct TextParts<'a> {
     ///
     /// [`Widget`]: crate::ui::Widget
     pub selections: &'a Selections,
+    /// The [`Folds`] of the [`Text`]
+    ///
+    /// This is where fold providers should add the ranges that can
+    /// be folded.
+    pub folds: &'a mut Folds,
 }
@@ -17,9 +17,9 @@ use super::Item;
 /// [`Text`]: super::Text
 #[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
 pub struct Point {
-    b: u32,
-    c: u32,
-    l: u32,
+    b: usize,
+    c: usize,
+    l: usize,
 }
 
 impl Point {
@@ -32,7 +32,6 @@ impl Point {
 
     /// Internal function to create [`Point`]s
     pub(super) fn from_raw(b: usize, c: usize, l: usize) -> Self {
-        let (b, c, l) = (b as u32, c as u32, l as u32);
         Self { b, c, l }
     }
 
@@ -49,9 +48,9 @@ impl Point {
     pub fn len_of(str: impl AsRef<str>) -> Self {
         let str = str.as_ref();
         Self {
-            b: str.len() as u32,
-            c: str.chars().count() as u32,
-            l: str.bytes().filter(|c| *c == b'\n').count() as u32,
+            b: str.len(),
+            c: str.chars().count(),
+            l: str.bytes().filter(|c| *c == b'\n').count(),
         }
     }
 
@@ -65,7 +64,7 @@ impl Point {
     /// [`Bytes`]: super::Bytes
     /// [`Bytes::point_at_byte`]: super::Bytes::point_at_byte
     pub fn byte(&self) -> usize {
-        self.b as usize
+        self.b
     }
 
     /// Returns the char index (relative to the beginning of the
@@ -81,7 +80,7 @@ impl Point {
     /// [`Bytes::point_at_byte`]: super::Bytes::point_at_byte
     /// [`Bytes::strs`]: super::Bytes::strs
     pub fn char(&self) -> usize {
-        self.c as usize
+        self.c
     }
 
     /// Returns the line. Indexed at 0
@@ -93,7 +92,7 @@ impl Point {
     /// [`Bytes`]: super::Bytes
     /// [`Bytes::point_at_line`]: super::Bytes::point_at_line
     pub fn line(&self) -> usize {
-        self.l as usize
+        self.l
     }
 
     /// Checked [`Point`] subtraction
@@ -111,9 +110,9 @@ impl Point {
     #[inline(always)]
     pub(crate) fn fwd(self, char: char) -> Self {
         Self {
-            b: self.b + char.len_utf8() as u32,
+            b: self.b + char.len_utf8(),
             c: self.c + 1,
-            l: self.l + (char == '\n') as u32,
+            l: self.l + (char == '\n') as usize,
         }
     }
 
@@ -121,20 +120,20 @@ impl Point {
     #[inline(always)]
     pub(crate) fn rev(self, char: char) -> Self {
         Self {
-            b: self.b - char.len_utf8() as u32,
+            b: self.b - char.len_utf8(),
             c: self.c - 1,
-            l: self.l - (char == '\n') as u32,
+            l: self.l - (char == '\n') as usize,
         }
     }
 
     /// Shifts the [`Point`] by a "signed point"
     ///
     /// This assumes that no overflow is going to happen
-    pub(crate) fn shift_by(self, [b, c, l]: [i32; 3]) -> Self {
+    pub(crate) fn shift_by(self, [b, c, l]: [isize; 3]) -> Self {
         Self {
-            b: (self.b as i32 + b) as u32,
-            c: (self.c as i32 + c) as u32,
-            l: (self.l as i32 + l) as u32,
+            b: self.b.wrapping_add_signed(b),
+            c: self.c.wrapping_add_signed(c),
+            l: self.l.wrapping_add_signed(l),
         }
     }
 }
@@ -10,7 +10,7 @@
 //! [`Text`]: super::Text
 //! [`InnerTags`]: super::InnerTags
 
-const MAX_PER_RECORD: u32 = 256;
+const MAX_PER_RECORD: usize = 256;
 
 use super::shift_list::{Shift, ShiftList, Shiftable};
 
@@ -19,22 +19,20 @@ use super::shift_list::{Shift, ShiftList, Shiftable};
 /// [`Text`]: super::Text
 /// [`InnerTags`]: super::InnerTags
 #[derive(Default, Clone, Debug)]
-pub struct Records(ShiftList<[u32; 3]>);
+pub struct Records(ShiftList<[usize; 3]>);
 
 impl Records {
     /// Creates a new [`Records`]
     pub fn new(max: [usize; 3]) -> Self {
-        Self(ShiftList::new(max.map(|x| x as i32)))
+        Self(ShiftList::new(max.map(|x| x as isize)))
     }
 
     /// Insert a new [`Record`], if it would fit
     pub fn insert(&mut self, new: [usize; 3]) {
         // Quick early return.
-        if MAX_PER_RECORD * 2 > self.0.max()[0] as u32 {
+        if MAX_PER_RECORD * 2 > self.0.max()[0] as usize {
             return;
         }
-        let new = new.map(|x| x as u32);
-
         // For internal functions, I assume that I'm not going over self.max.
         let i = match self.0.find_by_key(new[0], |[b, ..]| b) {
             Ok(_) => return,
@@ -56,19 +54,17 @@ impl Records {
 
     /// Transforms a range in the [`Records`]
     pub fn transform(&mut self, start: [usize; 3], old_len: [usize; 3], new_len: [usize; 3]) {
-        let (Ok(s_i) | Err(s_i)) = self.0.find_by_key(start[0] as u32, |[b, ..]| b);
-        let (Ok(e_i) | Err(e_i)) = self
-            .0
-            .find_by_key((start[0] + old_len[0]) as u32, |[b, ..]| b);
+        let (Ok(s_i) | Err(s_i)) = self.0.find_by_key(start[0], |[b, ..]| b);
+        let (Ok(e_i) | Err(e_i)) = self.0.find_by_key(start[0] + old_len[0], |[b, ..]| b);
 
         self.0
             .extract_if_while(s_i..e_i, |_, _| Some(true))
             .for_each(|_| {});
 
         self.0.shift_by(s_i, [
-            new_len[0] as i32 - old_len[0] as i32,
-            new_len[1] as i32 - old_len[1] as i32,
-            new_len[2] as i32 - old_len[2] as i32,
+            new_len[0] as isize - old_len[0] as isize,
+            new_len[1] as isize - old_len[1] as isize,
+            new_len[2] as isize - old_len[2] as isize,
         ]);
     }
 
@@ -88,41 +84,40 @@ impl Records {
     }
 
     /// The [`Record`] closest to `at` by a key extracting function
-    pub fn closest_to_by_key(&self, key: usize, by: fn([u32; 3]) -> u32) -> [usize; 3] {
-        let key = key as u32;
+    pub fn closest_to_by_key(&self, key: usize, by: fn([usize; 3]) -> usize) -> [usize; 3] {
         match self.0.find_by_key(key, by) {
-            Ok(i) => self.0.get(i).unwrap().map(|x| x as usize),
+            Ok(i) => self.0.get(i).unwrap(),
             Err(i) => {
                 let prev = i.checked_sub(1).and_then(|prev_i| self.0.get(prev_i));
                 let next = self.0.get(i + 1);
                 
                 let (prev, next) = match (prev, next) {
-                    (None, None) => ([0; 3], self.0.max().map(|x| x as u32)),
+                    (None, None) => ([0; 3], self.max()),
                     (None, Some(next)) => ([0; 3], next),
-                    (Some(prev), None) => (prev, self.0.max().map(|x| x as u32)),
+                    (Some(prev), None) => (prev, self.max()),
                     (Some(prev), Some(next)) => (prev, next),
                 };
 
                 if key - by(prev) > by(next) - key {
-                    next.map(|x| x as usize)
+                    next
                 } else {
-                    prev.map(|x| x as usize)
+                    prev
                 }
             }
         }
     }
 }
 
-impl Shiftable for [u32; 3] {
-    type Shift = [i32; 3];
+impl Shiftable for [usize; 3] {
+    type Shift = [isize; 3];
 
     fn shift(self, by: Self::Shift) -> Self {
-        let sh = |i: usize| (self[i] as i32 + by[i]) as u32;
+        let sh = |i: usize| self[i].wrapping_add_signed(by[i]);
         [sh(0), sh(1), sh(2)]
     }
 }
 
-impl Shift for [i32; 3] {
+impl Shift for [isize; 3] {
     fn neg(self) -> Self {
         [-self[0], -self[1], -self[2]]
     }
@@ -12,7 +12,7 @@
 use std::{
     collections::HashMap,
     ops::RangeBounds,
-    sync::{LazyLock, RwLock, RwLockWriteGuard},
+    sync::{LazyLock, Mutex, RwLock, RwLockWriteGuard},
 };
 
 use regex_cursor::{
@@ -21,12 +21,13 @@ use regex_cursor::{
     regex_automata::{
         Anchored, PatternID,
         hybrid::dfa::{Cache, DFA},
-        nfa::thompson,
+        nfa::thompson::{self, pikevm::PikeVM},
         util::syntax,
     },
 };
 
 use super::{Bytes, Point, Text, TextRange};
+use crate::cfg::WordChars;
 
 impl Text {
     /// Searches forward for a [`RegexPattern`] in a [range]
@@ -67,6 +68,24 @@ impl Text {
         self.0.bytes.search_rev(pat, range)
     }
 
+    /// Searches forward for a regex, returning each match alongside
+    /// its replacement
+    ///
+    /// The replacement can reference the capture groups of each
+    /// match, either by index, like `$1`, or by name, like `$name` or
+    /// `${name}`. `$0` is the whole match, and `$$` is a literal `$`.
+    ///
+    /// This doesn't change the [`Text`], it only returns what each
+    /// match would be replaced with.
+    pub fn replacements<'a>(
+        &'a self,
+        pat: &'a str,
+        replacement: &'a str,
+        range: impl TextRange,
+    ) -> Result<impl Iterator<Item = ([Point; 2], String)> + 'a, Box<regex_syntax::Error>> {
+        self.0.bytes.replacements(pat, replacement, range)
+    }
+
     /// Returns true if the pattern is found in the given range
     ///
     /// This is unanchored by default, if you want an anchored search,
@@ -150,6 +169,64 @@ impl Bytes {
         }))
     }
 
+    /// Searches forward for a regex, returning each match alongside
+    /// its replacement
+    ///
+    /// The replacement can reference the capture groups of each
+    /// match, either by index, like `$1`, or by name, like `$name` or
+    /// `${name}`. `$0` is the whole match, and `$$` is a literal `$`.
+    ///
+    /// Matches whose capture groups couldn't be resolved are skipped,
+    /// rather than being replaced with an empty expansion.
+    pub fn replacements<'a>(
+        &'a self,
+        pat: &'a str,
+        replacement: &'a str,
+        range: impl TextRange,
+    ) -> Result<impl Iterator<Item = ([Point; 2], String)> + 'a, Box<regex_syntax::Error>> {
+        let pikevm = pikevm_from_pat(pat)?;
+        let mut cache = pikevm.create_cache();
+        let mut caps = pikevm.create_captures();
+
+        let matches = self.search_fwd(pat, range)?;
+
+        Ok(matches.filter_map(move |[start, end]| {
+            // The surrounding lines are included, so look-around
+            // assertions like `^` and `\b` behave like in the search.
+            let hay_start = self.point_at_line(start.line());
+            let [_, hay_end] = self.points_of_line(end.line());
+            let hay = self.strs(hay_start..hay_end).unwrap().to_string();
+
+            let span = start.byte() - hay_start.byte()..end.byte() - hay_start.byte();
+            let input = regex_automata::Input::new(&hay)
+                .span(span.clone())
+                .anchored(Anchored::Yes);
+            pikevm.search(&mut cache, &input, &mut caps);
+
+            // Without the captures, the replacement can't be expanded.
+            if caps.get_match().is_none_or(|m| m.range() != span) {
+                return None;
+            }
+
+            let mut replaced = String::new();
+            caps.interpolate_string_into(&hay, replacement, &mut replaced);
+
+            Some(([start, end], replaced))
+        }))
+    }
+
+    /// Whether a range isn't surrounded by [`WordChars`]
+    ///
+    /// This is what [`SearchOpts::whole_word`] uses in order to
+    /// filter out matches.
+    pub fn is_whole_word(&self, [p0, p1]: [Point; 2], word_chars: WordChars) -> bool {
+        let prev = self.chars_rev(..p0).and_then(|mut chars| chars.next());
+        let next = self.char_at(p1);
+
+        !prev.is_some_and(|(_, char)| word_chars.contains(char))
+            && !next.is_some_and(|char| word_chars.contains(char))
+    }
+
     /// Searches in reverse for a [`RegexPattern`] in a [range]
     ///
     /// A [`RegexPattern`] can either be a single regex string, an
@@ -373,6 +450,7 @@ pub struct Searcher {
     rev_dfa: &'static DFA,
     fwd_cache: RwLockWriteGuard<'static, Cache>,
     rev_cache: RwLockWriteGuard<'static, Cache>,
+    word_chars: Option<WordChars>,
 }
 
 impl Searcher {
@@ -385,6 +463,37 @@ impl Searcher {
             rev_dfa: &dfas.rev.0,
             fwd_cache: dfas.fwd.1.write().unwrap(),
             rev_cache: dfas.rev.1.write().unwrap(),
+            word_chars: None,
+        })
+    }
+
+    /// Returns a new [`Searcher`], interpreting the pattern with
+    /// [`SearchOpts`]
+    ///
+    /// If [`SearchOpts::whole_word`] is set, matches will only be
+    /// returned if they aren't surrounded by any of the `word_chars`.
+    ///
+    /// Errors refer to positions within `pat` itself, not within the
+    /// pattern that [`SearchOpts::pattern`] returns.
+    pub fn new_with(
+        pat: String,
+        opts: SearchOpts,
+        word_chars: WordChars,
+    ) -> Result<Self, Box<regex_syntax::Error>> {
+        if !opts.literal {
+            regex_syntax::ast::parse::Parser::new()
+                .parse(&pat)
+                .map_err(|err| Box::new(regex_syntax::Error::Parse(err)))?;
+        }
+
+        let dfas = dfas_from_pat(&opts.pattern(&pat))?;
+        Ok(Self {
+            pat,
+            fwd_dfa: &dfas.fwd.0,
+            rev_dfa: &dfas.rev.0,
+            fwd_cache: dfas.fwd.1.write().unwrap(),
+            rev_cache: dfas.rev.1.write().unwrap(),
+            word_chars: opts.whole_word.then_some(word_chars),
         })
     }
 
@@ -407,6 +516,7 @@ impl Searcher {
         let rev_dfa = &self.rev_dfa;
         let fwd_cache = &mut self.fwd_cache;
         let rev_cache = &mut self.rev_cache;
+        let word_chars = self.word_chars;
 
         std::iter::from_fn(move || {
             let init = fwd_input.start();
@@ -453,6 +563,7 @@ impl Searcher {
 
             Some([start, end])
         })
+        .filter(move |range| word_chars.is_none_or(|wc| bytes.is_whole_word(*range, wc)))
     }
 
     /// Searches in reverse for the required regex in a range[range]
@@ -474,6 +585,7 @@ impl Searcher {
         let rev_dfa = &self.rev_dfa;
         let fwd_cache = &mut self.fwd_cache;
         let rev_cache = &mut self.rev_cache;
+        let word_chars = self.word_chars;
         std::iter::from_fn(move || {
             let init = rev_input.end();
             let h_start = loop {
@@ -519,6 +631,7 @@ impl Searcher {
 
             Some([start, end])
         })
+        .filter(move |range| word_chars.is_none_or(|wc| bytes.is_whole_word(*range, wc)))
     }
 
     /// Whether or not the regex matches a specific pattern
@@ -541,6 +654,73 @@ impl Searcher {
     }
 }
 
+/// Options for how the pattern of a [`Searcher`] is interpreted
+///
+/// The default options treat the pattern as a case sensitive regex,
+/// which is what [`Searcher::new`] does.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SearchOpts {
+    /// How to treat the case of letters
+    pub case: Case,
+    /// Whether the pattern should be matched literally, rather than
+    /// as a regex
+    pub literal: bool,
+    /// Whether matches must not be surrounded by word characters
+    ///
+    /// Which characters count as word characters is decided by
+    /// [`PrintCfg::word_chars`].
+    ///
+    /// [`PrintCfg::word_chars`]: crate::cfg::PrintCfg::word_chars
+    pub whole_word: bool,
+}
+
+impl SearchOpts {
+    /// The regex pattern that will actually be searched for
+    ///
+    /// This escapes the pattern if it is [literal], and makes it case
+    /// insensitive if [`Case`] calls for it. [Whole word] matching
+    /// isn't a part of the pattern, it is done by the [`Searcher`].
+    ///
+    /// [literal]: SearchOpts::literal
+    /// [Whole word]: SearchOpts::whole_word
+    pub fn pattern(&self, pat: &str) -> String {
+        let pat = if self.literal {
+            regex_syntax::escape(pat)
+        } else {
+            pat.to_string()
+        };
+
+        let is_insensitive = match self.case {
+            Case::Sensitive => false,
+            Case::Insensitive => true,
+            Case::Smart => !has_uppercase_literal(&pat),
+        };
+
+        if is_insensitive && !pat.is_empty() {
+            format!("(?i){pat}")
+        } else {
+            pat
+        }
+    }
+}
+
+/// How a [`Searcher`] should treat the case of letters
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// Letters only match letters of the same case
+    #[default]
+    Sensitive,
+    /// Letters match letters of any case
+    Insensitive,
+    /// Like [`Case::Insensitive`], unless the pattern has an
+    /// uppercase letter, in which case it is like
+    /// [`Case::Sensitive`]
+    ///
+    /// Letters that are part of escapes, like `\S` or `\p{Lu}`,
+    /// don't count.
+    Smart,
+}
+
 struct DFAs {
     fwd: (DFA, RwLock<Cache>),
     rev: (DFA, RwLock<Cache>),
@@ -571,6 +751,30 @@ fn dfas_from_pat(pat: impl RegexPattern) -> Result<&'static DFAs, Box<regex_synt
     }
 }
 
+/// A [`PikeVM`] for a pattern, which, unlike the [`DFA`]s, can
+/// resolve capture groups
+fn pikevm_from_pat(pat: &str) -> Result<&'static PikeVM, Box<regex_syntax::Error>> {
+    static PIKEVM_LIST: LazyLock<Mutex<HashMap<String, &'static PikeVM>>> =
+        LazyLock::new(Mutex::default);
+
+    let mut list = PIKEVM_LIST.lock().unwrap();
+
+    if let Some(pikevm) = list.get(pat) {
+        Ok(*pikevm)
+    } else {
+        let fixed_pat = pat.replace("\\b", "(?-u:\\b)");
+        syntax::parse(&fixed_pat)?;
+        let pikevm = PikeVM::builder()
+            .syntax(syntax::Config::new().multi_line(true))
+            .build(&fixed_pat)
+            .unwrap();
+
+        let pikevm: &'static PikeVM = Box::leak(Box::new(pikevm));
+        list.insert(pat.to_string(), pikevm);
+        Ok(pikevm)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Patterns<'a> {
     One(&'a str),
@@ -770,3 +974,123 @@ fn get_inputs(
     let rev_input = Input::new(haystack);
     (fwd_input, rev_input)
 }
+
+/// Whether a regex has uppercase letters that are matched literally
+///
+/// Escapes like `\S` or `\p{Lu}` and group names are not literals,
+/// so they are ignored. If the regex is invalid, every letter counts.
+fn has_uppercase_literal(pat: &str) -> bool {
+    use regex_syntax::ast::{self, Ast, ClassSetItem};
+
+    struct Finder(bool);
+
+    impl ast::Visitor for Finder {
+        type Err = ();
+        type Output = bool;
+
+        fn finish(self) -> Result<bool, ()> {
+            Ok(self.0)
+        }
+
+        fn visit_pre(&mut self, ast: &Ast) -> Result<(), ()> {
+            if let Ast::Literal(lit) = ast {
+                self.0 |= lit.c.is_uppercase();
+            }
+            Ok(())
+        }
+
+        fn visit_class_set_item_pre(&mut self, item: &ClassSetItem) -> Result<(), ()> {
+            match item {
+                ClassSetItem::Literal(lit) => self.0 |= lit.c.is_uppercase(),
+                ClassSetItem::Range(range) => {
+                    self.0 |= range.start.c.is_uppercase() || range.end.c.is_uppercase()
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    match ast::parse::Parser::new().parse(pat) {
+        Ok(ast) => ast::visit(&ast, Finder(false)).unwrap(),
+        Err(_) => pat.chars().any(char::is_uppercase),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Case, SearchOpts, has_uppercase_literal};
+    use crate::text::Text;
+
+    /// Returns each match of the pattern alongside its replacement
+    fn replacements(text: &str, pat: &str, replacement: &str) -> Vec<[String; 2]> {
+        let text = Text::from(text);
+        text.replacements(pat, replacement, ..)
+            .unwrap()
+            .map(|(range, replaced)| [text.strs(range).unwrap().to_string(), replaced])
+            .collect()
+    }
+
+    #[test]
+    fn replacements_expand_numbered_groups() {
+        let replaced = replacements("ab, cd\nef, gh", r"(\w+), (\w+)", "$2, $1");
+        assert_eq!(replaced, [["ab, cd", "cd, ab"], ["ef, gh", "gh, ef"]]);
+
+        let replaced = replacements("a1", r"\w(\d)", "$0 $$ ${1}x");
+        assert_eq!(replaced, [["a1", "a1 $ 1x"]]);
+    }
+
+    #[test]
+    fn replacements_expand_named_groups() {
+        let replaced = replacements("key = value", r"(?P<k>\w+) = (?P<v>\w+)", "$v: ${k}");
+        assert_eq!(replaced, [["key = value", "value: key"]]);
+    }
+
+    #[test]
+    fn replacements_allow_empty_expansions() {
+        // A group that didn't participate expands to nothing.
+        let replaced = replacements("b ab", "(a)?b", "[$1]");
+        assert_eq!(replaced, [["b", "[]"], ["ab", "[a]"]]);
+
+        let replaced = replacements("one two", r"\s\w+", "");
+        assert_eq!(replaced, [[" two", ""]]);
+    }
+
+    #[test]
+    fn replacements_respect_look_around() {
+        // The surrounding text is part of the haystack, so `\b` and `^`
+        // work like they do when searching.
+        let replaced = replacements("cat concat\ncat", r"(?m)^\bcat\b", "dog");
+        assert_eq!(replaced, [["cat", "dog"], ["cat", "dog"]]);
+    }
+
+    #[test]
+    fn smart_case_ignores_escaped_uppercase() {
+        for pat in [r"\W+", r"\S", r"\p{Lu}", r"\P{L}", r"(?P<N>a)", r"\bfoo\B"] {
+            assert!(!has_uppercase_literal(pat), "{pat} has uppercase");
+        }
+
+        for pat in ["Foo", r"\w+A", "[A-Z]", "[a-zA]", r"\x41", "(?i:B)"] {
+            assert!(has_uppercase_literal(pat), "{pat} has no uppercase");
+        }
+
+        // Invalid regexes count every uppercase letter.
+        assert!(has_uppercase_literal(r"(\S"));
+    }
+
+    #[test]
+    fn smart_case_patterns() {
+        let opts = SearchOpts {
+            case: Case::Smart,
+            ..SearchOpts::default()
+        };
+        assert_eq!(opts.pattern(r"\Sfoo"), r"(?i)\Sfoo");
+        assert_eq!(opts.pattern(r"\SFoo"), r"\SFoo");
+        assert_eq!(opts.pattern(""), "");
+
+        // Escaping literal patterns doesn't add uppercase letters.
+        let opts = SearchOpts { literal: true, ..opts };
+        assert_eq!(opts.pattern(r"a\w"), r"(?i)a\\w");
+        assert_eq!(opts.pattern("A.b"), r"A\.b");
+    }
+}
@@ -19,7 +19,7 @@ const MIN_FOR_RANGE: usize = 16;
 /// A struct to keep better track of very long [`RawTag`] ranges
 #[derive(Debug, Clone)]
 pub struct Bounds {
-    list: ShiftList<([u32; 2], RawTag, RangeId)>,
+    list: ShiftList<([usize; 2], RawTag, RangeId)>,
     ranges_to_update: Ranges,
     min_len: usize,
 }
@@ -31,7 +31,7 @@ impl Bounds {
         ranges_to_update.set_min_len(MIN_FOR_RANGE);
 
         Self {
-            list: ShiftList::new([0, max as i32]),
+            list: ShiftList::new([0, max as isize]),
             ranges_to_update,
             min_len: MIN_FOR_RANGE,
         }
@@ -46,10 +46,10 @@ impl Bounds {
         if e_n - s_n >= self.min_len {
             let id = RangeId::new();
 
-            self.list.insert(s_i, ([s_n as u32, s_b as u32], s_tag, id));
+            self.list.insert(s_i, ([s_n, s_b], s_tag, id));
 
             let e_i = self.shift_by(e_n, [1, 0]);
-            self.list.insert(e_i, ([e_n as u32, e_b as u32], e_tag, id));
+            self.list.insert(e_i, ([e_n, e_b], e_tag, id));
         } else {
             self.shift_by(e_n, [1, 0]);
         }
@@ -57,8 +57,8 @@ impl Bounds {
 
     /// Represents the given range in the list, if it wasn't there
     /// already
-    pub fn represent(&mut self, [s, e]: [([u32; 2], RawTag); 2]) {
-        let before_id = |(bound, tag, _): ([u32; 2], RawTag, _)| (bound, tag);
+    pub fn represent(&mut self, [s, e]: [([usize; 2], RawTag); 2]) {
+        let before_id = |(bound, tag, _): ([usize; 2], RawTag, _)| (bound, tag);
 
         let (Err(s_i), Err(e_i)) = (
             self.list.find_by_key(s, before_id),
@@ -78,8 +78,8 @@ impl Bounds {
 
     /// Shifts the bounds within by a difference in a position,
     /// returns the insertion point of that shift
-    pub fn shift_by(&mut self, n: usize, [n_diff, b_diff]: [i32; 2]) -> usize {
-        let (Ok(i) | Err(i)) = self.list.find_by_key(n as u32, |([n, _], ..)| n);
+    pub fn shift_by(&mut self, n: usize, [n_diff, b_diff]: [isize; 2]) -> usize {
+        let (Ok(i) | Err(i)) = self.list.find_by_key(n, |([n, _], ..)| n);
         self.list.shift_by(i, [n_diff, b_diff]);
 
         self.ranges_to_update.shift_by(n, n_diff);
@@ -87,7 +87,7 @@ impl Bounds {
         // accounted for.
         if n_diff > 0 {
             self.ranges_to_update.add({
-                let [s, e] = [n, n + n_diff.unsigned_abs() as usize];
+                let [s, e] = [n, n + n_diff.unsigned_abs()];
                 let end = (e + self.min_len).min(self.list.max()[0] as usize);
                 s.saturating_sub(self.min_len)..end
             });
@@ -103,10 +103,10 @@ impl Bounds {
     pub fn remove_intersecting(
         &mut self,
         range: Range<usize>,
-        filter: impl Fn((u32, RawTag)) -> bool,
+        filter: impl Fn((usize, RawTag)) -> bool,
     ) -> Vec<usize> {
-        let (Ok(s) | Err(s)) = self.list.find_by_key(range.start as u32, |([_, c], ..)| c);
-        let (Ok(e) | Err(e)) = self.list.find_by_key(range.end as u32, |([_, c], ..)| c);
+        let (Ok(s) | Err(s)) = self.list.find_by_key(range.start, |([_, c], ..)| c);
+        let (Ok(e) | Err(e)) = self.list.find_by_key(range.end, |([_, c], ..)| c);
 
         let mut removed = Vec::new();
         let mut starts = Vec::new();
@@ -115,7 +115,7 @@ impl Bounds {
         self.list
             .extract_if_while(s..e, |_, ([_, b], tag, _)| Some(filter((b, tag))))
             .for_each(|(_, ([n, _], tag, id))| {
-                removed.push(n as usize);
+                removed.push(n);
                 if tag.is_start() {
                     starts.push((n, id));
                 } else {
@@ -135,7 +135,7 @@ impl Bounds {
                         Some(false)
                     }
                 })
-                .map(|(_, ([n, _], ..))| n as usize),
+                .map(|(_, ([n, _], ..))| n),
         );
 
         removed.extend(
@@ -150,7 +150,7 @@ impl Bounds {
                         Some(false)
                     }
                 })
-                .map(|(_, ([n, _], ..))| n as usize),
+                .map(|(_, ([n, _], ..))| n),
         );
 
         // I could improve this, but idrc ¯\_(ツ)_/¯.
@@ -177,7 +177,7 @@ impl Bounds {
                 let is_matching = |(_, (.., lhs)): &(_, (_, _, RangeId))| *lhs == id;
                 let (j, ([n1, _], ..)) = self.list.iter_fwd(i + 1..).find(is_matching).unwrap();
 
-                if n1 - n0 < self.min_len as u32 {
+                if n1 - n0 < self.min_len {
                     for k in [i, j] {
                         let (Ok(l) | Err(l)) = bounds_to_remove.binary_search(&k);
                         bounds_to_remove.insert(l, k);
@@ -202,14 +202,14 @@ impl Bounds {
     pub fn iter_fwd(&self) -> impl Iterator<Item = ([usize; 2], RawTag)> + Clone {
         self.list
             .iter_fwd(..)
-            .map(|(_, (bound, tag, _))| (bound.map(|x| x as usize), tag))
+            .map(|(_, (bound, tag, _))| (bound, tag))
     }
 
     /// Iterates over the bounds
     pub fn iter_rev(&self) -> impl Iterator<Item = ([usize; 2], RawTag)> + Clone {
         self.list
             .iter_rev(..)
-            .map(|(_, (bound, tag, _))| (bound.map(|x| x as usize), tag))
+            .map(|(_, (bound, tag, _))| (bound, tag))
     }
 
     /// Takes the ranges of ranges_to_update
@@ -224,10 +224,10 @@ impl Bounds {
 
     /// Try to find the match for a RawTag at a given index
     pub fn match_of(&self, n: usize) -> Option<([usize; 2], RawTag)> {
-        let i = self.list.find_by_key(n as u32, |([n, _], ..)| n).ok()?;
+        let i = self.list.find_by_key(n, |([n, _], ..)| n).ok()?;
 
-        let is_matching = |(_, ([n, b], tag, id)): (_, ([u32; 2], _, RangeId))| {
-            (id == self.list.get(i).unwrap().2).then_some(([n as usize, b as usize], tag))
+        let is_matching = |(_, ([n, b], tag, id)): (_, ([usize; 2], _, RangeId))| {
+            (id == self.list.get(i).unwrap().2).then_some(([n, b], tag))
         };
 
         Some(if self.list.get(i).unwrap().1.is_start() {
@@ -245,7 +245,7 @@ impl std::fmt::Debug for DebugBounds<'_> {
             f.write_str("\nlist: [")?;
             for (_, ([n, b], tag, id)) in self.0.bounds.list.iter_fwd(..) {
                 write!(f, "\n    {:?}", ([n, b], tag, id))?;
-                write!(f, "\n        {:?}", self.0.list.get(n as usize))?;
+                write!(f, "\n        {:?}", self.0.list.get(n))?;
             }
             if !self.0.bounds.list.is_empty() {
                 f.write_str("\n")?;
@@ -260,16 +260,16 @@ impl std::fmt::Debug for DebugBounds<'_> {
     }
 }
 
-impl Shiftable for ([u32; 2], RawTag, RangeId) {
-    type Shift = [i32; 2];
+impl Shiftable for ([usize; 2], RawTag, RangeId) {
+    type Shift = [isize; 2];
 
     fn shift(self, by: Self::Shift) -> Self {
-        let sh = |i: usize| (self.0[i] as i32 + by[i]) as u32;
+        let sh = |i: usize| self.0[i].wrapping_add_signed(by[i]);
         ([sh(0), sh(1)], self.1, self.2)
     }
 }
 
-impl Shift for [i32; 2] {
+impl Shift for [isize; 2] {
     fn neg(self) -> Self {
         [-self[0], -self[1]]
     }
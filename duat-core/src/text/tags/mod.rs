@@ -14,18 +14,14 @@ use std::{
     ops::{Range, RangeBounds},
 };
 
-use self::{
-    bounds::Bounds,
-    taggers::TaggerExtents,
-    types::{TagId, Toggle},
-};
+use self::{bounds::Bounds, taggers::TaggerExtents, types::TagId};
 pub use self::{
     ids::*,
     taggers::{Tagger, Taggers},
     types::{
-        AlignCenter, AlignLeft, AlignRight, Conceal, ExtraCaret, FormTag, Ghost, MainCaret,
+        AlignCenter, AlignLeft, AlignRight, Button, Conceal, ExtraCaret, FormTag, Ghost, MainCaret,
         RawTag::{self, *},
-        Spacer, Tag,
+        Spacer, Tag, Toggle,
     },
 };
 use super::{
@@ -134,10 +130,10 @@ impl std::fmt::Debug for Tags<'_> {
 /// The struct that holds the [`RawTag`]s of the [`Text`]
 ///
 /// It also holds the [`Text`]s of any [`Ghost`]s, and the
-/// functions of [`ToggleStart`]s
+/// functions of [`Button`]s
 #[derive(Clone)]
 pub struct InnerTags {
-    list: ShiftList<(u32, RawTag)>,
+    list: ShiftList<(usize, RawTag)>,
     ghosts: Vec<(GhostId, Text)>,
     toggles: Vec<(ToggleId, Toggle)>,
     bounds: Bounds,
@@ -148,7 +144,7 @@ impl InnerTags {
     /// Creates a new [`InnerTags`] with a given len
     pub(super) fn new(max: usize) -> Self {
         Self {
-            list: ShiftList::new(max as i32),
+            list: ShiftList::new(max as isize),
             ghosts: Vec::new(),
             toggles: Vec::new(),
             bounds: Bounds::new(max),
@@ -181,8 +177,8 @@ impl InnerTags {
                 && s_b < e_b
             {
                 let (s_i, e_i) = match (
-                    tags.list.find_by_key((s_b as u32, s_tag), |t| t),
-                    tags.list.find_by_key((e_b as u32, e_tag), |t| t),
+                    tags.list.find_by_key((s_b, s_tag), |t| t),
+                    tags.list.find_by_key((e_b, e_tag), |t| t),
                 ) {
                     (Ok(_), Ok(_)) => return false,
                     (Ok(s_i), Err(e_i)) | (Err(s_i), Ok(e_i)) | (Err(s_i), Err(e_i)) => {
@@ -190,8 +186,8 @@ impl InnerTags {
                     }
                 };
 
-                tags.list.insert(s_i, (s_b as u32, s_tag));
-                tags.list.insert(e_i, (e_b as u32, e_tag));
+                tags.list.insert(s_i, (s_b, s_tag));
+                tags.list.insert(e_i, (e_b, e_tag));
 
                 tags.bounds
                     .insert([([s_i, s_b], s_tag), ([e_i, e_b], e_tag)]);
@@ -201,8 +197,8 @@ impl InnerTags {
 
                 true
             } else if end.is_none() {
-                let (Ok(i) | Err(i)) = tags.list.find_by_key((s_b as u32, s_tag), |s| s);
-                tags.list.insert(i, (s_b as u32, s_tag));
+                let (Ok(i) | Err(i)) = tags.list.find_by_key((s_b, s_tag), |s| s);
+                tags.list.insert(i, (s_b, s_tag));
 
                 tags.bounds.shift_by(i, [1, 0]);
 
@@ -226,7 +222,7 @@ impl InnerTags {
         let mut starts = Vec::new();
 
         for (_, (b, tag)) in other.list.iter_fwd(..) {
-            let b = b as usize + p.char();
+            let b = b + p.char();
             match tag {
                 PushForm(..) => starts.push((b, tag)),
                 PopForm(tagger, id) => {
@@ -266,8 +262,13 @@ impl InnerTags {
                     let entry = other.ghosts.extract_if(.., |(l, _)| l == &id).next();
                     self.insert(tagger, b, Ghost(entry.unwrap().1));
                 }
-                StartToggle(..) => todo!(),
-                EndToggle(..) => todo!(),
+                StartToggle(..) => starts.push((b, tag)),
+                EndToggle(tagger, id) => {
+                    let i = starts.iter().rposition(|(_, t)| t.ends_with(&tag)).unwrap();
+                    let (sb, _) = starts.remove(i);
+                    let entry = other.toggles.extract_if(.., |(l, _)| l == &id).next();
+                    self.insert(tagger, sb..b, Button(entry.unwrap().1));
+                }
             };
         }
     }
@@ -307,8 +308,7 @@ impl InnerTags {
         {
             self.remove_from_if(range, |(b, tag)| {
                 taggers.contains_tagger(tag.tagger())
-                    && ((b > start as u32 || !tag.is_end())
-                        && (b < excl_end as u32 && !tag.is_start()))
+                    && ((b > start || !tag.is_end()) && (b < excl_end && !tag.is_start()))
             });
         }
     }
@@ -323,7 +323,7 @@ impl InnerTags {
     fn remove_from_if(
         &mut self,
         range: Range<usize>,
-        filter: impl Fn((u32, RawTag)) -> bool + Copy,
+        filter: impl Fn((usize, RawTag)) -> bool + Copy,
     ) {
         for i in self
             .bounds
@@ -342,8 +342,8 @@ impl InnerTags {
         let mut starts = Vec::new();
         let mut ends = Vec::new();
 
-        let (Ok(start) | Err(start)) = self.list.find_by_key(range.start as u32, |(b, _)| b);
-        let (Ok(end) | Err(end)) = self.list.find_by_key(range.end as u32, |(b, _)| b);
+        let (Ok(start) | Err(start)) = self.list.find_by_key(range.start, |(b, _)| b);
+        let (Ok(end) | Err(end)) = self.list.find_by_key(range.end, |(b, _)| b);
 
         self.list
             .extract_if_while(start..end, |_, entry| Some(filter(entry)))
@@ -408,14 +408,14 @@ impl InnerTags {
 
             // If the range becomes empty, we should remove the remainig pairs
             if new.end == old.start
-                && let Ok(s_i) = self.list.find_by_key(old.start as u32, |(b, _)| b)
+                && let Ok(s_i) = self.list.find_by_key(old.start, |(b, _)| b)
             {
                 let mut to_remove: Vec<usize> = Vec::new();
                 let mut starts = Vec::new();
                 let mut iter = self.list.iter_fwd(s_i..);
 
                 while let Some((i, (b, tag))) = iter.next()
-                    && b == old.start as u32
+                    && b == old.start
                 {
                     if tag.is_start() {
                         starts.push((i, tag));
@@ -438,8 +438,8 @@ impl InnerTags {
             }
         }
 
-        let shift = new.len() as i32 - old.len() as i32;
-        let (Ok(i) | Err(i)) = self.list.find_by_key(old.start as u32 + 1, |(b, _)| b);
+        let shift = new.len() as isize - old.len() as isize;
+        let (Ok(i) | Err(i)) = self.list.find_by_key(old.start + 1, |(b, _)| b);
 
         self.list.shift_by(i, shift);
         self.bounds.shift_by(i, [0, shift]);
@@ -456,8 +456,7 @@ impl InnerTags {
                     && let Some(i) = starts.iter().rposition(|(.., lhs)| lhs.ends_with(&tag))
                 {
                     let (s_n, s_b, s_tag) = starts.remove(i);
-                    self.bounds
-                        .represent([([s_n as u32, s_b], s_tag), ([i as u32, b], tag)]);
+                    self.bounds.represent([([s_n, s_b], s_tag), ([i, b], tag)]);
                 }
             }
         }
@@ -479,7 +478,7 @@ impl InnerTags {
     #[define_opaque(FwdTags)]
     pub fn fwd_at(&self, b: usize) -> FwdTags<'_> {
         let s_i = {
-            let (Ok(s_i) | Err(s_i)) = self.list.find_by_key(b as u32, |(b, _)| b);
+            let (Ok(s_i) | Err(s_i)) = self.list.find_by_key(b, |(b, _)| b);
             s_i.saturating_sub(self.bounds.min_len())
         };
 
@@ -488,10 +487,10 @@ impl InnerTags {
 
         let tags = self.list.iter_fwd(s_i..).map(|(n, (b, tag))| match tag {
             StartConceal(tagger) => match self.bounds.match_of(n) {
-                Some(([_, e_b], _)) => (b as usize, ConcealUntil(e_b as u32)),
-                _ => (b as usize, StartConceal(tagger)),
+                Some(([_, e_b], _)) => (b, ConcealUntil(e_b)),
+                _ => (b, StartConceal(tagger)),
             },
-            tag => (b as usize, tag),
+            tag => (b, tag),
         });
 
         bounds.into_iter().chain(tags).peekable()
@@ -501,7 +500,7 @@ impl InnerTags {
     #[define_opaque(RevTags)]
     pub fn rev_at(&self, b: usize) -> RevTags<'_> {
         let e_i = {
-            let (Ok(e_i) | Err(e_i)) = self.list.find_by_key(b as u32, |(b, _)| b);
+            let (Ok(e_i) | Err(e_i)) = self.list.find_by_key(b, |(b, _)| b);
             (e_i + self.bounds.min_len()).min(self.list.len())
         };
 
@@ -510,35 +509,31 @@ impl InnerTags {
 
         let tags = self.list.iter_rev(..e_i).map(|(n, (b, tag))| match tag {
             EndConceal(tagger) => match self.bounds.match_of(n) {
-                Some(([_, s_b], _)) => (b as usize, ConcealUntil(s_b as u32)),
-                _ => (b as usize, EndConceal(tagger)),
+                Some(([_, s_b], _)) => (b, ConcealUntil(s_b)),
+                _ => (b, EndConceal(tagger)),
             },
-            tag => (b as usize, tag),
+            tag => (b, tag),
         });
 
         bounds.chain(tags).peekable()
     }
 
     pub fn raw_fwd_at(&self, b: usize) -> impl Iterator<Item = (usize, RawTag)> + '_ {
-        let (Ok(s_i) | Err(s_i)) = self.list.find_by_key(b as u32, |(b, _)| b);
-        self.list
-            .iter_fwd(s_i..)
-            .map(|(_, (b, tag))| (b as usize, tag))
+        let (Ok(s_i) | Err(s_i)) = self.list.find_by_key(b, |(b, _)| b);
+        self.list.iter_fwd(s_i..).map(|(_, (b, tag))| (b, tag))
     }
 
     pub fn raw_rev_at(&self, b: usize) -> impl Iterator<Item = (usize, RawTag)> + '_ {
-        let (Ok(e_i) | Err(e_i)) = self.list.find_by_key(b as u32, |(b, _)| b);
-        self.list
-            .iter_rev(..e_i)
-            .map(|(_, (b, tag))| (b as usize, tag))
+        let (Ok(e_i) | Err(e_i)) = self.list.find_by_key(b, |(b, _)| b);
+        self.list.iter_rev(..e_i).map(|(_, (b, tag))| (b, tag))
     }
 
     /// Returns an iterator over a single byte
     pub fn iter_only_at(&self, b: usize) -> impl Iterator<Item = RawTag> + '_ {
-        let (Ok(s_i) | Err(s_i)) = self.list.find_by_key(b as u32, |(b, _)| b);
+        let (Ok(s_i) | Err(s_i)) = self.list.find_by_key(b, |(b, _)| b);
         self.list
             .iter_fwd(s_i..)
-            .take_while(move |(_, (cur_b, _))| *cur_b as usize == b)
+            .take_while(move |(_, (cur_b, _))| *cur_b == b)
             .map(|(_, (_, tag))| tag)
     }
 
@@ -559,6 +554,13 @@ impl InnerTags {
             .iter()
             .find_map(|(lhs, text)| (*lhs == id).then_some(text))
     }
+
+    /// Return the [`Toggle`] of a given [`ToggleId`]
+    pub fn get_toggle(&self, id: ToggleId) -> Option<&Toggle> {
+        self.toggles
+            .iter()
+            .find_map(|(lhs, toggle)| (*lhs == id).then_some(toggle))
+    }
 }
 
 struct DebugBuf<'a, R: RangeBounds<usize>>(&'a InnerTags, R);
@@ -576,7 +578,7 @@ impl<R: RangeBounds<usize> + Clone> std::fmt::Debug for DebugBuf<'_, R> {
 
             for (i, (b, tag)) in self.0.list.iter_fwd(..) {
                 nesting = nesting.saturating_sub(tag.is_end() as usize);
-                if (start as u32..=end as u32).contains(&b) {
+                if (start..=end).contains(&b) {
                     let space = " ".repeat(nesting);
                     let n_txt = format!("n: {i}");
                     let b_txt = format!("b: {b}");
@@ -695,15 +697,15 @@ mod ids {
     }
 }
 
-impl Shiftable for (u32, RawTag) {
-    type Shift = i32;
+impl Shiftable for (usize, RawTag) {
+    type Shift = isize;
 
     fn shift(self, by: Self::Shift) -> Self {
-        ((self.0 as i32 + by) as u32, self.1)
+        (self.0.wrapping_add_signed(by), self.1)
     }
 }
 
-impl Shift for i32 {
+impl Shift for isize {
     fn neg(self) -> Self {
         -self
     }
@@ -14,13 +14,13 @@ use crate::text::shift_list::{ShiftList, Shiftable};
 #[derive(Clone, Debug)]
 pub struct TaggerExtents {
     extents: Vec<(Tagger, Extent)>,
-    max: u32,
+    max: usize,
 }
 
 impl TaggerExtents {
     /// Returns a new instance of [`TaggerExtents`]
     pub fn new(max: usize) -> Self {
-        Self { extents: Vec::new(), max: max as u32 }
+        Self { extents: Vec::new(), max }
     }
 
     /// Inserts a new [`RawTag`]'s byte
@@ -37,8 +37,8 @@ impl TaggerExtents {
             self.extents.push((
                 tagger,
                 Extent::Sparse({
-                    let mut list = ShiftList::new(self.max as i32);
-                    list.insert(0, byte as u32);
+                    let mut list = ShiftList::new(self.max as isize);
+                    list.insert(0, byte);
                     list
                 }),
             ));
@@ -56,7 +56,7 @@ impl TaggerExtents {
                     }
                     Some((_, Extent::Rampant)) => {}
                     None => {
-                        new_list.shift_by(0, self.max as i32);
+                        new_list.shift_by(0, self.max as isize);
                         self.extents.push((tagger, Extent::Sparse(new_list)));
                     }
                 },
@@ -123,17 +123,15 @@ impl TaggerExtents {
 
         self.extents.retain(|(tagger, extent)| match extent {
             Extent::Sparse(_) => true,
-            Extent::Rampant => {
-                !(filter(*tagger) && range.start == 0 && range.end as u32 == self.max)
-            }
+            Extent::Rampant => !(filter(*tagger) && range.start == 0 && range.end == self.max),
         });
 
         ranges
     }
 
     /// Shifts the [`TaggerExtents`] by a given character difference
-    pub fn shift_by(&mut self, c: usize, by: i32) {
-        self.max = (self.max as i32 + by) as u32;
+    pub fn shift_by(&mut self, c: usize, by: isize) {
+        self.max = self.max.wrapping_add_signed(by);
         for (_, extent) in self.extents.iter_mut() {
             extent.shift_by(c, by);
         }
@@ -145,15 +143,15 @@ impl TaggerExtents {
 /// [`InnerTags`]: super::InnerTags
 #[derive(Clone, Debug)]
 enum Extent {
-    Sparse(ShiftList<u32>),
+    Sparse(ShiftList<usize>),
     Rampant,
 }
 
 impl Extent {
     /// Shifts the indices within
-    fn shift_by(&mut self, c: usize, by: i32) {
+    fn shift_by(&mut self, c: usize, by: isize) {
         if let Extent::Sparse(list) = self {
-            let (Ok(i) | Err(i)) = list.find_by_key(c as u32, |c| c);
+            let (Ok(i) | Err(i)) = list.find_by_key(c, |c| c);
             list.shift_by(i, by);
         }
     }
@@ -165,10 +163,10 @@ impl Extent {
         const MAX_FOR_SPARSE: usize = 1024;
 
         if let Self::Sparse(list) = self
-            && let Err(i) = list.find_by_key(c as u32, |c| c)
+            && let Err(i) = list.find_by_key(c, |c| c)
         {
             if list.len() < MAX_FOR_SPARSE {
-                list.insert(i, c as u32);
+                list.insert(i, c);
             } else {
                 *self = Extent::Rampant;
             }
@@ -183,25 +181,25 @@ impl Extent {
             return None;
         };
 
-        let (Ok(s_i) | Err(s_i)) = list.find_by_key(range.start as u32, |c| c);
-        let (Ok(e_i) | Err(e_i)) = list.find_by_key(range.end as u32, |c| c);
+        let (Ok(s_i) | Err(s_i)) = list.find_by_key(range.start, |c| c);
+        let (Ok(e_i) | Err(e_i)) = list.find_by_key(range.end, |c| c);
 
         Some(
             list.extract_if_while(s_i..e_i, |_, _| Some(true))
-                .map(|(_, c)| c as usize),
+                .map(|(_, c)| c),
         )
     }
 }
 
-impl Shiftable for u32 {
-    type Shift = i32;
+impl Shiftable for usize {
+    type Shift = isize;
 
     fn shift(self, by: Self::Shift) -> Self {
-        (self as i32 + by) as u32
+        self.wrapping_add_signed(by)
     }
 }
 
-static TAGGER_COUNT: AtomicU32 = AtomicU32::new(4);
+static TAGGER_COUNT: AtomicU32 = AtomicU32::new(5);
 
 /// A struct that lets one add and remove [`Tag`]s to a [`Text`]
 ///
@@ -298,6 +296,16 @@ impl Tagger {
     pub(crate) const fn for_alias() -> Self {
         Self(2)
     }
+
+    /// A [`Tagger`] specifically for folds
+    pub(in crate::text) const fn for_folds() -> Self {
+        Self(3)
+    }
+
+    /// A [`Tagger`] specifically for diagnostics
+    pub(in crate::text) const fn for_diagnostics() -> Self {
+        Self(4)
+    }
 }
 
 impl std::fmt::Debug for Tagger {
@@ -3,7 +3,7 @@
 //! There are two "types" of tag: [`Tag`]s and [`RawTag`]s. [`Tag`]s
 //! are what is show to the end user, being convenient in the way they
 //! include extra information, like a whole function in the case of
-//! [`StartToggle`]. [`RawTag`]s, on the other hand, are meant to
+//! [`Button`]. [`RawTag`]s, on the other hand, are meant to
 //! be as small as possible in order not to waste memory, as they will
 //! be stored in the [`Text`]. As such, they have as little
 //! information as possible, occupying only 8 bytes.
@@ -38,9 +38,7 @@ use crate::{
 ///   [`Text`], and `caret`s can't interact with;
 /// - [`Conceal`]: Hides a [range] in the [`Text`], mostly only useful
 ///   in the [`File`] [`Widget`];
-///
-/// Additionally, there is also a `Toggle` internal [`Tag`], but it is
-/// not currently implemented.
+/// - [`Button`]: Makes a [range] in the [`Text`] react to the mouse.
 ///
 /// [`Form`]: crate::form::Form
 /// [range]: TextRange
@@ -243,6 +241,69 @@ impl<T: Into<Text> + std::fmt::Debug> Tag<Point> for Ghost<T> {
 pub struct Conceal;
 ranged_impl_tag!(Conceal, RawTag::StartConceal, RawTag::EndConceal);
 
+/// [`Tag`]: Makes a [range] in the [`Text`] react to the mouse
+///
+/// Whenever a mouse event happens over the [range], the [`Toggle`]
+/// function is called with the [`Point`] under the pointer and the
+/// [`MouseEventKind`]. Hovering over it sends
+/// [`MouseEventKind::Moved`], and clicking on it sends
+/// [`MouseEventKind::Down`] and [`MouseEventKind::Up`]. While the
+/// pointer is over the [range], it is also printed with the
+/// `"button.hover"` [`Form`].
+///
+/// Since the function doesn't get a [`Pass`], if you want to act on
+/// Duat's state, you should do so through [`context::queue`]:
+///
+/// ```rust
+/// use duat_core::{mode::MouseEventKind, prelude::*};
+///
+/// fn add_button(text: &mut Text, tagger: Tagger) {
+///     text.insert_tag(
+///         tagger,
+///         0..5,
+///         Button::new(|_, kind| {
+///             if let MouseEventKind::Down(_) = kind {
+///                 context::queue(|_| context::info!("Clicked on the button!"));
+///             }
+///         }),
+///     );
+/// }
+/// ```
+///
+/// [range]: TextRange
+/// [`Form`]: crate::form::Form
+/// [`Pass`]: crate::data::Pass
+/// [`context::queue`]: crate::context::queue
+#[derive(Clone)]
+pub struct Button(pub(super) Toggle);
+
+impl Button {
+    /// Returns a new [`Button`], which calls `f` on mouse events
+    pub fn new(f: impl Fn(Point, MouseEventKind) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl<I: TextRange> Tag<I> for Button {
+    fn decompose(
+        self,
+        index: I,
+        max: usize,
+        key: Tagger,
+    ) -> ((usize, RawTag), Option<(usize, RawTag)>, Option<TagId>) {
+        let id = ToggleId::new();
+        let range = index.to_range(max);
+        let (s_tag, e_tag) = (StartToggle(key, id), EndToggle(key, id));
+        ranged(range, s_tag, e_tag, Some(TagId::Toggle(id, self.0)))
+    }
+}
+
+impl std::fmt::Debug for Button {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Button")
+    }
+}
+
 /// An internal representation of [`Tag`]s
 ///
 /// Unlike [`Tag`]s, however, each variant here is only placed in a
@@ -292,18 +353,16 @@ pub enum RawTag {
     /// [`Text`]: super::Text
     EndConceal(Tagger),
 
-    // TODO: Deal with the consequences of changing this from a usize.
     /// More direct skipping method, allowing for full skips without
     /// the iteration, which could be slow.
     ///
     /// This variant is not actually stored in the buffer, but is
     /// created when iterating.
-    ConcealUntil(u32),
+    ConcealUntil(usize),
 
     /// Text that shows up on screen, but is ignored otherwise.
     Ghost(Tagger, GhostId),
 
-    // Not Implemented:
     /// Begins a toggleable section in the text.
     StartToggle(Tagger, ToggleId),
     /// Ends a toggleable section in the text.
@@ -544,10 +603,11 @@ impl std::fmt::Debug for RawTag {
 
 /// A toggleable function in a range of [`Text`], kind of like a
 /// button
+///
+/// This is the function called by a [`Button`].
 pub type Toggle = Arc<dyn Fn(Point, MouseEventKind) + 'static + Send + Sync>;
 
 #[derive(Clone)]
-#[allow(dead_code)]
 pub enum TagId {
     Ghost(GhostId, Text),
     Toggle(ToggleId, Toggle),
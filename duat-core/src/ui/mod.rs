@@ -32,10 +32,10 @@
 //! [`hook`]: crate::hook
 //! [`File`]: crate::file::File
 //! [`WidgetCreated`]: crate::hook::WidgetCreated
-use std::{fmt::Debug, sync::mpsc, time::Instant};
+use std::{fmt::Debug, path::PathBuf, sync::mpsc, time::Instant};
 
 use bincode::{Decode, Encode};
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 
 pub(crate) use self::widget::Node;
 pub use self::{
@@ -49,7 +49,7 @@ use crate::{
     cfg::PrintCfg,
     data::Pass,
     form::Painter,
-    text::{FwdIter, Item, Point, RevIter, Text, TwoPoints},
+    text::{FwdIter, Item, Point, RevIter, Text, ToggleId, TwoPoints},
 };
 
 pub mod layout;
@@ -430,6 +430,55 @@ pub trait Area: Clone + PartialEq + Sized + 'static {
     ///
     /// Only one [`Area`] should be active at any given moment.
     fn is_active(&self) -> bool;
+
+    /// Wether a position on screen is within this [`Area`]
+    ///
+    /// The position is given in the same units as a [`MouseEvent`]'s
+    /// `column` and `row`.
+    ///
+    /// By default, returns `false`, so [`Ui`]s that don't support the
+    /// mouse don't need to implement this.
+    #[allow(unused_variables)]
+    fn contains_coord(&self, x: u32, y: u32) -> bool {
+        false
+    }
+
+    /// The [`Point`]s that were printed at a position on screen
+    ///
+    /// If the position is past the end of a line, returns the
+    /// [`Point`]s of the last character on that line. If it is below
+    /// the last printed line, returns the [`Point`]s of the last
+    /// printed character. Returns [`None`] if the position is not
+    /// within this [`Area`].
+    ///
+    /// By default, returns [`None`], so [`Ui`]s that don't support
+    /// the mouse don't need to implement this.
+    #[allow(unused_variables)]
+    fn points_at_coord(
+        &self,
+        text: &Text,
+        x: u32,
+        y: u32,
+        cfg: PrintCfg,
+    ) -> Option<(Point, Option<Point>)> {
+        None
+    }
+
+    /// The [`ToggleId`] of the [`Button`] printed at a position on
+    /// screen
+    ///
+    /// This should be based on what was printed the last time, so it
+    /// correctly takes alignment and [`Spacer`]s into account.
+    ///
+    /// By default, returns [`None`], so [`Ui`]s that don't support
+    /// the mouse don't need to implement this.
+    ///
+    /// [`Button`]: crate::text::Button
+    /// [`Spacer`]: crate::text::Spacer
+    #[allow(unused_variables)]
+    fn toggle_at_coord(&self, x: u32, y: u32) -> Option<ToggleId> {
+        None
+    }
 }
 
 /// A dimension on screen, can either be horizontal or vertical
@@ -481,6 +530,10 @@ impl From<PushSpecs> for Axis {
 pub enum DuatEvent {
     /// A [`KeyEvent`] was typed
     Tagger(KeyEvent),
+    /// A [`MouseEvent`] happened
+    Mouse(MouseEvent),
+    /// Text was pasted
+    Paste(String),
     /// A function was queued
     QueuedFunction(Box<dyn FnOnce(&mut Pass) + Send>),
     /// The Screen has resized
@@ -507,6 +560,10 @@ pub enum DuatEvent {
     OpenWindow(String),
     /// Switch to the n'th window
     SwitchWindow(usize),
+    /// A [`File`] was changed on disk
+    ///
+    /// [`File`]: crate::file::File
+    FileChangedOnDisk(PathBuf),
     /// Focused on Duat
     FocusedOnDuat,
     /// Unfocused from Duat
@@ -533,6 +590,16 @@ impl Sender {
         self.0.send(DuatEvent::Tagger(key))
     }
 
+    /// Sends a [`MouseEvent`]
+    pub fn send_mouse(&self, event: MouseEvent) -> Result<(), mpsc::SendError<DuatEvent>> {
+        self.0.send(DuatEvent::Mouse(event))
+    }
+
+    /// Sends pasted text
+    pub fn send_paste(&self, text: String) -> Result<(), mpsc::SendError<DuatEvent>> {
+        self.0.send(DuatEvent::Paste(text))
+    }
+
     /// Sends a notice that the app has resized
     pub fn send_resize(&self) -> Result<(), mpsc::SendError<DuatEvent>> {
         self.0.send(DuatEvent::Resized)
@@ -44,7 +44,8 @@ use crate::{
     context::Handle,
     data::{Pass, RwData},
     form::{self, Painter},
-    hook::{self, FocusedOn, UnfocusedFrom},
+    hook::{self, FocusedOn, MouseEventSentTo, UnfocusedFrom, WidgetUpdated},
+    mode::{self, MouseEvent},
     text::Text,
     ui::{BuildInfo, GetAreaId},
 };
@@ -378,6 +379,27 @@ pub trait Widget<U: Ui>: Send + 'static {
     {
     }
 
+    /// Actions to do whenever a [`MouseEvent`] happens on this
+    /// [`Widget`]
+    ///
+    /// By default, this scrolls the [`Widget`] with the mouse wheel.
+    /// If your [`Widget`] has [`Selections`], you can call
+    /// [`mode::select_with_mouse`] in order to also place and drag
+    /// them, like the [`File`] does.
+    ///
+    /// When implementing this, you are free to remove the `where` clause.
+    ///
+    /// [`Selections`]: crate::mode::Selections
+    /// [`mode::select_with_mouse`]: crate::mode::select_with_mouse
+    /// [`File`]: crate::file::File
+    #[allow(unused)]
+    fn on_mouse_event(pa: &mut Pass, handle: &Handle<Self, U>, event: MouseEvent)
+    where
+        Self: Sized,
+    {
+        mode::scroll_with_mouse(pa, handle, event);
+    }
+
     /// Tells Duat that this [`Widget`] should be updated
     ///
     /// Determining wether a [`Widget`] should be updated, for a good
@@ -520,6 +542,7 @@ pub(crate) struct Node<U: Ui> {
     print: Arc<dyn Fn(&mut Pass) + Send>,
     on_focus: Arc<dyn Fn(&mut Pass, Handle<dyn Widget<U>, U>) + Send>,
     on_unfocus: Arc<dyn Fn(&mut Pass, Handle<dyn Widget<U>, U>) + Send>,
+    on_mouse_event: Arc<dyn Fn(&mut Pass, MouseEvent) + Send>,
 }
 
 impl<U: Ui> Node<U> {
@@ -534,7 +557,10 @@ impl<U: Ui> Node<U> {
             handle: handle.to_dyn(),
             update: Arc::new({
                 let handle = handle.clone();
-                move |pa| W::update(pa, &handle)
+                move |pa| {
+                    W::update(pa, &handle);
+                    hook::trigger(pa, WidgetUpdated(handle.clone()));
+                }
             }),
             print: Arc::new({
                 let handle = handle.clone();
@@ -558,6 +584,13 @@ impl<U: Ui> Node<U> {
                     W::on_unfocus(pa, &handle);
                 }
             }),
+            on_mouse_event: Arc::new({
+                let handle = handle.clone();
+                move |pa, event| {
+                    hook::trigger(pa, MouseEventSentTo((event, handle.clone())));
+                    W::on_mouse_event(pa, &handle, event);
+                }
+            }),
         }
     }
 
@@ -638,6 +671,11 @@ impl<U: Ui> Node<U> {
     pub(crate) fn on_unfocus(&self, pa: &mut Pass, new: Handle<dyn Widget<U>, U>) {
         (self.on_unfocus)(pa, new)
     }
+
+    /// What to do when a [`MouseEvent`] happens on this [`Widget`]
+    pub(crate) fn on_mouse_event(&self, pa: &mut Pass, event: MouseEvent) {
+        (self.on_mouse_event)(pa, event)
+    }
 }
 
 impl<U: Ui> GetAreaId for Node<U> {
@@ -142,6 +142,7 @@ impl<U: Ui> Windows<U> {
                 .filter_map(Node::try_downcast)
                 .collect();
 
+            lhs.read(pa).delete_recovery();
             hook::trigger(pa, OnFileClose((lhs.clone(), Cache::new())));
 
             (lhs_win, lhs, nodes)
@@ -486,6 +487,25 @@ impl<U: Ui> Windows<U> {
             .flat_map(|w| w.file_handles(pa))
     }
 
+    /// Returns a function that checks if the list of [`File`]s has
+    /// changed since the last time it was called
+    pub(crate) fn files_checker(&self) -> impl FnMut(&Pass) -> bool {
+        let windows = self.0.clone();
+        move |pa| {
+            let has_changed = windows.has_changed();
+            windows.read(pa);
+            has_changed
+        }
+    }
+
+    /// Declares that the list of [`File`]s has changed
+    ///
+    /// This is also done when a [`File`]'s path starts existing, so
+    /// that it gets watched for changes.
+    pub(crate) fn declare_files_changed(&self) {
+        self.0.declare_written();
+    }
+
     /// Iterates over all widget entries, with window and widget
     /// indices, in that order
     pub(crate) fn entries<'a>(
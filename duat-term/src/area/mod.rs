@@ -7,7 +7,7 @@ use crossterm::cursor;
 use duat_core::{
     cfg::PrintCfg,
     form::Painter,
-    text::{FwdIter, Item, Part, Point, RevIter, Text, txt},
+    text::{FwdIter, Item, Part, Point, RevIter, Text, ToggleId, txt},
     ui::{self, Axis, Caret, Constraint, MutArea, PushSpecs, SpawnSpecs},
 };
 use iter::{print_iter, print_iter_indented, rev_print_iter};
@@ -68,6 +68,7 @@ pub struct Area {
     layouts: Rc<RefCell<Vec<Layout>>>,
     pub id: AreaId,
     ansi_codes: Arc<Mutex<micromap::Map<CStyle, String, 16>>>,
+    toggles: Arc<Mutex<Vec<(ToggleId, Coords)>>>,
 }
 
 impl PartialEq for Area {
@@ -78,7 +79,12 @@ impl PartialEq for Area {
 
 impl Area {
     pub fn new(id: AreaId, layouts: Rc<RefCell<Vec<Layout>>>) -> Self {
-        Self { layouts, id, ansi_codes: Arc::default() }
+        Self {
+            layouts,
+            id,
+            ansi_codes: Arc::default(),
+            toggles: Arc::default(),
+        }
     }
 
     fn print<'a>(
@@ -120,6 +126,7 @@ impl Area {
             (lines, iter)
         };
 
+        let hovered = duat_core::mode::hovered_button();
         let mut style_was_set = false;
         enum Cursor {
             Main,
@@ -210,7 +217,20 @@ impl Area {
                     Part::ResetState => {
                         style!(lines, &mut ansi_codes, painter.reset())
                     }
-                    Part::ToggleStart(_) | Part::ToggleEnd(_) => todo!(),
+                    Part::ToggleStart(id) => {
+                        lines.start_toggle(id);
+                        if hovered == Some(id) {
+                            painter.apply_button_hover();
+                            style_was_set = true;
+                        }
+                    }
+                    Part::ToggleEnd(id) => {
+                        lines.end_toggle(id);
+                        if hovered == Some(id) {
+                            painter.remove_button_hover();
+                            style_was_set = true;
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -224,6 +244,7 @@ impl Area {
             lines.end_line(&mut ansi_codes, &painter);
         }
 
+        *self.toggles.lock().unwrap() = lines.take_toggles();
         layout.printer.send(self.id, lines);
     }
 }
@@ -627,6 +648,73 @@ impl ui::Area for Area {
             false
         }
     }
+
+    fn contains_coord(&self, x: u32, y: u32) -> bool {
+        let layouts = self.layouts.borrow();
+        let Some(layout) = get_layout(&layouts, self.id) else {
+            return false;
+        };
+
+        let rect = layout.get(self.id).unwrap();
+        let (coords, _) = layout.printer.coords(rect.var_points(), false);
+        (coords.tl.x..coords.br.x).contains(&x) && (coords.tl.y..coords.br.y).contains(&y)
+    }
+
+    fn points_at_coord(
+        &self,
+        text: &Text,
+        x: u32,
+        y: u32,
+        cfg: PrintCfg,
+    ) -> Option<(Point, Option<Point>)> {
+        let layouts = self.layouts.borrow();
+        let layout = get_layout(&layouts, self.id)?;
+        let rect = layout.get(self.id).unwrap();
+        let (coords, has_changed) = layout.printer.coords(rect.var_points(), false);
+
+        if !(coords.tl.x..coords.br.x).contains(&x) || !(coords.tl.y..coords.br.y).contains(&y) {
+            return None;
+        }
+
+        let (s_points, x_shift) = {
+            let mut info = rect.print_info().unwrap().get();
+            let s_points = info.start_points(coords, text, cfg, has_changed);
+            rect.print_info().unwrap().set(info);
+            (s_points, info.x_shift())
+        };
+
+        let (x, row) = (x - coords.tl.x + x_shift, y - coords.tl.y);
+
+        let iter = text.iter_fwd(text.visual_line_start(s_points));
+        let mut cur_row = None;
+        let mut last = None;
+
+        for (caret, item) in print_iter(iter, cfg.wrap_width(coords.width()), cfg, s_points) {
+            if caret.wrap {
+                cur_row = Some(cur_row.map_or(0, |row| row + 1));
+                if cur_row > Some(row) {
+                    break;
+                }
+            }
+
+            if let Part::Char(_) = item.part {
+                last = Some((item.real, item.ghost));
+                if cur_row == Some(row) && caret.x + caret.len > x {
+                    break;
+                }
+            }
+        }
+
+        last
+    }
+
+    fn toggle_at_coord(&self, x: u32, y: u32) -> Option<ToggleId> {
+        let toggles = self.toggles.lock().unwrap();
+        toggles.iter().find_map(|(id, coords)| {
+            ((coords.tl.x..coords.br.x).contains(&x) && (coords.tl.y..coords.br.y).contains(&y))
+                .then_some(*id)
+        })
+    }
 }
 
 mod layouted {
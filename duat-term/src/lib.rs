@@ -87,7 +87,8 @@ impl ui::Ui for Ui {
                         }
                         CtEvent::FocusGained => tx.send_focused(),
                         CtEvent::FocusLost => tx.send_unfocused(),
-                        CtEvent::Mouse(_) | CtEvent::Paste(_) => Ok(()),
+                        CtEvent::Mouse(mouse) => tx.send_mouse(mouse),
+                        CtEvent::Paste(text) => tx.send_paste(text),
                     };
                     if res.is_err() {
                         break;
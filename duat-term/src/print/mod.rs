@@ -14,6 +14,7 @@ use crossterm::{
 use duat_core::{
     cfg::PrintCfg,
     form::{self, Painter},
+    text::ToggleId,
     ui::Axis,
 };
 use sync_solver::SyncSolver;
@@ -255,9 +256,12 @@ impl Printer {
             positions: Vec::new(),
             gaps: Gaps::OnRight,
             default_gaps: Gaps::OnRight,
+            open_toggles: Vec::new(),
+            line_toggles: Vec::new(),
 
             shift,
             cap,
+            toggles: Vec::new(),
         }
     }
 
@@ -599,10 +603,15 @@ pub struct Lines {
     positions: Vec<(usize, u32)>,
     gaps: Gaps,
     default_gaps: Gaps,
+    open_toggles: Vec<(ToggleId, u32, usize)>,
+    line_toggles: Vec<(ToggleId, [u32; 2], [usize; 2])>,
 
     // Outside information
     shift: u32,
     cap: u32,
+
+    // Values that will be kept by the Area
+    toggles: Vec<(ToggleId, Coords)>,
 }
 
 impl Lines {
@@ -631,6 +640,31 @@ impl Lines {
         self.gaps.add_spacer(self.line.len());
     }
 
+    /// Starts the region of a [`Button`] on the current position
+    ///
+    /// [`Button`]: duat_core::text::Button
+    pub fn start_toggle(&mut self, id: ToggleId) {
+        self.open_toggles.push((id, self.len, self.line.len()));
+    }
+
+    /// Ends the region of a [`Button`] on the current position
+    ///
+    /// [`Button`]: duat_core::text::Button
+    pub fn end_toggle(&mut self, id: ToggleId) {
+        if let Some(i) = self.open_toggles.iter().rposition(|(lhs, ..)| *lhs == id) {
+            let (_, len, b) = self.open_toggles.remove(i);
+            let (end_len, end_b) = (self.len, self.line.len());
+            self.line_toggles.push((id, [len, end_len], [b, end_b]));
+        }
+    }
+
+    /// Takes the regions of [`Button`]s that were printed on screen
+    ///
+    /// [`Button`]: duat_core::text::Button
+    pub fn take_toggles(&mut self) -> Vec<(ToggleId, Coords)> {
+        std::mem::take(&mut self.toggles)
+    }
+
     pub fn show_real_cursor(&mut self) {
         self.real_cursor = Some(true);
     }
@@ -834,6 +868,7 @@ impl Lines {
     }
 
     fn go_to_next_line(&mut self) {
+        self.map_toggles();
         self.cutoffs.push(self.bytes.len());
         self.line.clear();
         self.positions.clear();
@@ -841,6 +876,59 @@ impl Lines {
         self.len = 0;
     }
 
+    /// Maps the [`Button`]s of the current line to [`Coords`]
+    ///
+    /// [`Button`]: duat_core::text::Button
+    fn map_toggles(&mut self) {
+        // Toggles that haven't ended continue on the next line.
+        for (id, len, b) in self.open_toggles.iter_mut() {
+            let (end_len, end_b) = (self.len, self.line.len());
+            self.line_toggles.push((*id, [*len, end_len], [*b, end_b]));
+            (*len, *b) = (0, 0);
+        }
+
+        if self.line_toggles.is_empty() {
+            return;
+        }
+
+        let spaces = self.gaps.get_spaces(self.cap.saturating_sub(self.len));
+        let start_d = match &self.gaps {
+            Gaps::OnRight | Gaps::Spacers(_) => 0,
+            Gaps::OnLeft => self.cap.saturating_sub(self.len),
+            Gaps::OnSides => self.cap.saturating_sub(self.len) / 2,
+        };
+
+        // Spacers on the start of a region come before it, while spacers
+        // on the end come after it.
+        let x_of = |len: u32, b: usize, is_end: bool| {
+            let spaced: u32 = match &self.gaps {
+                Gaps::Spacers(bytes) => bytes
+                    .iter()
+                    .zip(&spaces)
+                    .take_while(|(sb, _)| **sb < b || (**sb == b && !is_end))
+                    .map(|(_, space)| space)
+                    .sum(),
+                _ => 0,
+            };
+            let x = (start_d + len + spaced).saturating_sub(self.shift);
+            self.coords.tl.x + x.min(self.coords.width())
+        };
+
+        let y = self.coords.tl.y + (self.cutoffs.len() - 1) as u32;
+        let toggles: Vec<(ToggleId, Coords)> = self
+            .line_toggles
+            .iter()
+            .map(|&(id, [s_len, e_len], [s_b, e_b])| {
+                let (s_x, e_x) = (x_of(s_len, s_b, false), x_of(e_len, e_b, true));
+                (id, Coords::new(Coord::new(s_x, y), Coord::new(e_x, y + 1)))
+            })
+            .filter(|(_, coords)| coords.width() > 0)
+            .collect();
+
+        self.toggles.extend(toggles);
+        self.line_toggles.clear();
+    }
+
     fn add_ansi(&mut self, start_i: usize) {
         let mut adding_ansi = false;
         for &b in &self.line[..start_i] {
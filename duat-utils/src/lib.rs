@@ -8,7 +8,7 @@
 //!
 //! The crate has the following elements:
 //!
-//! - 5 [`widgets`]:
+//! - 6 [`widgets`]:
 //!   - [`LineNumbers`] shows the numbers on a [`File`] (for now), and
 //!     you can configure their alignment, relativeness, etc.
 //!   - The [`PromptLine`] lets you run commands and do other things,
@@ -24,8 +24,10 @@
 //!   - [`LogBook`] is a log of everything that has been notified to
 //!     Duat. It is usually more admissive than [`Notifications`], and
 //!     is most commonly scrolled by the [`Pager`] [`Mode`].
+//!   - [`GrepResults`] shows the matches of the `grep` command, which
+//!     searches through every file in the current directory.
 //!
-//! - 3 [`modes`]:
+//! - 4 [`modes`]:
 //!   - [`Regular`] is essentially the standard [`Mode`] that text
 //!     editors use. Sort of like VSCode.
 //!   - [`Prompt`] is a multitool that can serve many purposes,
@@ -35,8 +37,10 @@
 //!   - [`Pager`] is a simple, read only [`Mode`], designed for
 //!     scrolling and searching through [`Widget`]s, most commonly the
 //!     [`LogBook`].
+//!   - [`Grep`] is used to browse the [`GrepResults`], jumping to
+//!     the selected match.
 //!
-//! - For the [`PromptLine`], there are 4 [`PromptMode`]s:
+//! - For the [`PromptLine`], there are 5 [`PromptMode`]s:
 //!   - [`RunCommands`] will interpret and run Duat commands, with
 //!     syntax highlighting for correctness, defined by the
 //!     [`Parameter`] trait.
@@ -46,14 +50,20 @@
 //!   - [`IncSearch`] is a specialized mode used for incremental
 //!     search, which can abstract over what the search actually does
 //!     with the [`IncSearcher`] trait.
+//!   - [`ReplaceWith`] will replace the matches of a pattern within
+//!     each selection, previewing the replacements as you type.
+//!   - [`HistoryPicker`] will fuzzy search through the history of
+//!     another [`PromptMode`], like a shell's `reverse-i-search`.
 //!
-//! - For [`IncSearch`], there are 4 [`IncSearcher`]s:
+//! - For [`IncSearch`], there are 5 [`IncSearcher`]s:
 //!   - [`SearchFwd`] will move each [`Cursor`] to the next match.
 //!   - [`SearchRev`] will move each [`Cursor`] to the previous match.
 //!   - [`ExtendFwd`] will extend each [`Cursor`]'s selections to the
 //!     next match.
 //!   - [`ExtendRev`] will extend each [`Cursor`]'s selections to the
 //!     previous match.
+//!   - [`Replace`] will highlight the matches within each
+//!     [`Cursor`]'s selection, then replace them with [`ReplaceWith`].
 //!
 //! Note that the [`IncSearcher`] trait can be used for many more
 //! interesting things, like in [`duat-kak`] for example, where its
@@ -100,11 +110,16 @@
 //! [`SearchRev`]: modes::SearchRev
 //! [`ExtendFwd`]: modes::ExtendFwd
 //! [`ExtendRev`]: modes::ExtendRev
+//! [`Replace`]: modes::Replace
+//! [`ReplaceWith`]: modes::ReplaceWith
+//! [`HistoryPicker`]: modes::HistoryPicker
 //! [`duat-kak`]: https://docs.rs/duat-kak/latest/duat_kak
 //! [`SearchUpdated`]: hooks::SearchUpdated
 //! [`SearchPerformed`]: hooks::SearchPerformed
 //! [`Pager`]: modes::Pager
 //! [`LogBook`]: widgets::LogBook
+//! [`GrepResults`]: widgets::GrepResults
+//! [`Grep`]: modes::Grep
 #![feature(
     decl_macro,
     closure_lifetime_binder,
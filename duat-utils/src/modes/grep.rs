@@ -0,0 +1,106 @@
+use std::marker::PhantomData;
+
+use duat_core::prelude::*;
+
+use crate::{modes::RunCommands, widgets::GrepResults};
+
+/// A [`Mode`] for browsing the results of the `grep` command
+///
+/// In it, `j` and `k` select the next and previous match, while
+/// `Enter` jumps to the selected match, opening its [`File`] if it
+/// wasn't opened already.
+pub struct Grep<U: Ui>(Option<String>, PhantomData<U>);
+
+impl<U: Ui> Grep<U> {
+    /// Returns a new [`Grep`], for browsing the previous results
+    pub fn new() -> Self {
+        Self(None, PhantomData)
+    }
+
+    /// Returns a new [`Grep`], which starts a new search
+    ///
+    /// The search is done through [`GrepResults::search`].
+    pub fn search(pat: impl ToString) -> Self {
+        Self(Some(pat.to_string()), PhantomData)
+    }
+}
+
+impl<U: Ui> Mode<U> for Grep<U> {
+    type Widget = GrepResults;
+
+    fn on_switch(&mut self, pa: &mut Pass, handle: Handle<Self::Widget, U>) {
+        if let Some(pat) = self.0.take()
+            && let Err(err) = handle.write(pa).search(pat)
+        {
+            context::error!("{err}");
+        }
+    }
+
+    fn send_key(&mut self, pa: &mut Pass, key: KeyEvent, handle: Handle<Self::Widget, U>) {
+        use KeyCode::*;
+        match key {
+            key!(Char('j') | Down) => select_by(pa, &handle, 1),
+            key!(Char('J')) | key!(Down, KeyMod::SHIFT) => select_by(pa, &handle, i32::MAX),
+            key!(Char('k') | Up) => select_by(pa, &handle, -1),
+            key!(Char('K')) | key!(Up, KeyMod::SHIFT) => select_by(pa, &handle, i32::MIN),
+            key!(Enter) => {
+                let Some(m) = handle.read(pa).selected().cloned() else {
+                    context::error!("There is no match to jump to");
+                    return;
+                };
+
+                if let Err(err) = cmd::edit_path::<U>(pa, m.path()) {
+                    context::error!("{err}");
+                    return;
+                }
+
+                // The File might not have been opened yet.
+                context::queue(move |pa| {
+                    let name = m.path().to_string_lossy();
+                    let Ok(handle) = context::file_named::<U>(pa, name) else {
+                        return;
+                    };
+
+                    // The File could have unsaved changes, so the match is
+                    // found by its line and column, not its position on disk.
+                    let (line, col) = m.coords();
+                    let [start, end] = m.range();
+                    let len = end.char() - start.char();
+
+                    handle.edit_main(pa, |mut c| {
+                        let text = c.text();
+                        let line = line.min(text.last_point().line());
+                        let [line_start, line_end] = text.points_of_line(line);
+
+                        let start = (line_start.char() + col).min(line_end.char());
+                        let end = (start + len).min(text.len().char());
+                        let [start, end] = [start, end].map(|char| text.point_at_char(char));
+                        c.move_to(start..end);
+                    });
+                });
+            }
+            key!(Esc) => mode::reset::<File<U>, U>(),
+            key!(Char(':')) => mode::set::<U>(RunCommands::new()),
+            _ => {}
+        }
+    }
+}
+
+impl<U: Ui> Clone for Grep<U> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<U: Ui> Default for Grep<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Selects another match, scrolling to it
+fn select_by<U: Ui>(pa: &mut Pass, handle: &Handle<GrepResults, U>, by: i32) {
+    if let Some(point) = handle.write(pa).select_by(by) {
+        handle.scroll_to_points(pa, point);
+    }
+}
@@ -0,0 +1,238 @@
+//! History for the [`Prompt`]
+//!
+//! Every [`PromptMode`] has its own history, which is shared between
+//! all instances of its type. So, for example, [`IncSearch<SearchFwd,
+//! U>`] and [`IncSearch<SearchRev, U>`] have separate histories.
+//!
+//! The histories are stored in Duat's cache when it closes or
+//! reloads, and are loaded back the first time they are needed.
+//!
+//! [`IncSearch<SearchFwd, U>`]: super::IncSearch
+//! [`IncSearch<SearchRev, U>`]: super::IncSearch
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{LazyLock, Mutex},
+};
+
+use duat_core::{
+    context::{Decode, Encode},
+    duat_name,
+    hook::ConfigUnloaded,
+    prelude::*,
+};
+
+use super::{Prompt, PromptMode};
+
+/// How many entries are kept in the history of each [`PromptMode`]
+const MAX_ENTRIES: usize = 200;
+
+static HISTORY: LazyLock<Mutex<PromptHistory>> = LazyLock::new(|| {
+    hook::add_no_alias::<ConfigUnloaded>(|_, _| {
+        let history = HISTORY.lock().unwrap().clone();
+        if let Err(err) = context::store_global(history) {
+            context::error!("Couldn't store the prompt history: {err}");
+        }
+    });
+
+    Mutex::new(context::load_global().unwrap_or_default())
+});
+static TAGGER: LazyLock<Tagger> = LazyLock::new(Tagger::new);
+
+/// The histories of every [`PromptMode`], from oldest to newest
+#[derive(Default, Clone, Encode, Decode)]
+#[bincode(crate = "duat_core::context::bincode")]
+struct PromptHistory(HashMap<String, Vec<String>>);
+
+/// Adds an entry to the history of a [`PromptMode`]
+///
+/// If the entry was already in the history, it is moved to the end.
+pub(super) fn push<M: 'static>(entry: String) {
+    if entry.is_empty() {
+        return;
+    }
+
+    let mut history = HISTORY.lock().unwrap();
+    let entries = history.0.entry(duat_name::<M>().to_string()).or_default();
+    entries.retain(|e| *e != entry);
+    entries.push(entry);
+
+    if entries.len() > MAX_ENTRIES {
+        entries.drain(..entries.len() - MAX_ENTRIES);
+    }
+}
+
+/// The history of a [`PromptMode`], from oldest to newest
+pub(super) fn entries<M: 'static>() -> Vec<String> {
+    let history = HISTORY.lock().unwrap();
+    history.0.get(duat_name::<M>()).cloned().unwrap_or_default()
+}
+
+/// A [`PromptMode`] to pick an entry from the history of another
+///
+/// This works like the `reverse-i-search` of shells. What is typed
+/// is fuzzy matched against the history, and the newest matching
+/// entry is shown after it. `Up` or `Ctrl+R` go to older matches,
+/// while `Down` or `Ctrl+S` go to newer ones.
+///
+/// Confirming switches back to the original [`PromptMode`], with the
+/// entry as its initial text. Cancelling also switches back, with the
+/// text that was there before.
+///
+/// This [`PromptMode`] is opened by pressing `Ctrl+R` in a
+/// [`Prompt`] whose [`PromptMode`] [keeps a history].
+///
+/// [keeps a history]: PromptMode::keeps_history
+pub struct HistoryPicker<M: PromptMode<U>, U: Ui> {
+    mode: M,
+    initial: String,
+    entries: Vec<String>,
+    query: Option<String>,
+    matches: Vec<(usize, Vec<usize>)>,
+    selected: usize,
+    ghost: PhantomData<U>,
+}
+
+impl<M: PromptMode<U>, U: Ui> HistoryPicker<M, U> {
+    /// Returns a [`Prompt`] with [`HistoryPicker`] as its
+    /// [`PromptMode`]
+    ///
+    /// The `initial` text is what the [`Prompt`] of `mode` will have
+    /// if the picking is cancelled.
+    pub fn new(mode: M, initial: impl ToString) -> Prompt<U, Self> {
+        Prompt::new(Self {
+            mode,
+            initial: initial.to_string(),
+            entries: entries::<M>(),
+            query: None,
+            matches: Vec::new(),
+            selected: 0,
+            ghost: PhantomData,
+        })
+    }
+
+    /// The currently selected entry
+    fn selected(&self) -> Option<&str> {
+        let (i, _) = self.matches.get(self.selected)?;
+        Some(&self.entries[*i])
+    }
+}
+
+impl<M: PromptMode<U>, U: Ui> PromptMode<U> for HistoryPicker<M, U> {
+    type ExitWidget = M::ExitWidget;
+
+    fn update(&mut self, _: &mut Pass, mut text: Text, _: &<U as Ui>::Area) -> Text {
+        text.remove_tags(*TAGGER, ..);
+
+        let query = text.to_string();
+        if self.query.as_ref() != Some(&query) {
+            self.matches = (0..self.entries.len())
+                .rev()
+                .filter_map(|i| Some((i, fuzzy_match(&query, &self.entries[i])?)))
+                .collect();
+            self.selected = 0;
+            self.query = Some(query);
+        }
+
+        let ghost = if let Some((i, matched)) = self.matches.get(self.selected) {
+            let mut b = Text::builder();
+            b.push(txt!("  "));
+            for (byte, char) in self.entries[*i].char_indices() {
+                if matched.contains(&byte) {
+                    b.push(txt!("[history.match]{char}"));
+                } else {
+                    b.push(txt!("[history.entry]{char}"));
+                }
+            }
+            b.push(txt!(
+                "  [history.count]({}/{})",
+                self.selected + 1,
+                self.matches.len()
+            ));
+            b.build()
+        } else {
+            txt!("  [history.count](no matches)").build()
+        };
+
+        let end = text.len().byte() - 1;
+        text.insert_tag(*TAGGER, end, Ghost(ghost));
+
+        text
+    }
+
+    fn on_switch(&mut self, pa: &mut Pass, text: Text, area: &<U as Ui>::Area) -> Text {
+        self.update(pa, text, area)
+    }
+
+    fn before_exit(&mut self, _: &mut Pass, text: Text, _: &<U as Ui>::Area) {
+        let text = match self.selected() {
+            Some(entry) if !text.is_empty() => entry.to_string(),
+            _ => self.initial.clone(),
+        };
+
+        mode::set(Prompt::new_with(self.mode.clone(), text));
+    }
+
+    fn send_key(&mut self, _: &mut Pass, key: KeyEvent) -> bool {
+        use KeyCode::*;
+        match key {
+            key!(Up) | key!(Char('r'), KeyMod::CONTROL) => {
+                self.selected = (self.selected + 1).min(self.matches.len().saturating_sub(1));
+            }
+            key!(Down) | key!(Char('s'), KeyMod::CONTROL) => {
+                self.selected = self.selected.saturating_sub(1)
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn once() {
+        form::set_weak("history.entry", "default");
+        form::set_weak("history.match", "accent");
+        form::set_weak("history.count", "default.info");
+    }
+
+    fn prompt(&self) -> Text {
+        txt!("[prompt]history").build()
+    }
+
+    fn keeps_history(&self) -> bool {
+        false
+    }
+
+    fn return_handle(&self) -> Option<Handle<Self::ExitWidget, U>> {
+        self.mode.return_handle()
+    }
+}
+
+impl<M: PromptMode<U>, U: Ui> Clone for HistoryPicker<M, U> {
+    fn clone(&self) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            initial: self.initial.clone(),
+            entries: self.entries.clone(),
+            query: self.query.clone(),
+            matches: self.matches.clone(),
+            selected: self.selected,
+            ghost: PhantomData,
+        }
+    }
+}
+
+/// The bytes of the `entry` that match the `query`, if it matches
+///
+/// The match is case insensitive, and the characters of the `query`
+/// must show up in order, not necessarily next to each other.
+fn fuzzy_match(query: &str, entry: &str) -> Option<Vec<usize>> {
+    let mut entry_chars = entry.char_indices();
+    query
+        .chars()
+        .map(|q| {
+            entry_chars
+                .find(|(_, e)| e.to_lowercase().eq(q.to_lowercase()))
+                .map(|(byte, _)| byte)
+        })
+        .collect()
+}
@@ -7,7 +7,18 @@
 //!
 //! [`duat-core`]: duat_core
 //! [`Cursor`]: duat_core::mode::Cursor
-use duat_core::{prelude::*, text::Searcher};
+use std::sync::{LazyLock, Mutex};
+
+use duat_core::{
+    data::RwData,
+    hook::WidgetUpdated,
+    prelude::*,
+    text::{SearchOpts, Searcher},
+};
+
+use super::ReplaceWith;
+
+pub(super) static REPLACE_TAGGER: LazyLock<Tagger> = LazyLock::new(Tagger::new);
 
 /// An abstraction trait used to handle incremental search
 ///
@@ -19,6 +30,7 @@ use duat_core::{prelude::*, text::Searcher};
 /// - [`SearchRev`]: In each cursor, searches backwards for the match
 /// - [`ExtendFwd`]: In each cursor, extends forward for the match
 /// - [`ExtendRev`]: In each cursor, extends backwards for the match
+/// - [`Replace`]: In each cursor, replaces the matches
 ///
 /// Here is how you can implement this trait yourself:
 ///
@@ -57,10 +69,28 @@ use duat_core::{prelude::*, text::Searcher};
 /// There are more advanced implementations in the [`duat-kak`] crate
 ///
 /// [`duat-kak`]: https://docs.rs/duat-kak
+#[allow(unused_variables)]
 pub trait IncSearcher<U: Ui>: Clone + Send + 'static {
     /// Performs the incremental search
     fn search(&mut self, pa: &mut Pass, handle: Handle<File<U>, U, Searcher>);
 
+    /// What to do once the search is finished
+    ///
+    /// The pattern is [`None`] if the search was cancelled or if the
+    /// pattern wasn't a valid regex. The [`SearchOpts`] are the ones
+    /// that were in use when the search finished.
+    fn finish(&mut self, pa: &mut Pass, pat: Option<&str>, opts: SearchOpts) {}
+
+    /// The [`SearchOpts`] that the search starts with
+    ///
+    /// These can be toggled while searching, see [`IncSearch`] for
+    /// the keys that do so.
+    ///
+    /// [`IncSearch`]: super::IncSearch
+    fn opts(&self) -> SearchOpts {
+        SearchOpts::default()
+    }
+
     /// What prompt to show in the [`PromptLine`]
     ///
     /// [`PromptLine`]: crate::widgets::PromptLine
@@ -170,3 +200,181 @@ impl<U: Ui> IncSearcher<U> for ExtendRev {
         txt!("[prompt]rev search (extend)").build()
     }
 }
+
+/// Replaces the matches within each [`Cursor`]'s selection
+///
+/// While typing the pattern, every match within the selections is
+/// highlighted. Once it is confirmed, a [`ReplaceWith`] [`Prompt`]
+/// is opened, where you type the replacement, which can make use of
+/// the capture groups of the pattern.
+///
+/// [`Cursor`]: duat_core::mode::Cursor
+/// [`Prompt`]: super::Prompt
+#[derive(Clone, Copy)]
+pub struct Replace;
+
+impl<U: Ui> IncSearcher<U> for Replace {
+    fn search(&mut self, pa: &mut Pass, handle: Handle<File<U>, U, Searcher>) {
+        let mut matches = Vec::new();
+        handle.edit_all(pa, |mut e| {
+            let [_, end] = e.range();
+            let caret_was_on_end = e.set_caret_on_start();
+            matches.extend(e.search_inc_fwd(Some(end)));
+            if caret_was_on_end {
+                e.swap_ends();
+            }
+        });
+
+        let text = handle.text_mut(pa);
+        text.remove_tags(*REPLACE_TAGGER, ..);
+
+        let id = form::id_of!("replace.match");
+        for [start, end] in matches {
+            text.insert_tag(*REPLACE_TAGGER, start..end, id.to_tag(0));
+        }
+    }
+
+    fn finish(&mut self, pa: &mut Pass, pat: Option<&str>, opts: SearchOpts) {
+        if let Some(pat) = pat {
+            mode::set(ReplaceWith::<U>::with_opts(pat, opts));
+        } else {
+            let handle = context::fixed_file::<U>(pa).unwrap();
+            handle.text_mut(pa).remove_tags(*REPLACE_TAGGER, ..);
+        }
+    }
+
+    fn prompt(&self) -> Text {
+        txt!("[prompt]replace").build()
+    }
+}
+
+////////// Match highlighting
+
+/// The matches of the current [`IncSearch`]
+///
+/// This can be shown in a [`StatusLine`] through
+/// [`state::search_txt`], which is a part of the default
+/// [`StatusLine`].
+///
+/// [`IncSearch`]: super::IncSearch
+/// [`StatusLine`]: crate::widgets::StatusLine
+/// [`state::search_txt`]: crate::state::search_txt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchMatches {
+    /// The position of the match on the main selection, starting at
+    /// 1
+    ///
+    /// Is [`None`] if the main selection doesn't start on a match.
+    pub current: Option<usize>,
+    /// The total number of matches in the [`File`]
+    pub total: usize,
+}
+
+pub(crate) static SEARCH_MATCHES: LazyLock<RwData<Option<SearchMatches>>> =
+    LazyLock::new(|| RwData::new(None));
+static SEARCH_TAGGER: LazyLock<Tagger> = LazyLock::new(Tagger::new);
+static HIGHLIGHT: Mutex<Option<Highlight>> = Mutex::new(None);
+
+/// The pattern being highlighted, and where it has been highlighted
+struct Highlight {
+    pat: String,
+    opts: SearchOpts,
+    range: Option<[Point; 2]>,
+}
+
+/// Counts the matches of a pattern, and highlights them on screen
+///
+/// The highlighting is done later, once the [`File`] has scrolled to
+/// the new position of the main selection, and is redone whenever
+/// it scrolls again.
+pub(super) fn highlight_matches<U: Ui>(pa: &mut Pass, pat: &str, opts: SearchOpts) {
+    if pat.is_empty() {
+        clear_highlight::<U>(pa);
+        return;
+    }
+
+    let handle = context::fixed_file::<U>(pa).unwrap();
+    let file = handle.read(pa);
+    let Ok(mut searcher) = Searcher::new_with(pat.to_string(), opts, file.print_cfg().word_chars)
+    else {
+        clear_highlight::<U>(pa);
+        return;
+    };
+
+    let text = file.text();
+    let main_start = text.selections().get_main().map(|main| main.start());
+
+    let mut matches = SearchMatches { current: None, total: 0 };
+    for [start, _] in searcher.search_fwd(text, ..) {
+        matches.total += 1;
+        if Some(start) == main_start {
+            matches.current = Some(matches.total);
+        }
+    }
+
+    *SEARCH_MATCHES.write(pa) = Some(matches);
+    *HIGHLIGHT.lock().unwrap() = Some(Highlight { pat: pat.to_string(), opts, range: None });
+
+    context::queue(|pa| {
+        if let Ok(handle) = context::fixed_file::<U>(pa) {
+            update_highlight(pa, &handle);
+        }
+    });
+}
+
+/// Redoes the highlighting whenever the fixed [`File`] is updated
+///
+/// This is called by [`IncSearch::once`], so it may be added more
+/// than once for the same [`Ui`], but [`update_highlight`] does
+/// nothing if the visible range was already highlighted.
+///
+/// [`IncSearch::once`]: super::PromptMode::once
+pub(super) fn add_highlight_hook<U: Ui>() {
+    hook::add::<WidgetUpdated<File<U>, U>, U>(|pa, handle| {
+        if context::fixed_file::<U>(pa).is_ok_and(|fixed| fixed == *handle) {
+            update_highlight(pa, handle);
+        }
+    });
+}
+
+/// Highlights the matches in the visible range of the [`File`]
+///
+/// Does nothing if that range was already highlighted, so it is
+/// cheap to call whenever the [`File`] might have scrolled.
+fn update_highlight<U: Ui>(pa: &mut Pass, handle: &Handle<File<U>, U>) {
+    let mut highlight = HIGHLIGHT.lock().unwrap();
+    let Some(highlight) = highlight.as_mut() else {
+        return;
+    };
+
+    let range = [handle.start_points(pa).0, handle.end_points(pa).0];
+    if highlight.range == Some(range) {
+        return;
+    }
+    highlight.range = Some(range);
+
+    let word_chars = handle.read(pa).print_cfg().word_chars;
+    let Ok(mut searcher) = Searcher::new_with(highlight.pat.clone(), highlight.opts, word_chars)
+    else {
+        return;
+    };
+
+    let text = handle.text_mut(pa);
+    let matches: Vec<[Point; 2]> = searcher.search_fwd(&*text, range[0]..range[1]).collect();
+
+    text.remove_tags(*SEARCH_TAGGER, ..);
+    let id = form::id_of!("search.match");
+    for [start, end] in matches {
+        text.insert_tag(*SEARCH_TAGGER, start..end, id.to_tag(0));
+    }
+}
+
+/// Removes the highlighted matches, and the match count
+pub(super) fn clear_highlight<U: Ui>(pa: &mut Pass) {
+    *HIGHLIGHT.lock().unwrap() = None;
+    *SEARCH_MATCHES.write(pa) = None;
+
+    if let Ok(handle) = context::fixed_file::<U>(pa) {
+        handle.text_mut(pa).remove_tags(*SEARCH_TAGGER, ..);
+    }
+}
@@ -38,13 +38,20 @@
 //!
 //! [`Mode`]: duat_core::mode::Mode
 //! [`Cursor`]: duat_core::mode::Cursor
+pub(crate) use self::inc_search::SEARCH_MATCHES;
 pub use self::{
-    inc_search::{ExtendFwd, ExtendRev, IncSearcher, SearchFwd, SearchRev},
-    prompt::{IncSearch, PipeSelections, Prompt, PromptMode, RunCommands},
+    grep::Grep,
+    history::HistoryPicker,
+    inc_search::{ExtendFwd, ExtendRev, IncSearcher, Replace, SearchFwd, SearchMatches, SearchRev},
+    prompt::{
+        IncSearch, PipeSelections, Prompt, PromptMode, ReloadOrKeep, ReplaceWith, RunCommands,
+    },
     regular::Regular,
     pager::{Pager, PagerSearch}
 };
 
+mod grep;
+mod history;
 mod inc_search;
 mod prompt;
 mod regular;
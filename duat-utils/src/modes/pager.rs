@@ -14,24 +14,35 @@ static SEARCH: Mutex<String> = Mutex::new(String::new());
 static PAGER_TAGGER: LazyLock<Tagger> = LazyLock::new(Tagger::new);
 
 /// A simple mode, meant for scrolling and searching through [`Text`]
-pub struct Pager<W: Widget<U>, U: Ui>(PhantomData<(W, U)>);
+///
+/// While in this mode, the [`Text`] is [read-only].
+///
+/// [read-only]: Text::set_read_only
+pub struct Pager<W: Widget<U>, U: Ui>(bool, PhantomData<(W, U)>);
 
 impl<W: Widget<U>, U: Ui> Pager<W, U> {
     /// Returns a new [`Pager`]
     pub fn new() -> Self {
-        Self(PhantomData)
+        Self(false, PhantomData)
     }
 }
 
 impl<W: Widget<U>, U: Ui> Mode<U> for Pager<W, U> {
     type Widget = W;
 
+    fn on_switch(&mut self, pa: &mut Pass, handle: Handle<Self::Widget, U>) {
+        let text = handle.write(pa).text_mut();
+        self.0 = text.is_read_only();
+        text.set_read_only(true);
+    }
+
     fn send_key(&mut self, pa: &mut Pass, key: KeyEvent, handle: Handle<Self::Widget, U>) {
         use KeyCode::*;
+        let count = mode::count().unwrap_or(1);
         match (key, duat_core::mode::alt_is_reverse()) {
-            (key!(Char('j') | Down), _) => handle.scroll_ver(pa, 1),
+            (key!(Char('j') | Down), _) => handle.scroll_ver(pa, count as i32),
             (key!(Char('J')) | key!(Down, KeyMod::SHIFT), _) => handle.scroll_ver(pa, i32::MAX),
-            (key!(Char('k') | Up), _) => handle.scroll_ver(pa, -1),
+            (key!(Char('k') | Up), _) => handle.scroll_ver(pa, -(count as i32)),
             (key!(Char('K')) | key!(Down, KeyMod::SHIFT), _) => handle.scroll_ver(pa, i32::MIN),
             (key!(Char('/')), _) => mode::set::<U>(PagerSearch::new(pa, &handle, true)),
             (key!(Char('/'), KeyMod::ALT), true) | (key!(Char('?')), false) => {
@@ -43,7 +54,8 @@ impl<W: Widget<U>, U: Ui> Mode<U> for Pager<W, U> {
                 let (point, _) = handle.start_points(pa);
 
                 let text = handle.read(pa).text();
-                let Some([point, _]) = text.search_fwd(&*se, point..).unwrap().next() else {
+                let mut matches = text.search_fwd(&*se, point..).unwrap();
+                let Some([point, _]) = matches.nth(count - 1) else {
                     context::error!("[a]{se}[] was not found");
                     return;
                 };
@@ -56,7 +68,8 @@ impl<W: Widget<U>, U: Ui> Mode<U> for Pager<W, U> {
                 let (point, _) = handle.start_points(pa);
 
                 let text = handle.read(pa).text();
-                let Some([point, _]) = text.search_rev(&*se, ..point).unwrap().next() else {
+                let mut matches = text.search_rev(&*se, ..point).unwrap();
+                let Some([point, _]) = matches.nth(count - 1) else {
                     context::error!("[a]{se}[] was not found");
                     return;
                 };
@@ -68,11 +81,22 @@ impl<W: Widget<U>, U: Ui> Mode<U> for Pager<W, U> {
             _ => {}
         }
     }
+
+    fn before_exit(&mut self, pa: &mut Pass, handle: Handle<Self::Widget, U>) {
+        handle.write(pa).text_mut().set_read_only(self.0);
+    }
+
+    fn count_digit(&self, key: KeyEvent) -> Option<u32> {
+        match key {
+            key!(KeyCode::Char(char)) => char.to_digit(10),
+            _ => None,
+        }
+    }
 }
 
 impl<W: Widget<U>, U: Ui> Clone for Pager<W, U> {
     fn clone(&self) -> Self {
-        Self(PhantomData)
+        Self(self.0, PhantomData)
     }
 }
 
@@ -1,8 +1,16 @@
 use std::{io::Write, marker::PhantomData, sync::LazyLock};
 
-use duat_core::{prelude::*, text::Searcher};
+use duat_core::{
+    file,
+    prelude::*,
+    text::{Case, SearchOpts, Searcher},
+};
 
-use super::IncSearcher;
+use super::{
+    IncSearcher,
+    history::{self, HistoryPicker},
+    inc_search::{self, REPLACE_TAGGER},
+};
 use crate::{
     hooks::{SearchPerformed, SearchUpdated},
     widgets::PromptLine,
@@ -31,10 +39,21 @@ static TAGGER: LazyLock<Tagger> = LazyLock::new(Tagger::new);
 ///   actually do. I.e. will it search for the next ocurrence, split
 ///   selections by matches, things of the sort.
 ///
+/// Every [`PromptMode`] that [keeps a history] can go through it by
+/// pressing `Up` and `Down`, which only goes through the entries that
+/// start with what was typed. Pressing `Ctrl+R` opens a
+/// [`HistoryPicker`], to fuzzy search through it instead.
+///
 /// [`Parameter`]: cmd::Parameter
 /// [`Selection`]: mode::Selection
+/// [keeps a history]: PromptMode::keeps_history
 #[derive(Clone)]
-pub struct Prompt<U: Ui, M: PromptMode<U> = RunCommands>(M, String, PhantomData<U>);
+pub struct Prompt<U: Ui, M: PromptMode<U> = RunCommands>(
+    M,
+    String,
+    Option<(String, usize)>,
+    PhantomData<U>,
+);
 
 impl<M: PromptMode<U>, U: Ui> Prompt<U, M> {
     /// Returns a new [`Prompt`] from this [`PromptMode`]
@@ -43,12 +62,54 @@ impl<M: PromptMode<U>, U: Ui> Prompt<U, M> {
     /// [`PromptMode`] implementors return a [`Prompt<Self, U>`],
     /// rather than the [`PromptMode`] itself.
     pub fn new(mode: M) -> Self {
-        Self(mode, String::new(), PhantomData)
+        Self(mode, String::new(), None, PhantomData)
     }
 
 	/// Returns a new [`Prompt`] with some initial text
     pub fn new_with(mode: M, initial: impl ToString) -> Self {
-        Self(mode, initial.to_string(), PhantomData)
+        Self(mode, initial.to_string(), None, PhantomData)
+    }
+
+    /// The [`Ghost`] with the prompt, at the start of the
+    /// [`PromptLine`]
+    fn prompt_ghost(&self, pl: &PromptLine<U>) -> Ghost {
+        Ghost(match pl.prompt_of::<M>() {
+            Some(text) => txt!("{text}[prompt.colon]:").build(),
+            None => txt!("{}[prompt.colon]:", self.0.prompt()).build(),
+        })
+    }
+
+    /// Goes to an older or newer entry in the history
+    ///
+    /// Only the entries that start with what was typed before
+    /// browsing are considered. Going past the newest one brings
+    /// back what was typed.
+    fn browse_history(&mut self, pa: &mut Pass, handle: &Handle<PromptLine<U>, U>, older: bool) {
+        let entries = history::entries::<M>();
+        let (prefix, i) = self
+            .2
+            .get_or_insert_with(|| (handle.read(pa).text().to_string(), entries.len()));
+
+        let next = if older {
+            entries[..*i].iter().rposition(|e| e.starts_with(&*prefix))
+        } else {
+            entries
+                .get(*i + 1..)
+                .and_then(|later| later.iter().position(|e| e.starts_with(&*prefix)))
+                .map(|j| *i + 1 + j)
+        };
+
+        match next {
+            Some(j) => {
+                *i = j;
+                set_text(pa, handle, &entries[j]);
+            }
+            None if !older => {
+                *i = entries.len();
+                set_text(pa, handle, prefix);
+            }
+            None => {}
+        }
     }
 }
 
@@ -56,9 +117,13 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
     type Widget = PromptLine<U>;
 
     fn send_key(&mut self, pa: &mut Pass, key: KeyEvent, handle: Handle<Self::Widget, U>) {
-        let mut update = |pa: &mut Pass| {
+        if !matches!(key, key!(KeyCode::Up | KeyCode::Down)) {
+            self.2 = None;
+        }
+
+        let update = |pa: &mut Pass, mode: &mut M| {
             let text = std::mem::take(handle.write(pa).text_mut());
-            let text = self.0.update(pa, text, handle.area(pa));
+            let text = mode.update(pa, text, handle.area(pa));
             *handle.write(pa).text_mut() = text;
         };
 
@@ -67,7 +132,8 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
                 if handle.read(pa).text().is_empty() {
                     handle.write(pa).text_mut().selections_mut().clear();
 
-                    update(pa);
+                    self.0.on_cancel(pa);
+                    update(pa, &mut self.0);
 
                     if let Some(ret_handle) = self.0.return_handle() {
                         mode::reset_to(ret_handle);
@@ -81,12 +147,12 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
                         e.replace("");
                         e.unset_anchor();
                     });
-                    update(pa);
+                    update(pa, &mut self.0);
                 }
             }
             key!(KeyCode::Delete) => {
                 handle.edit_main(pa, |mut e| e.replace(""));
-                update(pa);
+                update(pa, &mut self.0);
             }
 
             key!(KeyCode::Char(char)) => {
@@ -94,15 +160,15 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
                     e.insert(char);
                     e.move_hor(1);
                 });
-                update(pa);
+                update(pa, &mut self.0);
             }
             key!(KeyCode::Left) => {
                 handle.edit_main(pa, |mut e| e.move_hor(-1));
-                update(pa);
+                update(pa, &mut self.0);
             }
             key!(KeyCode::Right) => {
                 handle.edit_main(pa, |mut e| e.move_hor(1));
-                update(pa);
+                update(pa, &mut self.0);
             }
 
             key!(KeyCode::Esc) => {
@@ -114,7 +180,9 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
                     e.replace("");
                 });
                 handle.write(pa).text_mut().selections_mut().clear();
-                update(pa);
+
+                self.0.on_cancel(pa);
+                update(pa, &mut self.0);
 
                 if let Some(ret_handle) = self.0.return_handle() {
                     mode::reset_to(ret_handle);
@@ -122,10 +190,30 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
                     mode::reset::<M::ExitWidget, U>();
                 }
             }
+            key!(KeyCode::Up) if self.0.keeps_history() => {
+                self.browse_history(pa, &handle, true);
+                update(pa, &mut self.0);
+            }
+            key!(KeyCode::Down) if self.0.keeps_history() => {
+                self.browse_history(pa, &handle, false);
+                update(pa, &mut self.0);
+            }
+            key!(KeyCode::Char('r'), KeyMod::CONTROL) if self.0.keeps_history() => {
+                let initial = handle.read(pa).text().to_string();
+                set_text(pa, &handle, "");
+                handle.write(pa).text_mut().selections_mut().clear();
+                update(pa, &mut self.0);
+
+                mode::set(HistoryPicker::new(self.0.clone(), initial));
+            }
+
             key!(KeyCode::Enter) => {
+                if self.0.keeps_history() {
+                    history::push::<M>(handle.read(pa).text().to_string());
+                }
                 handle.write(pa).text_mut().selections_mut().clear();
 
-                update(pa);
+                update(pa, &mut self.0);
 
                 if let Some(ret_handle) = self.0.return_handle() {
                     mode::reset_to(ret_handle);
@@ -133,10 +221,35 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
                     mode::reset::<M::ExitWidget, U>();
                 }
             }
-            _ => {}
+            _ => {
+                if self.0.send_key(pa, key) {
+                    let pl = handle.write(pa);
+                    let tag = self.prompt_ghost(pl);
+                    pl.text_mut().remove_tags(*PROMPT_TAGGER, ..);
+                    pl.text_mut().insert_tag(*PROMPT_TAGGER, 0, tag);
+                    update(pa, &mut self.0);
+                }
+            }
         }
     }
 
+    fn paste(&mut self, pa: &mut Pass, text: String, handle: Handle<Self::Widget, U>) {
+        self.2 = None;
+
+        // The prompt is a single line, so the lines are joined.
+        let text = text.lines().collect::<Vec<_>>().join(" ");
+        let chars = text.chars().count() as i32;
+
+        handle.edit_main(pa, |mut e| {
+            e.insert(&text);
+            e.move_hor(chars);
+        });
+
+        let text = std::mem::take(handle.write(pa).text_mut());
+        let text = self.0.update(pa, text, handle.area(pa));
+        *handle.write(pa).text_mut() = text;
+    }
+
     fn on_switch(&mut self, pa: &mut Pass, handle: Handle<Self::Widget, U>) {
         let text = {
             let pl = handle.write(pa);
@@ -144,18 +257,25 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
             pl.text_mut().replace_range(0..0, &self.1);
             run_once::<M, U>();
 
-            let tag = Ghost(match pl.prompt_of::<M>() {
-                Some(text) => txt!("{text}[prompt.colon]:").build(),
-                None => txt!("{}[prompt.colon]:", self.0.prompt()).build(),
-            });
+            let tag = self.prompt_ghost(pl);
             pl.text_mut().insert_tag(*PROMPT_TAGGER, 0, tag);
 
             std::mem::take(pl.text_mut())
         };
 
         let text = self.0.on_switch(pa, text, handle.area(pa));
-
         *handle.write(pa).text_mut() = text;
+
+        // Initial text, like the one from the history, should be
+        // treated as if it had been typed.
+        if !self.1.is_empty() {
+            let p = handle.read(pa).text().len();
+            handle.edit_main(pa, |mut e| e.move_to(p));
+
+            let text = std::mem::take(handle.write(pa).text_mut());
+            let text = self.0.update(pa, text, handle.area(pa));
+            *handle.write(pa).text_mut() = text;
+        }
     }
 
     fn before_exit(&mut self, pa: &mut Pass, handle: Handle<Self::Widget, U>) {
@@ -164,6 +284,22 @@ impl<M: PromptMode<U>, U: Ui> mode::Mode<U> for Prompt<U, M> {
     }
 }
 
+/// Replaces the [`Text`] of the [`PromptLine`], placing the caret at
+/// its end
+fn set_text<U: Ui>(pa: &mut Pass, handle: &Handle<PromptLine<U>, U>, new: &str) {
+    let p = handle.read(pa).text().len();
+    handle.edit_main(pa, |mut e| {
+        e.move_to_start();
+        e.set_anchor();
+        e.move_to(p);
+        e.replace(new);
+        e.unset_anchor();
+        if !new.is_empty() {
+            e.move_hor(1);
+        }
+    });
+}
+
 /// A mode to control the [`Prompt`], by acting on its [`Text`] and
 /// [`U::Area`]
 ///
@@ -252,9 +388,37 @@ pub trait PromptMode<U: Ui>: Clone + Send + 'static {
     /// finishes the search, etc.
     fn before_exit(&mut self, pa: &mut Pass, text: Text, area: &U::Area) {}
 
+    /// What to do when the [`Prompt`] is cancelled
+    ///
+    /// This happens upon pressing esc or backspace in an empty
+    /// [`PromptLine`], and is followed by a call to
+    /// [`PromptMode::before_exit`] with an empty [`Text`].
+    fn on_cancel(&mut self, pa: &mut Pass) {}
+
+    /// Handles a key that the [`Prompt`] doesn't handle by itself
+    ///
+    /// These are keys like `Alt` and `Control` chords, which don't
+    /// insert anything in the [`PromptLine`]. If this returns `true`,
+    /// the [prompt] is shown again, and the [`Text`] is [updated].
+    ///
+    /// [prompt]: PromptMode::prompt
+    /// [updated]: PromptMode::update
+    fn send_key(&mut self, pa: &mut Pass, key: KeyEvent) -> bool {
+        false
+    }
+
     /// Things to do when this [`PromptMode`] is first instantiated
     fn once() {}
 
+    /// Whether the [`Prompt`] should keep a history of what was
+    /// confirmed in this [`PromptMode`]
+    ///
+    /// The history is shared by all [`PromptMode`]s with the same
+    /// type, including type arguments, and is stored in Duat's cache.
+    fn keeps_history(&self) -> bool {
+        true
+    }
+
     /// What text should be at the beginning of the [`PromptLine`], as
     /// a [`Ghost`]
     fn prompt(&self) -> Text;
@@ -348,23 +512,36 @@ impl<U: Ui> PromptMode<U> for RunCommands {
 /// ```
 ///
 /// This function returns a [`Prompt<IncSearch<SearchFwd, U>, U>`],
+///
+/// While searching, the [`SearchOpts`] can be toggled with the
+/// following keys, starting from the [`IncSearcher::opts`]:
+///
+/// - `Alt+c` cycles between case sensitive, case insensitive and
+///   smart case matching.
+/// - `Alt+l` toggles literal matching, instead of regex matching.
+/// - `Alt+w` toggles matching only whole words.
 #[derive(Clone)]
 pub struct IncSearch<I: IncSearcher<U>, U: Ui> {
     inc: I,
     orig: Option<(mode::Selections, <U::Area as Area>::PrintInfo)>,
     ghost: PhantomData<U>,
     prev: String,
+    opts: SearchOpts,
+    prev_opts: SearchOpts,
 }
 
 impl<I: IncSearcher<U>, U: Ui> IncSearch<I, U> {
     /// Returns a [`Prompt`] with [`IncSearch<I, U>`] as its
     /// [`PromptMode`]
     pub fn new(inc: I) -> Prompt<U, Self> {
+        let opts = inc.opts();
         Prompt::new(Self {
             inc,
             orig: None,
             ghost: PhantomData,
             prev: String::new(),
+            opts,
+            prev_opts: opts,
         })
     }
 }
@@ -376,28 +553,35 @@ impl<I: IncSearcher<U>, U: Ui> PromptMode<U> for IncSearch<I, U> {
 
         let handle = context::fixed_file::<U>(pa).unwrap();
 
-        if text == self.prev {
+        if text == self.prev && self.opts == self.prev_opts {
             return text;
-        } else {
+        } else if text != self.prev {
             let prev = std::mem::replace(&mut self.prev, text.to_string());
             hook::queue(SearchUpdated((prev, self.prev.clone())));
         }
+        self.prev_opts = self.opts;
 
-        match Searcher::new(text.to_string()) {
+        let word_chars = handle.read(pa).print_cfg().word_chars;
+        match Searcher::new_with(text.to_string(), self.opts, word_chars) {
             Ok(searcher) => {
                 let (file, area) = handle.write_with_area(pa);
                 area.set_print_info(orig_print_info.clone());
                 *file.selections_mut() = orig_selections.clone();
 
-                let ast = regex_syntax::ast::parse::Parser::new()
-                    .parse(&text.to_string())
-                    .unwrap();
+                if !self.opts.literal {
+                    let ast = regex_syntax::ast::parse::Parser::new()
+                        .parse(&text.to_string())
+                        .unwrap();
 
-                crate::tag_from_ast(*TAGGER, &mut text, &ast);
+                    crate::tag_from_ast(*TAGGER, &mut text, &ast);
+                }
 
                 self.inc.search(pa, handle.attach_searcher(searcher));
+                inc_search::highlight_matches::<U>(pa, &text.to_string(), self.opts);
             }
             Err(err) => {
+                inc_search::clear_highlight::<U>(pa);
+
                 let regex_syntax::Error::Parse(err) = *err else {
                     unreachable!("As far as I can tell, regex_syntax has goofed up");
                 };
@@ -423,26 +607,53 @@ impl<I: IncSearcher<U>, U: Ui> PromptMode<U> for IncSearch<I, U> {
         text
     }
 
-    fn before_exit(&mut self, _: &mut Pass, text: Text, _: &<U as Ui>::Area) {
-        if !text.is_empty() {
-            if let Err(err) = Searcher::new(text.to_string()) {
-                let regex_syntax::Error::Parse(err) = *err else {
-                    unreachable!("As far as I can tell, regex_syntax has goofed up");
-                };
+    fn before_exit(&mut self, pa: &mut Pass, text: Text, _: &<U as Ui>::Area) {
+        let word_chars = context::fixed_file::<U>(pa)
+            .unwrap()
+            .read(pa)
+            .print_cfg()
+            .word_chars;
+
+        inc_search::clear_highlight::<U>(pa);
+
+        if text.is_empty() {
+            self.inc.finish(pa, None, self.opts);
+        } else if let Err(err) = Searcher::new_with(text.to_string(), self.opts, word_chars) {
+            let regex_syntax::Error::Parse(err) = *err else {
+                unreachable!("As far as I can tell, regex_syntax has goofed up");
+            };
 
-                let range = err.span().start.offset..err.span().end.offset;
-                let err = txt!(
-                    "[a]{:?}, \"{}\"[prompt.colon]:[] {}",
-                    range,
-                    text.strs(range).unwrap(),
-                    err.kind()
-                );
+            let range = err.span().start.offset..err.span().end.offset;
+            let err = txt!(
+                "[a]{:?}, \"{}\"[prompt.colon]:[] {}",
+                range,
+                text.strs(range).unwrap(),
+                err.kind()
+            );
 
-                context::error!(target: self.inc.prompt().to_string(), "{err}")
-            } else {
-                hook::queue(SearchPerformed(text.to_string()));
+            context::error!(target: self.inc.prompt().to_string(), "{err}");
+            self.inc.finish(pa, None, self.opts);
+        } else {
+            hook::queue(SearchPerformed(text.to_string()));
+            self.inc.finish(pa, Some(&text.to_string()), self.opts);
+        }
+    }
+
+    fn send_key(&mut self, _: &mut Pass, key: KeyEvent) -> bool {
+        match key {
+            key!(KeyCode::Char('c'), KeyMod::ALT) => {
+                self.opts.case = match self.opts.case {
+                    Case::Sensitive => Case::Insensitive,
+                    Case::Insensitive => Case::Smart,
+                    Case::Smart => Case::Sensitive,
+                }
             }
+            key!(KeyCode::Char('l'), KeyMod::ALT) => self.opts.literal = !self.opts.literal,
+            key!(KeyCode::Char('w'), KeyMod::ALT) => self.opts.whole_word = !self.opts.whole_word,
+            _ => return false,
         }
+
+        true
     }
 
     fn once() {
@@ -450,10 +661,139 @@ impl<I: IncSearcher<U>, U: Ui> PromptMode<U> for IncSearch<I, U> {
         form::set_weak("regex.operator", "operator");
         form::set_weak("regex.class", "constant");
         form::set_weak("regex.bracket", "punctuation.bracket");
+        form::set_weak("replace.match", "selection.extra");
+        form::set_weak("replace.new", "accent");
+        form::set_weak("search.opts", "default.info");
+        form::set_weak("search.match", Form::underlined());
+
+        inc_search::add_highlight_hook::<U>();
     }
 
     fn prompt(&self) -> Text {
-        txt!("{}", self.inc.prompt()).build()
+        let case = match self.opts.case {
+            Case::Sensitive => None,
+            Case::Insensitive => Some("nocase"),
+            Case::Smart => Some("smartcase"),
+        };
+        let literal = self.opts.literal.then_some("literal");
+        let whole_word = self.opts.whole_word.then_some("word");
+
+        let opts: Vec<&str> = [case, literal, whole_word].into_iter().flatten().collect();
+        if opts.is_empty() {
+            txt!("{}", self.inc.prompt()).build()
+        } else {
+            txt!("{} [search.opts]({})", self.inc.prompt(), opts.join(",")).build()
+        }
+    }
+}
+
+/// The [`PromptMode`] that replaces the matches of a [`Replace`]
+///
+/// While typing the replacement, each match within the selections of
+/// the [`File`] is concealed, and what it would be replaced with is
+/// shown in its place, as a [`Ghost`].
+///
+/// Confirming the replacement makes it in all selections at once, so
+/// it can be undone as a single [`Moment`]. Confirming an empty
+/// replacement removes the matches, while cancelling does nothing.
+///
+/// [`Replace`]: super::Replace
+#[derive(Clone)]
+pub struct ReplaceWith<U: Ui>(String, SearchOpts, bool, PhantomData<U>);
+
+impl<U: Ui> ReplaceWith<U> {
+    /// Returns a [`Prompt`] with [`ReplaceWith`] as its
+    /// [`PromptMode`]
+    pub fn new(pat: impl ToString) -> Prompt<U, Self> {
+        Self::with_opts(pat, SearchOpts::default())
+    }
+
+    /// Returns a [`Prompt`] with [`ReplaceWith`] as its
+    /// [`PromptMode`], interpreting the pattern with [`SearchOpts`]
+    pub fn with_opts(pat: impl ToString, opts: SearchOpts) -> Prompt<U, Self> {
+        Prompt::new(Self(pat.to_string(), opts, false, PhantomData))
+    }
+
+    /// Previews the replacements on the [`File`]
+    fn preview(&self, pa: &mut Pass, replacement: &str) {
+        let handle = context::fixed_file::<U>(pa).unwrap();
+        let word_chars = handle.read(pa).print_cfg().word_chars;
+        let text = handle.text_mut(pa);
+        text.remove_tags(*REPLACE_TAGGER, ..);
+
+        let pat = self.1.pattern(&self.0);
+        let mut replacements = Vec::new();
+        for (selection, _) in text.selections().iter() {
+            let [start, end] = selection.point_range(text);
+            let Ok(iter) = text.replacements(&pat, replacement, start..end) else {
+                return;
+            };
+            replacements.extend(
+                iter.filter(|(range, _)| {
+                    !self.1.whole_word || text.is_whole_word(*range, word_chars)
+                }),
+            );
+        }
+
+        let match_id = form::id_of!("replace.match");
+        for ([start, end], replaced) in replacements {
+            if replacement.is_empty() {
+                text.insert_tag(*REPLACE_TAGGER, start..end, match_id.to_tag(0));
+            } else {
+                text.insert_tag(*REPLACE_TAGGER, start..end, Conceal);
+                let ghost = Ghost(txt!("[replace.new]{replaced}").build());
+                text.insert_tag(*REPLACE_TAGGER, start, ghost);
+            }
+        }
+    }
+}
+
+impl<U: Ui> PromptMode<U> for ReplaceWith<U> {
+    fn update(&mut self, pa: &mut Pass, text: Text, _: &<U as Ui>::Area) -> Text {
+        self.preview(pa, &text.to_string());
+        text
+    }
+
+    fn on_switch(&mut self, pa: &mut Pass, text: Text, _: &<U as Ui>::Area) -> Text {
+        self.preview(pa, "");
+        text
+    }
+
+    fn before_exit(&mut self, pa: &mut Pass, text: Text, _: &<U as Ui>::Area) {
+        let handle = context::fixed_file::<U>(pa).unwrap();
+        handle.text_mut(pa).remove_tags(*REPLACE_TAGGER, ..);
+
+        // Cancelling is the only way to not replace anything.
+        if self.2 {
+            return;
+        }
+
+        let replacement = text.to_string();
+
+        let mut replaced = Ok(0);
+        handle.text_mut(pa).new_moment();
+        handle.edit_all(pa, |mut e| {
+            if let Ok(count) = replaced {
+                replaced = e
+                    .replace_matches_with(&self.0, &replacement, self.1)
+                    .map(|n| count + n);
+            }
+        });
+        handle.text_mut(pa).new_moment();
+
+        match replaced {
+            Ok(1) => context::info!("Replaced [a]1[] match"),
+            Ok(n) => context::info!("Replaced [a]{n}[] matches"),
+            Err(err) => context::error!("{err}"),
+        }
+    }
+
+    fn on_cancel(&mut self, _: &mut Pass) {
+        self.2 = true;
+    }
+
+    fn prompt(&self) -> Text {
+        txt!("[prompt]replace with").build()
     }
 }
 
@@ -554,6 +894,54 @@ impl<U: Ui> PromptMode<U> for PipeSelections<U> {
     }
 }
 
+/// Asks if a [`File`] that was changed on disk should be reloaded
+///
+/// This is opened when a [`File`] with unsaved changes is changed by
+/// another program. Confirming `r` or `reload` discards the unsaved
+/// changes, reloading the [`File`] from disk, while anything else
+/// keeps them. Since the reload goes through the [`History`], it can
+/// be undone.
+///
+/// [`History`]: duat_core::text::History
+#[derive(Clone)]
+pub struct ReloadOrKeep<U: Ui>(Handle<File<U>, U>, String);
+
+impl<U: Ui> ReloadOrKeep<U> {
+    /// Returns a [`Prompt`] with [`ReloadOrKeep`] as its
+    /// [`PromptMode`]
+    pub fn new(pa: &Pass, handle: Handle<File<U>, U>) -> Prompt<U, Self> {
+        let name = handle.read(pa).name();
+        Prompt::new(Self(handle, name))
+    }
+}
+
+impl<U: Ui> PromptMode<U> for ReloadOrKeep<U> {
+    fn update(&mut self, _: &mut Pass, text: Text, _: &<U as Ui>::Area) -> Text {
+        text
+    }
+
+    fn before_exit(&mut self, pa: &mut Pass, text: Text, _: &<U as Ui>::Area) {
+        let name = &self.1;
+        if let "r" | "reload" = text.to_string().trim() {
+            match file::reload_from_disk(pa, &self.0) {
+                Ok(()) => context::info!("Reloaded [a]{name}[] from disk"),
+                Err(err) => context::error!("{err}"),
+            }
+        } else {
+            context::info!("Kept unsaved changes to [a]{name}");
+        }
+    }
+
+    fn keeps_history(&self) -> bool {
+        false
+    }
+
+    fn prompt(&self) -> Text {
+        let name = &self.1;
+        txt!("[a]{name}[prompt] changed on disk, [a]r[prompt]eload or [a]k[prompt]eep").build()
+    }
+}
+
 /// Runs the [`once`] function of widgets.
 ///
 /// [`once`]: Widget::once
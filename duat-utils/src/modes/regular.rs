@@ -3,12 +3,16 @@ use duat_core::{
     file::File,
     mode::{self, KeyCode::*, KeyEvent, KeyMod as Mod, key},
     prelude::Handle,
+    registers,
     ui::Ui,
 };
 
-use super::{IncSearch, RunCommands, SearchFwd, SearchRev};
+use super::{IncSearch, Replace, RunCommands, SearchFwd, SearchRev};
 
 /// The regular, bogstandard mode, a.k.a., supposed to be like VSCode
+///
+/// Movement keys can be given a count, which is typed by holding alt
+/// while pressing the digits.
 #[derive(Clone)]
 pub struct Regular;
 
@@ -16,6 +20,7 @@ impl<U: Ui> mode::Mode<U> for Regular {
     type Widget = File<U>;
 
     fn send_key(&mut self, pa: &mut Pass, key: KeyEvent, handle: Handle<Self::Widget, U>) {
+        let count = mode::count().unwrap_or(1) as i32;
         match key {
             // Characters
             key!(Char(char)) => handle.edit_all(pa, |mut e| {
@@ -48,53 +53,80 @@ impl<U: Ui> mode::Mode<U> for Regular {
             // Movement
             key!(Left) => handle.edit_all(pa, |mut e| {
                 e.unset_anchor();
-                e.move_hor(-1);
+                e.move_hor(-count);
             }),
             key!(Right) => handle.edit_all(pa, |mut e| {
                 e.unset_anchor();
-                e.move_hor(1);
+                e.move_hor(count);
             }),
             key!(Up) => handle.edit_all(pa, |mut e| {
                 e.unset_anchor();
-                e.move_ver(-1);
+                e.move_ver(-count);
             }),
             key!(Down) => handle.edit_all(pa, |mut e| {
                 e.unset_anchor();
-                e.move_ver(1);
+                e.move_ver(count);
             }),
             key!(Left, Mod::SHIFT) => handle.edit_all(pa, |mut e| {
                 e.set_anchor_if_needed();
-                e.move_hor(-1);
+                e.move_hor(-count);
             }),
             key!(Right, Mod::SHIFT) => handle.edit_all(pa, |mut e| {
                 e.set_anchor_if_needed();
-                e.move_hor(1);
+                e.move_hor(count);
             }),
             key!(Up, Mod::SHIFT) => handle.edit_all(pa, |mut e| {
                 e.set_anchor_if_needed();
-                e.move_ver(-1);
+                e.move_ver(-count);
             }),
             key!(Down, Mod::SHIFT) => handle.edit_all(pa, |mut e| {
                 e.set_anchor_if_needed();
-                e.move_ver(1);
+                e.move_ver(count);
             }),
 
             // Copying and pasting
-            key!(Char('c'), Mod::CONTROL) => handle.edit_main(pa, |e| {
-                duat_core::clipboard::set_text(e.selection());
-            }),
-            key!(Char('v'), Mod::CONTROL) => handle.edit_all(pa, |mut e| {
-                if let Some(text) = duat_core::clipboard::get_text() {
-                    e.replace(text);
-                }
-            }),
+            key!(Char('c'), Mod::CONTROL) => {
+                handle.edit_iter(pa, |cursors| cursors.yank_to(registers::DEFAULT))
+            }
+            key!(Char('v'), Mod::CONTROL) => {
+                handle.edit_iter(pa, |cursors| cursors.paste_from(registers::DEFAULT));
+            }
 
             // Control
             key!(Char('p'), Mod::CONTROL) => mode::set::<U>(RunCommands::new()),
             key!(Char('f'), Mod::CONTROL) => mode::set::<U>(IncSearch::new(SearchFwd)),
             key!(Char('F'), Mod::CONTROL) => mode::set::<U>(IncSearch::new(SearchRev)),
+            key!(Char('r'), Mod::CONTROL) => mode::set::<U>(IncSearch::new(Replace)),
 
             _ => {}
         }
     }
+
+    fn count_digit(&self, key: KeyEvent) -> Option<u32> {
+        // Digits are typed normally, so counts are typed with alt.
+        match key {
+            key!(Char(char), Mod::ALT) => char.to_digit(10),
+            _ => None,
+        }
+    }
+
+    fn paste(&mut self, pa: &mut Pass, text: String, handle: Handle<Self::Widget, U>) {
+        if handle.text(pa).is_read_only() {
+            return;
+        }
+
+        let chars = text.chars().count() as i32;
+
+        // Like in most editors, pasting replaces the selection.
+        handle.text_mut(pa).new_moment();
+        handle.edit_all(pa, |mut e| {
+            if e.anchor().is_some() {
+                e.replace("");
+                e.unset_anchor();
+            }
+            e.insert(&text);
+            e.move_hor(chars);
+        });
+        handle.text_mut(pa).new_moment();
+    }
 }
@@ -16,8 +16,11 @@ use duat_core::{
     data::{DataMap, RwData},
     hook::KeysSent,
     prelude::*,
+    text::Severity,
 };
 
+use crate::modes::{SEARCH_MATCHES, SearchMatches};
+
 /// [`StatusLine`] part: The [`File`]'s name, formatted
 ///
 /// Includes wether or not the file is written and wether or not it
@@ -202,11 +205,125 @@ pub fn sels_txt(file: &File<impl Ui>) -> Text {
     }
 }
 
+/// [`StatusLine`] part: The [`FileFormat`] of the [`File`], formatted
+///
+/// # Formatting
+///
+/// ```text
+/// [file.format]{encoding}[separator]:[file.format]{line_ending}
+/// ```
+///
+/// If the [`File`] has a byte order mark, `+bom` is appended to the
+/// `encoding`.
+///
+/// [`StatusLine`]: crate::widgets::StatusLine
+/// [`FileFormat`]: duat_core::file::FileFormat
+pub fn format_txt(file: &File<impl Ui>) -> Text {
+    let format = file.format();
+    let bom = if format.has_bom { "+bom" } else { "" };
+    txt!(
+        "[file.format]{}{bom}[separator]:[file.format]{}",
+        format.encoding,
+        format.line_ending
+    )
+    .build()
+}
+
+/// [`StatusLine`] part: The number of [`Diagnostic`]s of each
+/// [`Severity`], formatted
+///
+/// # Formatting
+///
+/// ```text
+/// [diagnostic.message.error]E{errors} [diagnostic.message.warning]W{warnings}
+/// ```
+///
+/// Followed by `I{infos}` and `H{hints}`, with their respective
+/// forms. Only the [`Severity`]s with at least one [`Diagnostic`]
+/// are shown, so this is empty if there are none.
+///
+/// [`StatusLine`]: crate::widgets::StatusLine
+/// [`Diagnostic`]: duat_core::text::Diagnostic
+pub fn diags_txt(file: &File<impl Ui>) -> Text {
+    use Severity::*;
+    let diags = file.text().diagnostics();
+    let mut builder = Text::builder();
+    let mut space = "";
+
+    for severity in [Error, Warning, Info, Hint] {
+        let count = diags.count(severity);
+        if count == 0 {
+            continue;
+        }
+
+        builder.push(match severity {
+            Error => txt!("{space}[diagnostic.message.error]E{count}"),
+            Warning => txt!("{space}[diagnostic.message.warning]W{count}"),
+            Info => txt!("{space}[diagnostic.message.info]I{count}"),
+            Hint => txt!("{space}[diagnostic.message.hint]H{count}"),
+        });
+        space = " ";
+    }
+
+    builder.build()
+}
+
+/// [`StatusLine`] part: The matches of the current [`IncSearch`]
+///
+/// Is [`None`] if there is no [`IncSearch`] going on.
+///
+/// [`StatusLine`]: crate::widgets::StatusLine
+/// [`IncSearch`]: crate::modes::IncSearch
+pub fn search_matches(pa: &Pass) -> DataMap<Option<SearchMatches>, Option<SearchMatches>> {
+    SEARCH_MATCHES.map(pa, |matches| *matches)
+}
+
+/// [`StatusLine`] part: The matches of the current [`IncSearch`],
+/// formatted
+///
+/// # Formatting
+///
+/// When the main selection is on a match:
+///
+/// ```text
+/// [search.count]{current}[separator]/[search.count]{total}
+/// ```
+///
+/// Otherwise:
+///
+/// ```text
+/// [search.count]-[separator]/[search.count]{total}
+/// ```
+///
+/// A space is added at the end, so it can be placed before other
+/// parts. If there is no [`IncSearch`] going on, the [`Text`] is
+/// empty.
+///
+/// [`StatusLine`]: crate::widgets::StatusLine
+/// [`IncSearch`]: crate::modes::IncSearch
+pub fn search_txt(pa: &Pass) -> DataMap<Option<SearchMatches>, Text> {
+    SEARCH_MATCHES.map(pa, |matches| match matches {
+        Some(SearchMatches { current: Some(current), total }) => {
+            txt!("[search.count]{current}[separator]/[search.count]{total} ").build()
+        }
+        Some(SearchMatches { current: None, total }) => {
+            txt!("[search.count]-[separator]/[search.count]{total} ").build()
+        }
+        None => Text::default(),
+    })
+}
+
 /// [`StatusLine`] part: The [keys] sent to be mapped, formatted
 ///
 /// # Formatting
 ///
-/// For every key, if they are a normal `char`:
+/// If a [count] was typed, it comes first:
+///
+/// ```text
+/// [key.count]{count}
+/// ```
+///
+/// Then, for every key, if they are a normal `char`:
 ///
 /// ```text
 /// [key]{char}
@@ -220,13 +337,17 @@ pub fn sels_txt(file: &File<impl Ui>) -> Text {
 ///
 /// [`StatusLine`]: crate::widgets::StatusLine
 /// [keys]: KeyEvent
+/// [count]: mode::count
 pub fn cur_map_txt(pa: &Pass) -> DataMap<(Vec<KeyEvent>, bool), Text> {
     mode::cur_sequence(pa).map(pa, |(keys, is_alias)| {
-        if is_alias {
-            Text::default()
-        } else {
-            mode::keys_to_text(&keys).build()
+        let mut builder = Text::builder();
+        if let Some(count) = mode::count() {
+            builder.push(txt!("[key.count]{count}"));
+        }
+        if !is_alias {
+            builder.push(mode::keys_to_text(&keys));
         }
+        builder.build()
     })
 }
 
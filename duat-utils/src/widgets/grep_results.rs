@@ -0,0 +1,440 @@
+use std::{
+    fs,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, LazyLock, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use duat_core::{
+    file::FileFormat,
+    prelude::*,
+    ui::{
+        Constraint::{self, Len, Ratio},
+        Side,
+    },
+};
+use ignore::WalkBuilder;
+
+use crate::modes::Grep;
+
+static GREP_TAGGER: LazyLock<Tagger> = LazyLock::new(Tagger::new);
+
+/// A [`Widget`] to display the results of the `grep` command
+///
+/// The search goes through every file in [`context::cur_dir`] that
+/// isn't ignored by a `.gitignore`, and is done on a separate thread,
+/// so the matches show up as they are found.
+///
+/// These results are meant to be browsed with the [`Grep`]
+/// [`Mode`], which lets you jump to the selected match.
+pub struct GrepResults {
+    text: Text,
+    search: Option<Search>,
+    matches: Vec<GrepMatch>,
+    selected: usize,
+    close_on_unfocus: bool,
+}
+
+impl GrepResults {
+    /// Starts a new search, replacing the previous results
+    ///
+    /// If a previous search was still going, it will be stopped.
+    pub fn search(&mut self, pat: impl ToString) -> Result<(), Text> {
+        let pat = pat.to_string();
+        validate(&pat)?;
+
+        let search = Search {
+            pat: pat.clone(),
+            found: Arc::default(),
+            is_done: Arc::default(),
+            is_cancelled: Arc::default(),
+            was_reported: false,
+        };
+
+        std::thread::spawn({
+            let found = search.found.clone();
+            let is_done = search.is_done.clone();
+            let is_cancelled = search.is_cancelled.clone();
+            move || {
+                search_files(&pat, &context::cur_dir(), &found, &is_cancelled);
+                is_done.store(true, Ordering::Relaxed);
+            }
+        });
+
+        self.search = Some(search);
+        self.matches.clear();
+        self.selected = 0;
+        self.text = Text::new();
+        self.text.set_read_only(true);
+
+        Ok(())
+    }
+
+    /// The pattern of the last search, if there was one
+    pub fn pattern(&self) -> Option<&str> {
+        self.search.as_ref().map(|search| search.pat.as_str())
+    }
+
+    /// Wether the last search is still going
+    pub fn is_searching(&self) -> bool {
+        self.search
+            .as_ref()
+            .is_some_and(|search| !search.is_done.load(Ordering::Relaxed))
+    }
+
+    /// The [`GrepMatch`]es that have been found so far
+    pub fn matches(&self) -> &[GrepMatch] {
+        &self.matches
+    }
+
+    /// The currently selected [`GrepMatch`]
+    pub fn selected(&self) -> Option<&GrepMatch> {
+        self.matches.get(self.selected)
+    }
+
+    /// Moves the selection by a number of matches
+    ///
+    /// Returns the start of the selected line, in order to scroll to
+    /// it.
+    pub(crate) fn select_by(&mut self, by: i32) -> Option<Point> {
+        let last = self.matches.len().checked_sub(1)?;
+        self.selected = self.selected.saturating_add_signed(by as isize).min(last);
+        Some(self.highlight_selected())
+    }
+
+    /// Highlights the selected line, returning its start
+    fn highlight_selected(&mut self) -> Point {
+        let [start, end] = self.text.points_of_line(self.selected);
+        self.text.remove_tags(*GREP_TAGGER, ..);
+
+        let id = form::id_of!("grep.selected");
+        self.text.insert_tag(*GREP_TAGGER, start..end, id.to_tag(0));
+
+        start
+    }
+}
+
+impl<U: Ui> Widget<U> for GrepResults {
+    type Cfg = GrepResultsCfg<U>;
+
+    fn cfg() -> Self::Cfg {
+        GrepResultsCfg {
+            close_on_unfocus: true,
+            hidden: true,
+            side: Side::Below,
+            height: Ratio(1, 4),
+            width: Ratio(2, 7),
+            _ghost: PhantomData,
+        }
+    }
+
+    fn update(pa: &mut Pass, handle: &Handle<Self, U>)
+    where
+        Self: Sized,
+    {
+        let gr = handle.write(pa);
+        let Some(search) = gr.search.as_mut() else {
+            return;
+        };
+
+        let new_matches = std::mem::take(&mut *search.found.lock().unwrap());
+        let matches_were_added = !new_matches.is_empty();
+
+        // The Text could be read-only, if it is being paged.
+        let is_read_only = gr.text.is_read_only();
+        gr.text.set_read_only(false);
+        for m in new_matches {
+            gr.text.insert_text(gr.text.len(), m.to_text());
+            gr.matches.push(m);
+        }
+        gr.text.set_read_only(is_read_only);
+
+        if search.is_done.load(Ordering::Relaxed) && !search.was_reported {
+            search.was_reported = true;
+            let pat = &search.pat;
+            match gr.matches.len() {
+                0 => context::warn!("No matches found for [a]{pat}"),
+                1 => context::info!("Found [a]1[] match for [a]{pat}"),
+                n => context::info!("Found [a]{n}[] matches for [a]{pat}"),
+            }
+        }
+
+        if matches_were_added {
+            gr.highlight_selected();
+        }
+    }
+
+    fn needs_update(&self, _: &Pass) -> bool {
+        self.search.as_ref().is_some_and(|search| {
+            !search.found.lock().unwrap().is_empty()
+                || (search.is_done.load(Ordering::Relaxed) && !search.was_reported)
+        })
+    }
+
+    fn text(&self) -> &Text {
+        &self.text
+    }
+
+    fn text_mut(&mut self) -> &mut Text {
+        &mut self.text
+    }
+
+    fn once() -> Result<(), Text> {
+        form::set_weak("grep.path", "file");
+        form::set_weak("grep.coord", "coord");
+        form::set_weak("grep.colon", "separator");
+        form::set_weak("grep.match", "accent");
+        form::set_weak("grep.selected", "selection.main");
+
+        cmd::add!("grep", |pa, pat: cmd::Remainder| {
+            validate(&pat)?;
+            mode::set(Grep::<U>::search(&pat));
+            Ok(Some(txt!("Searching for [a]{pat}").build()))
+        });
+
+        Ok(())
+    }
+
+    fn print_cfg(&self) -> PrintCfg {
+        *PrintCfg::new().set_scrolloff(0, 2)
+    }
+
+    fn on_focus(pa: &mut Pass, handle: &Handle<Self, U>) {
+        handle.area(pa).reveal().unwrap();
+    }
+
+    fn on_unfocus(pa: &mut Pass, handle: &Handle<Self, U>) {
+        if handle.read(pa).close_on_unfocus {
+            handle.area(pa).hide().unwrap()
+        }
+    }
+}
+
+/// A match found by the `grep` command
+#[derive(Debug, Clone)]
+pub struct GrepMatch {
+    path: PathBuf,
+    range: [Point; 2],
+    line_start: Point,
+    line: String,
+}
+
+impl GrepMatch {
+    /// The path of the file, relative to [`context::cur_dir`]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The range of the match in the file
+    pub fn range(&self) -> [Point; 2] {
+        self.range
+    }
+
+    /// The line and column where the match starts
+    ///
+    /// The column is counted in [`char`]s, and these are used to
+    /// find the match if the [`File`] was changed since the search.
+    pub fn coords(&self) -> (usize, usize) {
+        let [start, _] = self.range;
+        (start.line(), start.char() - self.line_start.char())
+    }
+
+    /// The line where the match starts, without the `'\n'`
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// The [`Text`] of this match in the [`GrepResults`]
+    fn to_text(&self) -> Text {
+        let [start, end] = self.range;
+        let m_start = (start.byte() - self.line_start.byte()).min(self.line.len());
+        let m_end = (end.byte() - self.line_start.byte()).min(self.line.len());
+
+        let (before, matched, after) = (
+            &self.line[..m_start],
+            &self.line[m_start..m_end],
+            &self.line[m_end..],
+        );
+
+        let mut builder = txt!(
+            "[grep.path]{}[grep.colon]:[grep.coord]{}[grep.colon]:[grep.coord]{}[grep.colon]:[] ",
+            self.path.to_string_lossy(),
+            start.line() + 1,
+            start.char() - self.line_start.char() + 1
+        );
+        builder.push(txt!("{before}[grep.match]{matched}[]{after}"));
+
+        builder.build()
+    }
+}
+
+/// [`WidgetCfg`] for the [`GrepResults`]
+pub struct GrepResultsCfg<U> {
+    close_on_unfocus: bool,
+    hidden: bool,
+    side: Side,
+    height: Constraint,
+    width: Constraint,
+    _ghost: PhantomData<U>,
+}
+
+impl<U> GrepResultsCfg<U> {
+    /// Have the [`GrepResults`] be open by default
+    pub fn open_by_default(self) -> Self {
+        Self { hidden: false, ..self }
+    }
+
+    /// Keeps the [`GrepResults`] open when unfocused, as opposed to
+    /// hiding it
+    pub fn keep_open_on_unfocus(self) -> Self {
+        Self { close_on_unfocus: false, ..self }
+    }
+
+    /// Pushes the [`GrepResults`] to the right, as opposed to below
+    pub fn on_the_right(self) -> Self {
+        Self { side: Side::Right, ..self }
+    }
+
+    /// Pushes the [`GrepResults`] to the left, as opposed to below
+    pub fn on_the_left(self) -> Self {
+        Self { side: Side::Left, ..self }
+    }
+
+    /// Pushes the [`GrepResults`] above, as opposed to below
+    pub fn above(self) -> Self {
+        Self { side: Side::Above, ..self }
+    }
+
+    /// Sets the height of the [`GrepResults`]
+    ///
+    /// This is ignored if pushing to the left or to the right.
+    pub fn height(self, height: usize) -> Self {
+        Self { height: Len(height as f32), ..self }
+    }
+
+    /// Sets the width of the [`GrepResults`]
+    ///
+    /// This is ignored if pushing above or below.
+    pub fn width(self, width: usize) -> Self {
+        Self { width: Len(width as f32), ..self }
+    }
+
+    /// Sets a ratio for the height of the [`GrepResults`]
+    ///
+    /// This is ignored if pushing to the left or to the right.
+    pub fn height_ratio(self, den: u16, div: u16) -> Self {
+        Self { height: Ratio(den, div), ..self }
+    }
+
+    /// Sets a ratio for the witdh of the [`GrepResults`]
+    ///
+    /// This is ignored if pushing above or below.
+    pub fn width_ratio(self, den: u16, div: u16) -> Self {
+        Self { width: Ratio(den, div), ..self }
+    }
+}
+
+impl<U: Ui> WidgetCfg<U> for GrepResultsCfg<U> {
+    type Widget = GrepResults;
+
+    fn build(self, _: &mut Pass, _: BuildInfo<U>) -> (Self::Widget, PushSpecs) {
+        let mut text = Text::new();
+        text.set_read_only(true);
+
+        let gr = GrepResults {
+            text,
+            search: None,
+            matches: Vec::new(),
+            selected: 0,
+            close_on_unfocus: self.close_on_unfocus,
+        };
+
+        let specs = match self.side {
+            Side::Right => PushSpecs::right().constrain_hor(self.width),
+            Side::Left => PushSpecs::left().constrain_hor(self.width),
+            Side::Above => PushSpecs::above().constrain_ver(self.height),
+            Side::Below => PushSpecs::below().constrain_ver(self.height),
+        };
+
+        (gr, if self.hidden { specs.hidden() } else { specs })
+    }
+}
+
+/// A search being done in a separate thread
+struct Search {
+    pat: String,
+    found: Arc<Mutex<Vec<GrepMatch>>>,
+    is_done: Arc<AtomicBool>,
+    is_cancelled: Arc<AtomicBool>,
+    was_reported: bool,
+}
+
+impl Drop for Search {
+    fn drop(&mut self) {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Searches through every file in a directory
+///
+/// Files that are ignored by a `.gitignore`, hidden or binary are
+/// skipped. The others are decoded just like when opening a [`File`],
+/// so the matches' [`Point`]s are the same as in its [`Text`].
+fn search_files(pat: &str, dir: &Path, found: &Mutex<Vec<GrepMatch>>, is_cancelled: &AtomicBool) {
+    for entry in WalkBuilder::new(dir).require_git(false).build() {
+        // The config crate can't be unloaded while this thread runs.
+        if is_cancelled.load(Ordering::Relaxed) || context::will_reload_or_quit() {
+            return;
+        }
+
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+
+        let Some(string) = fs::read(entry.path())
+            .ok()
+            .map(|raw| FileFormat::decode(&raw).1)
+            .filter(|string| !string.contains('\0'))
+        else {
+            continue;
+        };
+
+        let path = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        let text = Text::from(string);
+        let Ok(iter) = text.search_fwd(pat, ..) else {
+            return;
+        };
+
+        let matches: Vec<GrepMatch> = iter
+            .map(|[start, end]| {
+                let [line_start, line_end] = text.points_of_line(start.line());
+                let line = text.strs(line_start..line_end).unwrap().to_string();
+
+                GrepMatch {
+                    path: path.to_path_buf(),
+                    range: [start, end],
+                    line_start,
+                    line: line.trim_end_matches(['\n', '\r']).to_string(),
+                }
+            })
+            .collect();
+
+        if !matches.is_empty() {
+            found.lock().unwrap().extend(matches);
+        }
+    }
+}
+
+/// Checks if a pattern is a valid regex
+fn validate(pat: &str) -> Result<(), Text> {
+    match Text::new().search_fwd(pat, ..) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(txt!("[a]{pat}[] is not a valid regex").build()),
+    }
+}
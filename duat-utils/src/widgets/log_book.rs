@@ -64,9 +64,12 @@ impl<U: Ui> Widget<U> for LogBook {
         let records_were_added = !new_records.is_empty();
         lb.len_of_taken += new_records.len();
 
+        // The Text is read-only, so that it can't be edited by the user.
+        lb.text.set_read_only(false);
         for rec_text in new_records.into_iter().filter_map(&mut lb.format_rec) {
             lb.text.insert_text(lb.text.len(), rec_text);
         }
+        lb.text.set_read_only(true);
 
         if records_were_added {
             area.scroll_to_points(&lb.text, lb.text.len(), Widget::<U>::print_cfg(lb));
@@ -208,6 +211,7 @@ impl<U: Ui> WidgetCfg<U> for LogBookCfg<U> {
         for rec_text in records.into_iter().filter_map(&mut self.format_rec) {
             text.insert_text(text.len(), rec_text);
         }
+        text.set_read_only(true);
 
         let lb = LogBook {
             logs,
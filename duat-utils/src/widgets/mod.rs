@@ -19,6 +19,7 @@ use duat_core::{
 };
 
 pub use self::{
+    grep_results::{GrepMatch, GrepResults, GrepResultsCfg},
     line_numbers::{LineNumbers, LineNumbersCfg},
     log_book::{LogBook, LogBookCfg},
     notifications::{Notifications, NotificationsCfg},
@@ -26,6 +27,7 @@ pub use self::{
     status_line::{State, StatusLine, StatusLineCfg, status},
 };
 
+mod grep_results;
 mod line_numbers;
 mod log_book;
 mod notifications;
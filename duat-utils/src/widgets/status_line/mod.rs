@@ -18,7 +18,7 @@ mod state;
 use duat_core::{context::DynFile, prelude::*, text::Builder, ui::Side};
 
 pub use self::{macros::status, state::State};
-use crate::state::{name_txt, main_txt, mode_txt, sels_txt};
+use crate::state::{name_txt, main_txt, mode_txt, search_txt, sels_txt};
 
 /// A widget to show information, usually about a [`File`]
 ///
@@ -135,6 +135,7 @@ impl<U: Ui> Widget<U> for StatusLine<U> {
         form::set_weak("coord", Form::dark_yellow());
         form::set_weak("separator", Form::cyan());
         form::set_weak("mode", Form::green());
+        form::set_weak("search.count", "coord");
         Ok(())
     }
 }
@@ -200,13 +201,14 @@ impl<U: Ui> WidgetCfg<U> for StatusLineCfg<U> {
             (builder, checker)
         } else {
             let mode_txt = mode_txt(pa);
+            let search_txt = search_txt(pa);
             let cfg = match self.specs.side() {
-                Side::Above | Side::Below => {
-                    macros::status!("{mode_txt}{Spacer}{name_txt} {sels_txt} {main_txt}")
-                }
-                Side::Right => {
-                    macros::status!("{AlignRight}{name_txt} {mode_txt} {sels_txt} {main_txt}",)
-                }
+                Side::Above | Side::Below => macros::status!(
+                    "{mode_txt}{Spacer}{name_txt} {search_txt}{sels_txt} {main_txt}"
+                ),
+                Side::Right => macros::status!(
+                    "{AlignRight}{name_txt} {mode_txt} {search_txt}{sels_txt} {main_txt}",
+                ),
                 Side::Left => unreachable!(),
             };
 
@@ -539,6 +539,9 @@ pub mod hook {
     //!   [`Notifications`], via [`FocusedOn`] and [`UnfocusedFrom`].
     //! - `"ReloadOnWrite"`: Reloads the `config` crate whenever any
     //!   file in it is written to, via [`FileWritten`].
+    //! - `"PromptReloadOnDiskChange"`: Asks if a [`File`] with unsaved
+    //!   changes should be reloaded when it is changed on disk, via
+    //!   [`FileChangedOnDisk`].
     //!
     //! # Available hooks
     //!
@@ -557,13 +560,23 @@ pub mod hook {
     //! - [`FocusedOn`] lets you act on a [`Widget`] when focused.
     //! - [`UnfocusedFrom`] lets you act on a [`Widget`] when
     //!   unfocused.
+    //! - [`WidgetUpdated`] lets you act on a [`Widget`] right before
+    //!   it is printed.
     //! - [`KeysSent`] lets you act on a [`dyn Widget`], given a[key].
     //! - [`KeysSentTo`] lets you act on a given [`Widget`], given a
     //!   [key].
+    //! - [`Pasted`] triggers after text is pasted.
+    //! - [`MouseEventSent`] lets you act on a [mouse event].
+    //! - [`MouseEventSentTo`] lets you act on the [`Widget`] under
+    //!   the pointer, given a [mouse event].
     //! - [`FormSet`] triggers whenever a [`Form`] is added/altered.
     //! - [`ModeSwitched`] triggers when you change [`Mode`].
     //! - [`ModeCreated`] lets you act on a [`Mode`] after switching.
     //! - [`FileWritten`] triggers after the [`File`] is written.
+    //! - [`FileChangedOnDisk`] triggers when a [`File`] is changed
+    //!   by another program.
+    //! - [`FileRecovered`] triggers after recovering unsaved changes
+    //!   from a crashed session.
     //! - [`SearchPerformed`] (from duat-utils) triggers after a
     //!   search is performed.
     //! - [`SearchUpdated`] (from duat-utils) triggers after a search
@@ -588,6 +601,7 @@ pub mod hook {
     //! [`Widget`]: crate::prelude::Widget
     //! [`Form`]: crate::prelude::Form
     //! [key]: crate::mode::KeyEvent
+    //! [mouse event]: crate::mode::MouseEvent
     //! [deadlocks]: https://en.wikipedia.org/wiki/Deadlock_(computer_science)
     //! [`Mode`]: crate::mode::Mode
     //! [`&mut Widget`]: crate::prelude::Widget
@@ -798,6 +812,9 @@ pub mod hook {
     ///   not some things will be reloaded on the next opening of
     ///   Duat.
     ///
+    /// This also triggers before reloading the [`File`] from disk,
+    /// which happens when it is changed by another program.
+    ///
     /// This will not trigger upon closing Duat. For that, see
     /// [`OnFileClose`].
     ///
@@ -806,6 +823,38 @@ pub mod hook {
     /// [`Cache`]: duat_core::context::Cache
     pub type OnFileReload = duat_core::hook::OnFileReload<Ui>;
 
+    /// [`Hookable`]: Triggers when a [`File`] is changed on disk
+    ///
+    /// # Arguments
+    ///
+    /// - The [`File`]'s [`Handle`].
+    ///
+    /// After this hook is triggered, if the [`File`] still differs
+    /// from its contents on disk, it will be reloaded if it has no
+    /// unsaved changes. Otherwise, by default, the
+    /// `"PromptReloadOnDiskChange"` group opens a [`ReloadOrKeep`]
+    /// prompt, asking if the changes should be discarded.
+    ///
+    /// [`ReloadOrKeep`]: crate::mode::ReloadOrKeep
+    /// [`File`]: crate::prelude::File
+    /// [`Handle`]: crate::prelude::Handle
+    pub type FileChangedOnDisk = duat_core::hook::FileChangedOnDisk<Ui>;
+
+    /// [`Hookable`]: Triggers after recovering a [`File`]'s unsaved
+    /// changes
+    ///
+    /// # Arguments
+    ///
+    /// - The [`File`]'s [`Handle`].
+    ///
+    /// If Duat crashes, the unsaved changes of every [`File`] are
+    /// kept, and can be recovered with the `recover` command on the
+    /// next opening of said [`File`].
+    ///
+    /// [`File`]: crate::prelude::File
+    /// [`Handle`]: crate::prelude::Handle
+    pub type FileRecovered = duat_core::hook::FileRecovered<Ui>;
+
     /// [`Hookable`]: Triggers when the [`Widget`] is focused
     ///
     /// # Arguments
@@ -826,6 +875,17 @@ pub mod hook {
     /// [`Widget`]: crate::prelude::Widget
     pub type UnfocusedFrom<W> = duat_core::hook::UnfocusedFrom<W, Ui>;
 
+    /// [`Hookable`]: Triggers after a [`Widget`] is updated, right
+    /// before it is printed
+    ///
+    /// # Arguments
+    ///
+    /// - The [`Handle`] for the [`Widget`]
+    ///
+    /// [`Handle`]: crate::prelude::Handle
+    /// [`Widget`]: crate::prelude::Widget
+    pub type WidgetUpdated<W> = duat_core::hook::WidgetUpdated<W, Ui>;
+
     /// [`Hookable`]: Triggers whenever a [key] is sent to the [`Widget`]
     ///
     /// # Arguments
@@ -838,6 +898,19 @@ pub mod hook {
     /// [`Widget`]: crate::prelude::Widget
     pub type KeySentTo<W> = duat_core::hook::KeysSentTo<W, Ui>;
 
+    /// [`Hookable`]: Triggers whenever a [mouse event] is sent to the
+    /// [`Widget`]
+    ///
+    /// # Arguments
+    ///
+    /// - The [mouse event].
+    /// - The [`Handle`] for its [`Widget`].
+    ///
+    /// [mouse event]: crate::mode::MouseEvent
+    /// [`Handle`]: crate::prelude::Handle
+    /// [`Widget`]: crate::prelude::Widget
+    pub type MouseEventSentTo<W> = duat_core::hook::MouseEventSentTo<W, Ui>;
+
     /// [`Hookable`]: Lets you modify a [`Mode`] as it is set
     ///
     /// # Arguments
@@ -1012,9 +1085,10 @@ pub mod prelude {
         data::{self, Pass},
         file,
         prelude::Lender,
+        registers,
         text::{
-            self, AlignCenter, AlignLeft, AlignRight, Builder, Conceal, Ghost, Spacer, Tagger,
-            Text, txt,
+            self, AlignCenter, AlignLeft, AlignRight, Builder, Button, Conceal, Ghost, Spacer,
+            Tagger, Text, txt,
         },
         ui::{self, Area as AreaTrait, Widget, WidgetCfg},
     };
@@ -1028,8 +1102,8 @@ pub mod prelude {
         hook::{
             self, ColorSchemeSet, ConfigLoaded, ConfigUnloaded, ExitedDuat, FileWritten, FocusedOn,
             FocusedOnDuat, FormSet, KeysSent, KeysSentTo, ModeCreated, ModeSwitched,
-            SearchPerformed, SearchUpdated, UnfocusedFrom, UnfocusedFromDuat, WidgetCreated,
-            WindowCreated,
+            MouseEventSent, MouseEventSentTo, Pasted, SearchPerformed, SearchUpdated,
+            UnfocusedFrom, UnfocusedFromDuat, WidgetCreated, WindowCreated,
         },
         mode::{self, Mode, Pager, Prompt, User, alias, map},
         print, setup_duat,
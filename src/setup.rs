@@ -20,19 +20,18 @@ use duat_core::{
     context::{self, CurFile, CurWidget, Logs},
     form::Palette,
     session::{FileRet, SessionCfg},
-    text::History,
     ui::{self, Area, DuatEvent, Widget},
 };
 use duat_filetype::FileType;
 use duat_term::VertRule;
 use duat_utils::{
-    modes::{Pager, Regular},
-    widgets::{FooterWidgets, LogBook},
+    modes::{Grep, Pager, Regular, ReloadOrKeep},
+    widgets::{FooterWidgets, GrepResults, LogBook},
 };
 
 use crate::{
     CfgFn, Ui, form,
-    hook::{self, OnFileClose, OnFileReload, WindowCreated},
+    hook::{self, FileChangedOnDisk, OnFileClose, OnFileReload, WindowCreated},
     mode,
     prelude::{FileWritten, LineNumbers},
     widgets::File,
@@ -67,6 +66,7 @@ pub fn pre_setup(initials: Option<Initials>, duat_tx: &'static Sender<DuatEvent>
 
     mode::set_default(Regular);
     mode::set_default(Pager::<LogBook, Ui>::new());
+    mode::set_default(Grep::<Ui>::new());
 
     hook::add_grouped::<File>("FileWidgets", |_, (cfg, builder)| {
         builder.push(VertRule::cfg());
@@ -78,6 +78,10 @@ pub fn pre_setup(initials: Option<Initials>, duat_tx: &'static Sender<DuatEvent>
         builder.push(FooterWidgets::default());
     });
 
+    hook::add_grouped::<WindowCreated>("GrepResults", |_, builder| {
+        builder.push(GrepResults::cfg());
+    });
+
     hook::add_grouped::<FileWritten>("ReloadOnWrite", |_, (path, _, is_quitting)| {
         let path = Path::new(path);
         if !is_quitting
@@ -88,18 +92,31 @@ pub fn pre_setup(initials: Option<Initials>, duat_tx: &'static Sender<DuatEvent>
         }
     });
 
+    hook::add_grouped::<FileChangedOnDisk>("PromptReloadOnDiskChange", |pa, handle| {
+        if handle.read(pa).text().has_unsaved_changes() {
+            mode::set(ReloadOrKeep::new(pa, handle.clone()));
+        }
+    });
+
     hook::add_grouped::<OnFileReload>("SaveCacheOnReload", |pa, (handle, cache)| {
         let (file, area) = handle.write_with_area(pa);
 
         let path = file.path();
-        file.text_mut().new_moment();
 
-        if let Some(history) = file.text().history()
-            && let Err(err) = cache.store(&path, history.clone())
+        if let Some(history) = file.text_mut().cacheable_history()
+            && let Err(err) = cache.store(&path, history)
         {
             context::error!("{err}");
         }
 
+        if let Err(err) = cache.store(&path, file.text().cacheable_marks()) {
+            context::error!("{err}");
+        }
+
+        if let Err(err) = cache.store(&path, file.text().cacheable_folds()) {
+            context::error!("{err}");
+        }
+
         if let Some(area_cache) = area.cache()
             && let Err(err) = cache.store(&path, area_cache)
         {
@@ -113,11 +130,10 @@ pub fn pre_setup(initials: Option<Initials>, duat_tx: &'static Sender<DuatEvent>
         }
     });
 
-    hook::add_grouped::<OnFileClose>("DeleteHistoryOnClose", |pa, (handle, cache)| {
+    hook::add_grouped::<OnFileClose>("DeleteCacheOnClose", |pa, (handle, cache)| {
         let file = handle.write(pa);
 
         let path = file.path();
-        cache.delete_for::<History>(&path);
         if !file.exists() || file.text().has_unsaved_changes() {
             cache.delete(path);
         }
@@ -127,13 +143,32 @@ pub fn pre_setup(initials: Option<Initials>, duat_tx: &'static Sender<DuatEvent>
         let (file, area) = handle.write_with_area(pa);
 
         let path = file.path();
-        file.text_mut().new_moment();
 
         if let Some("gitcommit") = path.filetype() {
             cache.delete(path);
             return;
         }
 
+        // The History is only valid for the contents on disk.
+        if file.exists()
+            && !file.text().has_unsaved_changes()
+            && let Some(history) = file.text_mut().cacheable_history()
+            && let Err(err) = cache.store(&path, history)
+        {
+            context::error!("{err}");
+        }
+
+        // So are the Marks and Folds.
+        if file.exists() && !file.text().has_unsaved_changes() {
+            if let Err(err) = cache.store(&path, file.text().cacheable_marks()) {
+                context::error!("{err}");
+            }
+
+            if let Err(err) = cache.store(&path, file.text().cacheable_folds()) {
+                context::error!("{err}");
+            }
+        }
+
         if let Some(area_cache) = area.cache()
             && let Err(err) = cache.store(&path, area_cache)
         {